@@ -2,6 +2,6 @@
 from shutil import copyfile
 from subprocess import call
 
-#call('cargo build --target wasm32-unknown-unknown --release')
+#call(['cargo', 'build', '--target', 'wasm32-unknown-unknown', '--release', '--features', 'wasm'])
 copyfile('target/wasm32-unknown-unknown/release/varidor.wasm', 'html/varidor.wasm')
 call(['wasm-gc', 'html/varidor.wasm', 'html/varidor-gc.wasm'])
@@ -0,0 +1,336 @@
+//! Game tree search: alpha-beta over the moves of a board, with exact scores for races without
+//! walls and for positions of a tablebase.
+use board_square::*;
+use book::*;
+use evaluation::*;
+use record::*;
+use std::time::Instant;
+use tablebase::*;
+
+/// Score of a won position, reduced by the plies until the win so that faster wins score higher.
+pub const WIN_SCORE: i32 = 1_000_000;
+
+/// Settings of `search_with`.
+#[derive(Clone, Copy, Debug)]
+pub struct SearchSettings<'a, EvaluatorType = LinearEvaluator> {
+    /// plies to search ahead, at least one ply is searched
+    pub depth: usize,
+    /// exact outcomes of the positions it contains
+    pub tablebase: Option<&'a Tablebase>,
+    /// moves to choose from in the positions it contains, see `choose_move`
+    pub book: Option<&'a OpeningBook>,
+    /// scores the positions at the end of the search which are not decided
+    pub evaluator: EvaluatorType,
+    /// time after which the search stops, see `SearchResult::stopped`
+    pub deadline: Option<Instant>,
+}
+
+impl<'a> Default for SearchSettings<'a> {
+    fn default() -> Self {
+        SearchSettings {
+            depth: 0,
+            tablebase: None,
+            book: None,
+            evaluator: LinearEvaluator::default(),
+            deadline: None,
+        }
+    }
+}
+
+/// Result of `search`, the score is from the point of view of the player to move.
+#[derive(Clone, PartialEq, Debug)]
+pub struct SearchResult<MoveType> {
+    /// `None` if the game is already over
+    pub best_move: Option<MoveType>,
+    pub score: i32,
+    /// number of positions visited
+    pub nodes: u64,
+    /// the best move and the expected replies, as far as the search looked
+    pub principal_variation: Vec<MoveType>,
+    /// the deadline passed before all moves were searched, the best move is the best of the
+    /// moves searched until then
+    pub stopped: bool,
+}
+
+/// Longest game `play_game` plays before stopping without a winner.
+pub const MAX_GAME_PLIES: usize = 1000;
+
+/// Small pseudo random number generator (xorshift64*), the same seed gives the same numbers.
+#[derive(Clone, Debug)]
+pub struct Random {
+    state: u64,
+}
+
+impl Random {
+    pub fn new(seed: u64) -> Self {
+        Random {
+            state: seed.wrapping_mul(0x9e37_79b9_7f4a_7c15) | 1,
+        }
+    }
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+    /// A number below `bound`, which has to be positive.
+    pub fn below(&mut self, bound: u64) -> u64 {
+        self.next_u64() % bound
+    }
+}
+
+/// The move to play: drawn from the opening book if it knows the position, else searched.
+pub fn choose_move<Board, E>(
+    board: &Board,
+    player: TwoPlayerIndices,
+    settings: &SearchSettings<E>,
+    random: &mut Random,
+) -> Option<Move>
+where
+    Board: TablebaseBoard + Clone,
+    E: Evaluator<Board>,
+{
+    settings
+        .book
+        .and_then(|book| book.choose_move(board, player, random))
+        .or_else(|| search_with(board, player, settings).best_move)
+}
+
+/// Plays a game of `choose_move` against itself, the first `random_plies` plies are drawn
+/// uniformly from the legal moves to vary the games.
+pub fn self_play<Board, E>(
+    rules: Rules,
+    settings: &SearchSettings<E>,
+    random_plies: usize,
+    random: &mut Random,
+) -> GameRecord
+where
+    Board: TablebaseBoard + Clone,
+    E: Evaluator<Board>,
+{
+    play_game::<Board, E>(rules, [settings, settings], random_plies, random)
+}
+
+/// Plays a game of `choose_move` with the settings in the order White, Black, the first
+/// `random_plies` plies are drawn uniformly from the legal moves to vary the games.
+pub fn play_game<Board, E>(
+    rules: Rules,
+    settings: [&SearchSettings<E>; 2],
+    random_plies: usize,
+    random: &mut Random,
+) -> GameRecord
+where
+    Board: TablebaseBoard + Clone,
+    E: Evaluator<Board>,
+{
+    let mut board = Board::with_rules(rules);
+    let mut record = GameRecord::new(Board::AVERAGE_BOARD_SIZE, rules);
+    while board.get_winner().is_none() && record.moves.len() < MAX_GAME_PLIES {
+        let player = GameRecord::get_player(record.moves.len());
+        let next_move = if record.moves.len() < random_plies {
+            let moves = board.get_legal_moves(player);
+            moves[random.below(moves.len() as u64) as usize]
+        } else {
+            let player_settings = settings[record.moves.len() & 1];
+            match choose_move(&board, player, player_settings, random) {
+                Some(next_move) => next_move,
+                None => break,
+            }
+        };
+        board
+            .play_move(player, next_move)
+            .expect("engine chose an illegal move");
+        record.moves.push(next_move);
+    }
+    record.winner = board.get_winner();
+    record
+}
+
+/// Searches `depth` plies ahead for the best move of `player`, at least one ply is searched.
+///
+/// Positions are scored by the default `LinearEvaluator`, once no walls are left the outcome is
+/// known exactly from `BoardTrait::solve_race`.
+pub fn search<Board>(
+    board: &Board,
+    player: TwoPlayerIndices,
+    depth: usize,
+) -> SearchResult<BoardMove<Board>>
+where
+    Board: TablebaseBoard + Clone,
+{
+    search_with(
+        board,
+        player,
+        &SearchSettings {
+            depth,
+            ..SearchSettings::default()
+        },
+    )
+}
+
+/// `search` with all settings, positions found in the tablebase are scored exactly.
+///
+/// Past the deadline the search stops, a search of one ply is always finished.
+pub fn search_with<Board, E>(
+    board: &Board,
+    player: TwoPlayerIndices,
+    settings: &SearchSettings<E>,
+) -> SearchResult<BoardMove<Board>>
+where
+    Board: TablebaseBoard + Clone,
+    E: Evaluator<Board>,
+{
+    let mut board = board.clone();
+    let mut nodes = 1;
+    if board.get_winner().is_some() {
+        return SearchResult {
+            best_move: None,
+            score: get_score(&board, player, 0, settings),
+            nodes,
+            principal_variation: vec![],
+            stopped: false,
+        };
+    }
+    let mut best_move = None;
+    let mut alpha = -WIN_SCORE;
+    let mut principal_variation = vec![];
+    let mut replies = vec![];
+    let mut stopped = false;
+    for next_move in board.get_candidate_moves(player) {
+        if let Ok(token) = board.make_move(player, next_move) {
+            let score = -negamax(
+                &mut board,
+                player.next_player(),
+                (settings.depth.saturating_sub(1), 1),
+                (-WIN_SCORE, -alpha),
+                settings,
+                (&mut nodes, &mut stopped, &mut replies),
+            );
+            board.unmake_move(token);
+            // the score of an unfinished search is not known
+            if stopped {
+                break;
+            }
+            if best_move.is_none() || score > alpha {
+                alpha = score;
+                best_move = Some(next_move);
+                principal_variation = vec![next_move];
+                principal_variation.append(&mut replies);
+            }
+        }
+    }
+    SearchResult {
+        best_move,
+        score: alpha,
+        nodes,
+        principal_variation,
+        stopped,
+    }
+}
+
+// exact score of a finished game, a race or a tablebase position, `ply` is the distance to the
+// root of the search
+fn get_decided_score<Board, E>(
+    board: &Board,
+    player: TwoPlayerIndices,
+    ply: usize,
+    settings: &SearchSettings<E>,
+) -> Option<i32>
+where
+    Board: TablebaseBoard,
+    E: Evaluator<Board>,
+{
+    let result = match board.solve_race(player) {
+        Some(result) if result.winner == player => TablebaseResult::Win {
+            plies: result.plies,
+        },
+        Some(result) => TablebaseResult::Loss {
+            plies: result.plies,
+        },
+        None => settings
+            .tablebase
+            .and_then(|tablebase| tablebase.probe(board, player))?,
+    };
+    Some(match result {
+        TablebaseResult::Win { plies } => WIN_SCORE - (ply + plies) as i32,
+        TablebaseResult::Loss { plies } => -(WIN_SCORE - (ply + plies) as i32),
+        TablebaseResult::Draw => 0,
+    })
+}
+
+fn get_score<Board, E>(
+    board: &Board,
+    player: TwoPlayerIndices,
+    ply: usize,
+    settings: &SearchSettings<E>,
+) -> i32
+where
+    Board: TablebaseBoard,
+    E: Evaluator<Board>,
+{
+    match get_decided_score(board, player, ply, settings) {
+        Some(score) => score,
+        None => settings.evaluator.evaluate(board, player),
+    }
+}
+
+// walks the tree with make_move and unmake_move, the board is unchanged afterwards; the moves
+// of the best line found within the window are left in `line`; once the deadline has passed
+// `stopped` is set and the scores are meaningless
+fn negamax<Board, E>(
+    board: &mut Board,
+    player: TwoPlayerIndices,
+    (depth, ply): (usize, usize),
+    (mut alpha, beta): (i32, i32),
+    settings: &SearchSettings<E>,
+    (nodes, stopped, line): (&mut u64, &mut bool, &mut Vec<BoardMove<Board>>),
+) -> i32
+where
+    Board: TablebaseBoard,
+    E: Evaluator<Board>,
+{
+    *nodes += 1;
+    line.clear();
+    if depth == 0 {
+        return get_score(board, player, ply, settings);
+    }
+    if *stopped || settings.deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+        *stopped = true;
+        return 0;
+    }
+    if let Some(score) = get_decided_score(board, player, ply, settings) {
+        return score;
+    }
+    let mut best_score = None;
+    let mut replies = vec![];
+    for next_move in board.get_candidate_moves(player) {
+        if let Ok(token) = board.make_move(player, next_move) {
+            let score = -negamax(
+                board,
+                player.next_player(),
+                (depth - 1, ply + 1),
+                (-beta, -alpha),
+                settings,
+                (nodes, stopped, &mut replies),
+            );
+            board.unmake_move(token);
+            if *stopped {
+                return 0;
+            }
+            match best_score {
+                Some(best) if best >= score => {}
+                _ => best_score = Some(score),
+            }
+            if score > alpha {
+                alpha = score;
+                line.clear();
+                line.push(next_move);
+                line.append(&mut replies);
+                if alpha >= beta {
+                    break;
+                }
+            }
+        }
+    }
+    best_score.unwrap_or_else(|| get_score(board, player, ply, settings))
+}
@@ -0,0 +1,291 @@
+//! Analysis board: a tree of moves for studying games, in which playing another move than the
+//! one played before adds a variation instead of replacing the line.
+//!
+//! The tree is written as a `GameRecord` with the main line as its moves and the other
+//! branches as variations of the record, see `record`.
+use board_square::*;
+use record::*;
+use std::collections::BTreeMap;
+
+// a position of the tree and the move which led to it
+#[derive(Clone)]
+struct Node<Board> {
+    board: Board,
+    player: TwoPlayerIndices,
+    next_move: Option<Move>,
+    comment: Option<String>,
+    parent: Option<usize>,
+    // the first one continues the main line
+    children: Vec<usize>,
+}
+
+/// A tree of positions with a current one, starting from the start position of the rules or
+/// of the record it was read from.
+///
+/// Every position has its continuations, the first one being the main line; moves removed by
+/// `delete_variation` are no longer reachable.
+#[derive(Clone)]
+pub struct AnalysisBoard<Board> {
+    /// the tags of the record written by `to_record`
+    pub tags: Vec<(String, String)>,
+    /// the result written by `to_record`
+    pub winner: Option<TwoPlayerIndices>,
+    rules: Rules,
+    start_position: Option<Position>,
+    nodes: Vec<Node<Board>>,
+    current: usize,
+}
+
+impl<Board> AnalysisBoard<Board>
+where
+    Board: PositionBoard + Clone,
+{
+    pub fn new(rules: Rules) -> Self {
+        AnalysisBoard {
+            tags: vec![],
+            winner: None,
+            rules,
+            start_position: None,
+            nodes: vec![Node {
+                board: Board::with_rules(rules),
+                player: TwoPlayerIndices::White,
+                next_move: None,
+                comment: None,
+                parent: None,
+                children: vec![],
+            }],
+            current: 0,
+        }
+    }
+    /// The tree of the record with its variations and comments, positioned at the start.
+    ///
+    /// Variations of the first move of a variation replace the same move as it, so `to_record`
+    /// writes them next to it.
+    pub fn from_record(record: &GameRecord) -> Result<Self, RecordError> {
+        if Board::AVERAGE_BOARD_SIZE != record.board_size {
+            return Err(RecordError::BoardSize {
+                expected: Board::AVERAGE_BOARD_SIZE,
+                found: record.board_size,
+            });
+        }
+        let mut analysis = Self::new(record.rules);
+        if let Some(ref position) = record.start_position {
+            analysis.nodes[0].board =
+                Board::from_position(position, record.rules).ok_or(RecordError::Position)?;
+            analysis.nodes[0].player = position.player;
+            analysis.start_position = Some(position.clone());
+        }
+        analysis.tags = record.tags.clone();
+        analysis.winner = record.winner;
+        analysis.add_variation(&record.moves, &record.annotations)?;
+        analysis.current = 0;
+        Ok(analysis)
+    }
+    // plays the moves from the current position, then the variations of every move from the
+    // position before it, and returns to the current position
+    fn add_variation(
+        &mut self,
+        moves: &[Move],
+        annotations: &BTreeMap<usize, Annotation>,
+    ) -> Result<(), RecordError> {
+        let start = self.current;
+        for (index, &next_move) in moves.iter().enumerate() {
+            let before = self.current;
+            self.play_move(next_move)
+                .map_err(|error| RecordError::IllegalMove {
+                    ply: self.get_ply() + 1,
+                    error,
+                })?;
+            if let Some(annotation) = annotations.get(&index) {
+                self.nodes[self.current].comment = annotation.comment.clone();
+                let after = self.current;
+                for variation in &annotation.variations {
+                    self.current = before;
+                    self.add_variation(&variation.moves, &variation.annotations)?;
+                }
+                self.current = after;
+            }
+        }
+        self.current = start;
+        Ok(())
+    }
+    /// The main line with its variations and comments as a record.
+    pub fn to_record(&self) -> GameRecord {
+        let mut record = GameRecord::new(Board::AVERAGE_BOARD_SIZE, self.rules);
+        record.start_position = self.start_position.clone();
+        record.tags = self.tags.clone();
+        record.winner = self.winner;
+        let variation = self.get_variation(self.nodes[0].children.first().cloned(), true);
+        record.moves = variation.moves;
+        record.annotations = variation.annotations;
+        record
+    }
+    // the line starting with the node and following the main line, the siblings of every node
+    // of it being its variations; the siblings of the first node are left out for a variation,
+    // which is one of them
+    fn get_variation(&self, first: Option<usize>, is_main_line: bool) -> Variation {
+        let mut variation = Variation::default();
+        let mut node = first;
+        while let Some(index) = node {
+            let parent = self.nodes[index].parent.expect("moves have a parent");
+            let siblings = if is_main_line || node != first {
+                &self.nodes[parent].children[..]
+            } else {
+                &[]
+            };
+            let annotation = Annotation {
+                comment: self.nodes[index].comment.clone(),
+                variations: siblings
+                    .iter()
+                    .filter(|&&sibling| sibling != index)
+                    .map(|&sibling| self.get_variation(Some(sibling), false))
+                    .collect(),
+            };
+            if annotation != Annotation::default() {
+                variation.annotations.insert(variation.moves.len(), annotation);
+            }
+            variation.moves.push(self.nodes[index].next_move.expect("moves have a move"));
+            node = self.nodes[index].children.first().cloned();
+        }
+        variation
+    }
+    pub fn get_rules(&self) -> Rules {
+        self.rules
+    }
+    /// The board of the current position.
+    pub fn get_board(&self) -> &Board {
+        &self.nodes[self.current].board
+    }
+    pub fn get_current_player(&self) -> TwoPlayerIndices {
+        self.nodes[self.current].player
+    }
+    /// Number of moves from the start to the current position.
+    pub fn get_ply(&self) -> usize {
+        self.get_line().len()
+    }
+    /// The moves from the start to the current position.
+    pub fn get_line(&self) -> Vec<Move> {
+        let mut line = vec![];
+        let mut node = self.current;
+        while let Some(parent) = self.nodes[node].parent {
+            line.push(self.nodes[node].next_move.expect("moves have a move"));
+            node = parent;
+        }
+        line.reverse();
+        line
+    }
+    /// The moves known in the current position, the main line first.
+    pub fn get_continuations(&self) -> Vec<Move> {
+        self.nodes[self.current]
+            .children
+            .iter()
+            .map(|&child| self.nodes[child].next_move.expect("moves have a move"))
+            .collect()
+    }
+    /// Whether the current position lies on the main line.
+    pub fn is_main_line(&self) -> bool {
+        let mut node = self.current;
+        while let Some(parent) = self.nodes[node].parent {
+            if self.nodes[parent].children.first() != Some(&node) {
+                return false;
+            }
+            node = parent;
+        }
+        true
+    }
+    /// Goes to the position after the move: a known continuation, or a new one which
+    /// becomes a variation unless the position had no continuation.
+    pub fn play_move(&mut self, next_move: Move) -> Result<(), GameError> {
+        let current = &self.nodes[self.current];
+        let known = current
+            .children
+            .iter()
+            .find(|&&child| self.nodes[child].next_move == Some(next_move));
+        if let Some(&child) = known {
+            self.current = child;
+            return Ok(());
+        }
+        if let Some(winner) = current.board.get_winner() {
+            return Err(GameError::GameOver { winner });
+        }
+        let mut board = current.board.clone();
+        board.play_move(current.player, next_move)?;
+        let node = Node {
+            board,
+            player: current.player.next_player(),
+            next_move: Some(next_move),
+            comment: None,
+            parent: Some(self.current),
+            children: vec![],
+        };
+        self.nodes.push(node);
+        let index = self.nodes.len() - 1;
+        self.nodes[self.current].children.push(index);
+        self.current = index;
+        Ok(())
+    }
+    /// Goes one move forward along the main line, returns false if there is no move.
+    pub fn forward(&mut self) -> bool {
+        match self.nodes[self.current].children.first() {
+            Some(&child) => {
+                self.current = child;
+                true
+            }
+            None => false,
+        }
+    }
+    /// Goes one move back, returns false at the start.
+    pub fn back(&mut self) -> bool {
+        match self.nodes[self.current].parent {
+            Some(parent) => {
+                self.current = parent;
+                true
+            }
+            None => false,
+        }
+    }
+    pub fn goto_start(&mut self) {
+        self.current = 0;
+    }
+    /// Goes forward along the main line as far as it is known.
+    pub fn goto_end(&mut self) {
+        while self.forward() {}
+    }
+    /// Makes the line leading to the current position the main line.
+    pub fn promote_variation(&mut self) {
+        let mut node = self.current;
+        while let Some(parent) = self.nodes[node].parent {
+            let children = &mut self.nodes[parent].children;
+            let position = children
+                .iter()
+                .position(|&child| child == node)
+                .expect("nodes are children of their parent");
+            let child = children.remove(position);
+            children.insert(0, child);
+            node = parent;
+        }
+    }
+    /// Removes the move leading to the current position with everything after it and goes one
+    /// move back, returns false at the start.
+    pub fn delete_variation(&mut self) -> bool {
+        let node = self.current;
+        match self.nodes[node].parent {
+            Some(parent) => {
+                self.nodes[parent].children.retain(|&child| child != node);
+                self.current = parent;
+                true
+            }
+            None => false,
+        }
+    }
+    /// The comment on the move leading to the current position.
+    pub fn get_comment(&self) -> Option<&str> {
+        self.nodes[self.current].comment.as_deref()
+    }
+    /// Comments the move leading to the current position, the start position has no comment.
+    pub fn set_comment(&mut self, comment: Option<String>) {
+        if self.current != 0 {
+            self.nodes[self.current].comment = comment;
+        }
+    }
+}
@@ -0,0 +1,290 @@
+//! Game archive: finished games with their metadata in a local file, searchable by players,
+//! result, positions and openings.
+//!
+//! The archive file is a record file as described in `record`, added games are appended to it
+//! with the tags `Id`, `White`, `Black` and `Date` (`YYYY-MM-DD`), so it can be read and edited
+//! like any other record file. The positions of all games are indexed when the archive is
+//! opened.
+use board_square::*;
+use record::*;
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Player name of records without a `White` or `Black` tag.
+pub const UNKNOWN_PLAYER: &str = "?";
+
+/// A game in the archive.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ArchivedGame {
+    pub id: u64,
+    /// with the tags `Id`, `White`, `Black` and `Date`
+    pub record: GameRecord,
+    // canonical hashes of the positions before every move and after the last one
+    position_hashes: Vec<u64>,
+}
+
+impl ArchivedGame {
+    pub fn get_player(&self, player: TwoPlayerIndices) -> &str {
+        let tag = match player {
+            TwoPlayerIndices::White => "White",
+            TwoPlayerIndices::Black => "Black",
+        };
+        self.record.get_tag(tag).unwrap_or(UNKNOWN_PLAYER)
+    }
+    pub fn get_date(&self) -> &str {
+        self.record.get_tag("Date").unwrap_or("")
+    }
+    /// Number of plies played.
+    pub fn get_length(&self) -> usize {
+        self.record.moves.len()
+    }
+    /// Whether the position occurs in the game, `hash` being `Position::get_canonical_hash`.
+    pub fn contains_position(&self, hash: u64) -> bool {
+        self.position_hashes.contains(&hash)
+    }
+}
+
+/// Conditions on the games returned by `Archive::search`, `None` and an empty opening match
+/// every game.
+#[derive(Clone, PartialEq, Default, Debug)]
+pub struct GameQuery {
+    /// name of White or Black
+    pub player: Option<String>,
+    pub board_size: Option<usize>,
+    pub rules: Option<Rules>,
+    /// the winner, `Some(None)` for games without winner
+    pub result: Option<Option<TwoPlayerIndices>>,
+    /// canonical hash of a position reached in the game, see `Position::get_canonical_hash`
+    pub position_hash: Option<u64>,
+    /// the first moves of the game, or their mirror image
+    pub opening: Vec<Move>,
+}
+
+impl GameQuery {
+    pub fn matches(&self, game: &ArchivedGame) -> bool {
+        let record = &game.record;
+        let player_matches = match self.player {
+            Some(ref name) => {
+                game.get_player(TwoPlayerIndices::White) == name
+                    || game.get_player(TwoPlayerIndices::Black) == name
+            }
+            None => true,
+        };
+        let mirrored_opening = self.opening
+            .iter()
+            .map(|x| x.mirrored(record.board_size))
+            .collect::<Vec<_>>();
+        let opening_matches = record.moves.starts_with(&self.opening)
+            || record.moves.starts_with(&mirrored_opening);
+        player_matches && opening_matches
+            && self.board_size.is_none_or(|x| x == record.board_size)
+            && self.rules.is_none_or(|x| x == record.rules)
+            && self.result.is_none_or(|x| x == record.winner)
+            && self.position_hash.is_none_or(|x| game.contains_position(x))
+    }
+}
+
+/// Games stored in a local file, see the module documentation.
+pub struct Archive {
+    path: PathBuf,
+    games: Vec<ArchivedGame>,
+    // canonical position hash to the indices of the games reaching it
+    positions: HashMap<u64, Vec<usize>>,
+}
+
+fn invalid_data<E: ToString>(error: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error.to_string())
+}
+
+// canonical hashes of the positions of the game
+fn get_position_hashes(record: &GameRecord) -> io::Result<Vec<u64>> {
+    fn replay<Board: PositionBoard + Clone>(record: &GameRecord) -> io::Result<Vec<u64>> {
+        let boards = record.replay::<Board>().map_err(invalid_data)?;
+        Ok(boards
+            .iter()
+            .enumerate()
+            .map(|(index, board)| {
+                board
+                    .get_position(record.get_move_player(index))
+                    .get_canonical_hash()
+            })
+            .collect())
+    }
+    match record.board_size {
+        3 => replay::<SquareBoard3>(record),
+        4 => replay::<SquareBoard4>(record),
+        5 => replay::<SquareBoard5>(record),
+        6 => replay::<SquareBoard6>(record),
+        7 => replay::<SquareBoard7>(record),
+        8 => replay::<SquareBoard8>(record),
+        9 => replay::<SquareBoard9>(record),
+        10 => replay::<SquareBoard10>(record),
+        11 => replay::<SquareBoard11>(record),
+        size => Err(invalid_data(format!("unsupported board size {}", size))),
+    }
+}
+
+/// Today as `YYYY-MM-DD` in UTC.
+pub fn get_today() -> String {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|x| x.as_secs())
+        .unwrap_or(0);
+    // days since 1970-01-01 to the civil date, counted in eras of 400 years from 0000-03-01
+    let days = (seconds / 86_400) as i64 + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524
+        - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + (month <= 2) as i64;
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+impl Archive {
+    /// Opens the archive file, which is created by the first game added if it does not exist.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let mut archive = Archive {
+            path: path.as_ref().to_path_buf(),
+            games: vec![],
+            positions: HashMap::new(),
+        };
+        let mut text = String::new();
+        match File::open(&archive.path) {
+            Ok(mut file) => file.read_to_string(&mut text)?,
+            Err(ref error) if error.kind() == io::ErrorKind::NotFound => 0,
+            Err(error) => return Err(error),
+        };
+        for record in GameRecord::parse_all(&text).map_err(invalid_data)? {
+            let id = match record.get_tag("Id").map(|x| x.parse()) {
+                Some(Ok(id)) => id,
+                _ => return Err(invalid_data(format!("archived game without Id:\n{}", record))),
+            };
+            let position_hashes = get_position_hashes(&record)?;
+            archive.insert(id, record, position_hashes);
+        }
+        Ok(archive)
+    }
+    pub fn get_path(&self) -> &Path {
+        &self.path
+    }
+    pub fn len(&self) -> usize {
+        self.games.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.games.is_empty()
+    }
+    /// All games in the order they were added.
+    pub fn get_games(&self) -> &[ArchivedGame] {
+        &self.games
+    }
+    pub fn get_game(&self, id: u64) -> Option<&ArchivedGame> {
+        self.games.iter().find(|game| game.id == id)
+    }
+    fn insert(&mut self, id: u64, record: GameRecord, position_hashes: Vec<u64>) {
+        let index = self.games.len();
+        for &hash in &position_hashes {
+            let games = self.positions.entry(hash).or_default();
+            if games.last() != Some(&index) {
+                games.push(index);
+            }
+        }
+        self.games.push(ArchivedGame {
+            id,
+            record,
+            position_hashes,
+        });
+    }
+    /// Checks the moves of the record and appends it to the archive file, returns the id of
+    /// the game.
+    ///
+    /// The players are taken from the tags `White` and `Black`, the date from `Date` or is
+    /// today.
+    pub fn add(&mut self, record: &GameRecord) -> io::Result<u64> {
+        let id = self.games.iter().map(|game| game.id + 1).max().unwrap_or(1);
+        let mut record = record.clone();
+        record.tags.retain(|(name, _)| name != "Id");
+        record.tags.insert(0, ("Id".to_string(), id.to_string()));
+        for &tag in &["White", "Black"] {
+            if record.get_tag(tag).is_none() {
+                record.set_tag(tag, UNKNOWN_PLAYER);
+            }
+        }
+        if record.get_tag("Date").is_none() {
+            record.set_tag("Date", &get_today());
+        }
+        // the file is only written once the record is known to be valid
+        let position_hashes = get_position_hashes(&record)?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", record)?;
+        self.insert(id, record, position_hashes);
+        Ok(id)
+    }
+    /// The games matching the query in the order they were added.
+    pub fn search(&self, query: &GameQuery) -> Vec<&ArchivedGame> {
+        match query.position_hash {
+            Some(hash) => self.positions
+                .get(&hash)
+                .map(|games| games.iter().map(|&index| &self.games[index]).collect())
+                .unwrap_or_default(),
+            None => self.games.iter().collect::<Vec<_>>(),
+        }.into_iter()
+            .filter(|game| query.matches(game))
+            .collect()
+    }
+    /// Adds the records of a record file, or of all files of a directory, which are not yet in
+    /// the archive, returns the number of records added.
+    pub fn import<P: AsRef<Path>>(&mut self, path: P) -> io::Result<usize> {
+        let path = path.as_ref();
+        let records = if path.is_dir() {
+            read_records_from_directory(path)?
+        } else {
+            let mut text = String::new();
+            File::open(path)?.read_to_string(&mut text)?;
+            GameRecord::parse_all(&text).map_err(invalid_data)?
+        };
+        let mut count = 0;
+        for record in records {
+            if !self.games.iter().any(|game| is_same_game(&game.record, &record)) {
+                self.add(&record)?;
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+    /// Writes the games matching the query to a record file, returns their number.
+    pub fn export<P: AsRef<Path>>(&self, path: P, query: &GameQuery) -> io::Result<usize> {
+        let games = self.search(query);
+        let mut file = File::create(path)?;
+        for game in &games {
+            writeln!(file, "{}", game.record)?;
+        }
+        file.flush()?;
+        Ok(games.len())
+    }
+}
+
+// records which only differ in their id, tags missing in the new record are not compared
+fn is_same_game(archived: &GameRecord, record: &GameRecord) -> bool {
+    archived.board_size == record.board_size && archived.rules == record.rules
+        && archived.start_position == record.start_position
+        && archived.winner == record.winner && archived.moves == record.moves
+        && record
+            .tags
+            .iter()
+            .filter(|(name, _)| name != "Id")
+            .all(|(name, value)| archived.get_tag(name) == Some(value))
+}
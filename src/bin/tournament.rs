@@ -0,0 +1,146 @@
+extern crate varidor;
+use varidor::archive::*;
+use varidor::book::*;
+use varidor::evaluation::*;
+use varidor::tablebase::*;
+use varidor::tournament::*;
+use varidor::*;
+
+use std::env;
+use std::process;
+
+const USAGE: &str = "usage:
+    tournament <board size> <games> <engine> <engine> [option]...
+
+options:
+    --threads <count>       games played at the same time, default 1
+    --records <directory>   writes the record of every game to the directory
+    --archive <file>        adds every game to the archive file
+    --walls <count>         walls per player, default 5
+    --random-plies <count>  random plies at the start of every game, default 2
+    --seed <number>         seed of the random plies and book moves, default 1
+
+engines are given as settings separated by spaces, e.g. \"name=new depth=3 book=book5.txt\"
+or \"depth=2 walls=0.5\", the other names are weights of the evaluator replacing the defaults
+
+board sizes: 3 to 11";
+
+fn parse_number(argument: Option<&String>, name: &str) -> Result<usize, String> {
+    match argument {
+        Some(argument) => argument
+            .parse()
+            .map_err(|_| format!("{} has to be a number, found '{}'", name, argument)),
+        None => Err(format!("{} is missing", name)),
+    }
+}
+
+fn parse_engine(text: &str) -> Result<Engine, String> {
+    let mut engine = Engine::new(text, 2);
+    for part in text.split_whitespace() {
+        let mut name_and_value = part.splitn(2, '=');
+        let name = name_and_value.next().unwrap_or("");
+        let value = name_and_value
+            .next()
+            .ok_or_else(|| format!("engine setting '{}' has no value", part))?;
+        match name {
+            "name" => engine.name = value.to_string(),
+            "depth" => {
+                engine.depth = value
+                    .parse()
+                    .map_err(|_| format!("depth has to be a number, found '{}'", value))?
+            }
+            "book" => {
+                let book = OpeningBook::load(value)
+                    .map_err(|error| format!("cannot read {}: {}", value, error))?;
+                engine.book = Some(book);
+            }
+            _ => {
+                let index = FEATURE_NAMES
+                    .iter()
+                    .position(|&x| x == name)
+                    .ok_or_else(|| format!("unknown engine setting '{}'", name))?;
+                engine.evaluator.weights[index] = value
+                    .parse()
+                    .map_err(|_| format!("weight has to be a number, found '{}'", value))?;
+            }
+        }
+    }
+    Ok(engine)
+}
+
+fn run_tournament<Board>(
+    tournament: &Tournament,
+    engines: &[Engine; 2],
+    directory: Option<&String>,
+    archive_path: Option<&String>,
+) -> Result<(), String>
+where
+    Board: TablebaseBoard + Clone,
+{
+    let result = tournament.run::<Board>(engines);
+    println!("{}", result);
+    println!("score {:.1}%", 100. * result.get_score());
+    if let Some(directory) = directory {
+        result
+            .save_records(directory)
+            .map_err(|error| format!("cannot write {}: {}", directory, error))?;
+    }
+    if let Some(path) = archive_path {
+        let mut archive =
+            Archive::open(path).map_err(|error| format!("cannot read {}: {}", path, error))?;
+        for record in &result.records {
+            archive
+                .add(record)
+                .map_err(|error| format!("cannot write {}: {}", path, error))?;
+        }
+    }
+    Ok(())
+}
+
+fn run(arguments: &[String]) -> Result<(), String> {
+    if arguments.len() < 4 {
+        return Err(USAGE.to_string());
+    }
+    let size = parse_number(arguments.first(), "board size")?;
+    let mut tournament = Tournament {
+        games: parse_number(arguments.get(1), "number of games")?,
+        ..Tournament::default()
+    };
+    let engines = [parse_engine(&arguments[2])?, parse_engine(&arguments[3])?];
+    let mut directory = None;
+    let mut archive_path = None;
+    let mut options = arguments[4..].iter();
+    while let Some(option) = options.next() {
+        match option.as_str() {
+            "--threads" => tournament.threads = parse_number(options.next(), "threads")?,
+            "--walls" => tournament.rules.wall_count = parse_number(options.next(), "walls")?,
+            "--random-plies" => {
+                tournament.random_plies = parse_number(options.next(), "random plies")?
+            }
+            "--seed" => tournament.seed = parse_number(options.next(), "seed")? as u64,
+            "--records" => directory = Some(options.next().ok_or("directory is missing")?),
+            "--archive" => archive_path = Some(options.next().ok_or("archive file is missing")?),
+            _ => return Err(format!("unknown option '{}'\n{}", option, USAGE)),
+        }
+    }
+    match size {
+        3 => run_tournament::<SquareBoard3>(&tournament, &engines, directory, archive_path),
+        4 => run_tournament::<SquareBoard4>(&tournament, &engines, directory, archive_path),
+        5 => run_tournament::<SquareBoard5>(&tournament, &engines, directory, archive_path),
+        6 => run_tournament::<SquareBoard6>(&tournament, &engines, directory, archive_path),
+        7 => run_tournament::<SquareBoard7>(&tournament, &engines, directory, archive_path),
+        8 => run_tournament::<SquareBoard8>(&tournament, &engines, directory, archive_path),
+        9 => run_tournament::<SquareBoard9>(&tournament, &engines, directory, archive_path),
+        10 => run_tournament::<SquareBoard10>(&tournament, &engines, directory, archive_path),
+        11 => run_tournament::<SquareBoard11>(&tournament, &engines, directory, archive_path),
+        size => Err(format!("unsupported board size {}, use 3 to 11", size)),
+    }
+}
+
+fn main() {
+    let arguments = env::args().skip(1).collect::<Vec<_>>();
+    if let Err(error) = run(&arguments) {
+        eprintln!("{}", error);
+        process::exit(1);
+    }
+}
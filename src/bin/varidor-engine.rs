@@ -0,0 +1,16 @@
+extern crate varidor;
+use varidor::protocol::*;
+
+use std::io;
+use std::process;
+
+// talks the engine protocol on the standard input and output, see `varidor::protocol`
+fn main() {
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+    let mut session = EngineSession::new("varidor");
+    if let Err(error) = session.run(stdin.lock(), &mut stdout.lock()) {
+        eprintln!("{}", error);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,52 @@
+extern crate varidor;
+use varidor::server::*;
+use varidor::*;
+
+use std::env;
+use std::process;
+
+const USAGE: &str = "usage:
+    varidor-server <board size> [address] [walls]
+
+hosts games over WebSocket, the address defaults to 127.0.0.1:8080 and the walls per player to 5
+board sizes: 3, 4, 5, 9";
+
+fn serve<Board>(address: &str, rules: Rules) -> Result<(), String>
+where
+    Board: PositionBoard<WallDirectionType = WallDirections> + Send + 'static,
+{
+    let server = GameServer::<Board>::bind(address, rules)
+        .map_err(|error| format!("cannot listen on {}: {}", address, error))?;
+    if let Ok(address) = server.get_address() {
+        println!("listening on ws://{}", address);
+    }
+    server.run().map_err(|error| error.to_string())
+}
+
+fn run(arguments: &[String]) -> Result<(), String> {
+    let size = match arguments.first().map(|x| x.parse::<usize>()) {
+        Some(Ok(size)) => size,
+        _ => return Err(USAGE.to_string()),
+    };
+    let address = arguments.get(1).map_or("127.0.0.1:8080", |x| x.as_str());
+    let rules = match arguments.get(2).map(|x| x.parse()) {
+        Some(Ok(wall_count)) => Rules { wall_count },
+        Some(Err(_)) => return Err(USAGE.to_string()),
+        None => Rules::default(),
+    };
+    match size {
+        3 => serve::<SquareBoard3>(address, rules),
+        4 => serve::<SquareBoard4>(address, rules),
+        5 => serve::<SquareBoard5>(address, rules),
+        9 => serve::<SquareBoard9>(address, rules),
+        size => Err(format!("unsupported board size {}", size)),
+    }
+}
+
+fn main() {
+    let arguments = env::args().skip(1).collect::<Vec<_>>();
+    if let Err(error) = run(&arguments) {
+        eprintln!("{}", error);
+        process::exit(1);
+    }
+}
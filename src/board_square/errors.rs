@@ -0,0 +1,188 @@
+use std::error::Error;
+use std::fmt;
+use super::*;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum MoveError<FieldIndexType = FieldIndexSquare<usize>, DirectionsType = DirectionsSquare> {
+    BoardBoundary {
+        field: FieldIndexType,
+        direction: DirectionsType,
+    },
+    Wall {
+        field: FieldIndexType,
+        direction: DirectionsType,
+    },
+    FieldsNotAdjacent {
+        from: FieldIndexType,
+        to: FieldIndexType,
+    },
+}
+impl<FieldIndexType: fmt::Display, DirectionsType: fmt::Display> fmt::Display
+    for MoveError<FieldIndexType, DirectionsType>
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::MoveError::*;
+        match *self {
+            BoardBoundary {
+                ref field,
+                ref direction,
+            } => write!(
+                f,
+                "cannot move {} from {}: board boundary reached",
+                direction, field
+            ),
+            Wall {
+                ref field,
+                ref direction,
+            } => write!(
+                f,
+                "cannot move {} from {}: wall is blocking",
+                direction, field
+            ),
+            FieldsNotAdjacent { ref from, ref to } => {
+                write!(f, "fields {} and {} are not adjacent", from, to)
+            }
+        }
+    }
+}
+impl<FieldIndexType, DirectionsType> Error for MoveError<FieldIndexType, DirectionsType>
+where
+    FieldIndexType: fmt::Display + fmt::Debug,
+    DirectionsType: fmt::Display + fmt::Debug,
+{
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum WallPlacmentError<
+    FieldIndexType = FieldIndexSquare<usize>,
+    DirectionsType = DirectionsSquare,
+    PlayerIndexType = TwoPlayerIndices,
+> {
+    // the wall would stick out of the board when starting at field and heading to direction
+    BoardBoundary {
+        field: FieldIndexType,
+        direction: DirectionsType,
+    },
+    // field is the lower left field of the already placed wall segment
+    WallAlreadyPlaced { field: FieldIndexType },
+    NoMoreWalls { player: PlayerIndexType },
+    PlayerBlocked { player: PlayerIndexType },
+    // field is the lower left field of the occupied crossing
+    WallsAlreadyCrossing { field: FieldIndexType },
+    NotConnected,
+}
+pub type WallPlacmentResult<FieldIndexType, DirectionsType, PlayerIndexType> =
+    Result<(), WallPlacmentError<FieldIndexType, DirectionsType, PlayerIndexType>>;
+pub type WallSlotResult<WallSlotType, FieldIndexType, DirectionsType, PlayerIndexType> =
+    Result<WallSlotType, WallPlacmentError<FieldIndexType, DirectionsType, PlayerIndexType>>;
+impl<FieldIndexType, DirectionsType, PlayerIndexType> fmt::Display
+    for WallPlacmentError<FieldIndexType, DirectionsType, PlayerIndexType>
+where
+    FieldIndexType: fmt::Display,
+    DirectionsType: fmt::Display,
+    PlayerIndexType: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::WallPlacmentError::*;
+        match *self {
+            BoardBoundary {
+                ref field,
+                ref direction,
+            } => write!(
+                f,
+                "wall at {} heading {} would leave the board",
+                field, direction
+            ),
+            WallAlreadyPlaced { ref field } => {
+                write!(f, "a wall is already placed next to {}", field)
+            }
+            NoMoreWalls { ref player } => write!(f, "{} has no walls left", player),
+            PlayerBlocked { ref player } => {
+                write!(f, "wall would block {} from reaching the goal", player)
+            }
+            WallsAlreadyCrossing { ref field } => {
+                write!(f, "walls would cross at the corner of {}", field)
+            }
+            NotConnected => write!(f, "wall pieces are not connected"),
+        }
+    }
+}
+impl<FieldIndexType, DirectionsType, PlayerIndexType> Error
+    for WallPlacmentError<FieldIndexType, DirectionsType, PlayerIndexType>
+where
+    FieldIndexType: fmt::Display + fmt::Debug,
+    DirectionsType: fmt::Display + fmt::Debug,
+    PlayerIndexType: fmt::Display + fmt::Debug,
+{
+}
+
+/// Every reason why a `GameData` can reject an action.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum GameError<
+    FieldIndexType = FieldIndexSquare<usize>,
+    DirectionsType = DirectionsSquare,
+    PlayerIndexType = TwoPlayerIndices,
+> {
+    Move(MoveError<FieldIndexType, DirectionsType>),
+    WallPlacment(WallPlacmentError<FieldIndexType, DirectionsType, PlayerIndexType>),
+    GameOver { winner: PlayerIndexType },
+    NothingToUndo,
+}
+pub type GameResult<FieldIndexType, DirectionsType, PlayerIndexType> =
+    Result<(), GameError<FieldIndexType, DirectionsType, PlayerIndexType>>;
+pub type MakeMoveResult<UndoTokenType, FieldIndexType, DirectionsType, PlayerIndexType> =
+    Result<UndoTokenType, GameError<FieldIndexType, DirectionsType, PlayerIndexType>>;
+impl<FieldIndexType, DirectionsType, PlayerIndexType> fmt::Display
+    for GameError<FieldIndexType, DirectionsType, PlayerIndexType>
+where
+    FieldIndexType: fmt::Display,
+    DirectionsType: fmt::Display,
+    PlayerIndexType: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::GameError::*;
+        match *self {
+            Move(ref error) => error.fmt(f),
+            WallPlacment(ref error) => error.fmt(f),
+            GameOver { ref winner } => write!(f, "game is already over, {} has won", winner),
+            NothingToUndo => write!(f, "there is no move to take back"),
+        }
+    }
+}
+// the move and wall errors are shown as the game error itself, they are no further cause
+impl<FieldIndexType, DirectionsType, PlayerIndexType> Error
+    for GameError<FieldIndexType, DirectionsType, PlayerIndexType>
+where
+    FieldIndexType: fmt::Display + fmt::Debug,
+    DirectionsType: fmt::Display + fmt::Debug,
+    PlayerIndexType: fmt::Display + fmt::Debug,
+{
+}
+impl<FieldIndexType, DirectionsType, PlayerIndexType> From<MoveError<FieldIndexType, DirectionsType>>
+    for GameError<FieldIndexType, DirectionsType, PlayerIndexType>
+{
+    fn from(error: MoveError<FieldIndexType, DirectionsType>) -> Self {
+        GameError::Move(error)
+    }
+}
+impl<FieldIndexType, DirectionsType, PlayerIndexType>
+    From<WallPlacmentError<FieldIndexType, DirectionsType, PlayerIndexType>>
+    for GameError<FieldIndexType, DirectionsType, PlayerIndexType>
+{
+    fn from(error: WallPlacmentError<FieldIndexType, DirectionsType, PlayerIndexType>) -> Self {
+        GameError::WallPlacment(error)
+    }
+}
+
+/// Text which could not be read as a move or one of its parts.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ParseError {
+    pub text: String,
+    pub expected: &'static str,
+}
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "cannot read '{}' as {}", self.text, self.expected)
+    }
+}
+impl Error for ParseError {}
@@ -1,6 +1,7 @@
 mod player;
 pub use self::player::*;
 mod type_level_integers;
+pub use self::type_level_integers::*;
 mod square_board;
 pub use self::square_board::*;
 mod square_board5;
@@ -9,6 +10,37 @@ mod square_board3;
 pub use self::square_board4::*;
 mod square_board4;
 pub use self::square_board5::*;
+mod square_board6;
+pub use self::square_board6::*;
+mod square_board7;
+pub use self::square_board7::*;
+mod square_board8;
+pub use self::square_board8::*;
+mod square_board9;
+pub use self::square_board9::*;
+mod square_board10;
+pub use self::square_board10::*;
+mod square_board11;
+pub use self::square_board11::*;
+mod errors;
+pub use self::errors::*;
+mod rules;
+pub use self::rules::*;
+mod wall_slot;
+pub use self::wall_slot::*;
+mod shortest_paths;
+pub use self::shortest_paths::*;
+mod wall_table;
+pub use self::wall_table::*;
+mod wall_evaluation;
+pub use self::wall_evaluation::*;
+mod race;
+pub use self::race::*;
+mod position;
+pub use self::position::*;
+mod notation;
+
+use std::fmt;
 
 pub trait DirectionsTrait: Clone + Copy + 'static {
     const DIRECTIONS_COUNT: usize;
@@ -27,33 +59,45 @@ pub trait PlayerDataTrait {
     fn change_current_field(&mut self, new_field: Self::FieldIndexType);
     fn get_wall_count(&self) -> Self::WallCountType;
     fn reduce_wall_count_by_one(&mut self);
-    fn get_shortest_paths(&self) -> &Vec<Vec<(Self::FieldIndexType, Self::DirectionsType)>>;
+    fn get_shortest_paths(&self) -> &ShortestPathDag<Self::FieldIndexType, Self::DirectionsType>;
     fn change_shortest_paths(
         &mut self,
-        new_list: Vec<Vec<(Self::FieldIndexType, Self::DirectionsType)>>,
+        new_paths: ShortestPathDag<Self::FieldIndexType, Self::DirectionsType>,
     );
 }
 
-#[derive(PartialEq, Clone, Copy)]
+#[derive(PartialEq, Clone, Copy, Debug)]
 pub enum WallPlaced {
     IsWall,
     IsEmpty,
 }
-pub enum WallPlacmentError {
-    BoardBoundary,
-    WallAlreadyPlaced,
-    NoMoreWalls,
-    PlayerBlocked,
-    WallsAlreadyCrossing,
-    NotConnected,
+
+/// A single ply: either a pawn step or a wall placement at its canonical slot.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Move<DirectionsType = DirectionsSquare, WallSlotType = WallSlot> {
+    Step(DirectionsType),
+    Wall(WallSlotType),
 }
-pub enum MoveError {
-    BoardBoundary,
-    Wall,
-    FieldsNotAdjacent,
+
+impl<DirectionsType, WallSlotType> fmt::Display for Move<DirectionsType, WallSlotType>
+where
+    DirectionsType: fmt::Display,
+    WallSlotType: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Move::Step(ref direction) => write!(f, "step {}", direction),
+            Move::Wall(ref slot) => write!(f, "wall {}", slot),
+        }
+    }
 }
 
-pub trait BoardTrait {
+/// The `Move` type belonging to a board type.
+pub type BoardMove<Board> =
+    Move<<Board as BoardTrait>::DirectionsType, <Board as BoardTrait>::WallSlotType>;
+
+/// A position together with the movement and wall placement rules.
+pub trait BoardTrait: Sized {
     const AVERAGE_BOARD_SIZE: usize;
     type PlayerIndexType: PlayerIndexTrait;
     type DirectionsType: DirectionsTrait;
@@ -62,8 +106,14 @@ pub trait BoardTrait {
         FieldIndexType = Self::FieldIndexType,
         DirectionsType = Self::DirectionsType,
     >;
-    type WallDirectionType;
-    fn new() -> Self;
+    type WallDirectionType: Clone + Copy;
+    type WallSlotType: Clone + Copy + PartialEq;
+    type UndoTokenType;
+    fn new() -> Self {
+        Self::with_rules(Rules::default())
+    }
+    fn with_rules(rules: Rules) -> Self;
+    fn get_rules(&self) -> Rules;
     fn get_field_in_direction(
         field: Self::FieldIndexType,
         direction: Self::DirectionsType,
@@ -101,7 +151,7 @@ pub trait BoardTrait {
             .filter(|&&(_, direction)| {
                 self.check_for_wall_unsafe(field, direction) == WallPlaced::IsEmpty
             })
-            .map(|x| *x)
+            .cloned()
             .collect()
     }
     fn is_final_field(field: Self::FieldIndexType, player: Self::PlayerIndexType) -> bool;
@@ -111,79 +161,220 @@ pub trait BoardTrait {
     fn get_current_field(&self, player: Self::PlayerIndexType) -> Self::FieldIndexType {
         self.get_player_data(player).get_current_field()
     }
+    /// Number of steps of the player's shortest path, `None` if the goal cannot be reached.
+    fn get_path_length(&self, player: Self::PlayerIndexType) -> Option<usize> {
+        self.get_player_data(player).get_shortest_paths().get_length()
+    }
+    fn has_walls_left(&self, player: Self::PlayerIndexType) -> bool;
     fn move_player(
         &mut self,
         player: Self::PlayerIndexType,
         direction: Self::DirectionsType,
-    ) -> Option<MoveError> {
+    ) -> Result<(), MoveError<Self::FieldIndexType, Self::DirectionsType>> {
         let current_field = self.get_current_field(player);
         // check if fields is at board boundary
         if let Some((next_field, _)) = Self::get_field_in_direction(current_field, direction) {
             // check if wall is in between
             if self.check_for_wall_unsafe(current_field, direction) == WallPlaced::IsWall {
-                Some(MoveError::Wall)
+                Err(MoveError::Wall {
+                    field: current_field,
+                    direction,
+                })
             } else {
                 self.get_player_data_mut(player)
                     .change_current_field(next_field);
-                let new_paths = self.compute_shortest_paths(player).clone();
+                let new_paths = self.compute_shortest_paths(player);
                 self.get_player_data_mut(player)
                     .change_shortest_paths(new_paths);
-                None
+                Ok(())
             }
         } else {
-            Some(MoveError::BoardBoundary)
+            Err(MoveError::BoardBoundary {
+                field: current_field,
+                direction,
+            })
         }
     }
     fn compute_shortest_paths(
         &self,
         player: Self::PlayerIndexType,
-    ) -> Vec<Vec<(Self::FieldIndexType, Self::DirectionsType)>> {
-        let mut visited_fields =
-            Vec::with_capacity(Self::AVERAGE_BOARD_SIZE * Self::AVERAGE_BOARD_SIZE);
-        let start_field = self.get_current_field(player);
-        visited_fields.push(start_field);
-        let mut shortest_paths = Vec::with_capacity(Self::AVERAGE_BOARD_SIZE);
-        let mut current_paths = vec![(Vec::with_capacity(0), start_field)];
-        while shortest_paths.is_empty() && !current_paths.is_empty() {
-            let mut next_paths =
-                Vec::with_capacity(current_paths.len() * Self::DirectionsType::DIRECTIONS_COUNT);
-            let mut new_visited_fields =
-                Vec::with_capacity(current_paths.len() * Self::DirectionsType::DIRECTIONS_COUNT);
-            for (current_path, previous_end) in current_paths {
-                //current_path[0][0] + 0;
-                let _temp = self.get_surrounding_possible_fields(previous_end);
-                let surrounding_fields_directions = _temp
-                    .iter()
-                    .filter(|field_direction| {
-                        !visited_fields
-                            .iter()
-                            .any(|&visited_field| visited_field == field_direction.0)
-                    })
-                    .collect::<Vec<_>>();
-                for &(next_field, direction) in surrounding_fields_directions {
-                    let mut next_path = current_path.clone();
-                    next_path.push((next_field, direction));
-                    if Self::is_final_field(next_field, player) {
-                        shortest_paths.push(next_path);
-                    } else {
-                        next_paths.push((next_path, next_field));
-                    }
-                    new_visited_fields.push(next_field);
-                }
-            }
-            current_paths = next_paths;
-            new_visited_fields
-                .iter()
-                .map(|&x| visited_fields.push(x))
-                .collect::<Vec<_>>();
-        }
-        shortest_paths
+    ) -> ShortestPathDag<Self::FieldIndexType, Self::DirectionsType> {
+        ShortestPathDag::compute(
+            self.get_current_field(player),
+            |field| self.get_surrounding_possible_fields(field),
+            |field| Self::is_final_field(field, player),
+        )
     }
+    /// Canonical slot of the wall given by its first field, the direction of the wall as seen
+    /// from this field and the side to which the wall extends.
+    fn get_wall_slot(
+        first_field: Self::FieldIndexType,
+        direction: Self::DirectionsType,
+        wall_direction: Self::WallDirectionType,
+    ) -> WallSlotResult<
+        Self::WallSlotType,
+        Self::FieldIndexType,
+        Self::DirectionsType,
+        Self::PlayerIndexType,
+    >;
+    fn place_wall_at(
+        &mut self,
+        player: Self::PlayerIndexType,
+        slot: Self::WallSlotType,
+    ) -> WallPlacmentResult<Self::FieldIndexType, Self::DirectionsType, Self::PlayerIndexType>;
     fn place_wall(
         &mut self,
         player: Self::PlayerIndexType,
         first_field: Self::FieldIndexType,
         direction: Self::DirectionsType,
         wall_direction: Self::WallDirectionType,
-    ) -> Option<WallPlacmentError>;
+    ) -> WallPlacmentResult<Self::FieldIndexType, Self::DirectionsType, Self::PlayerIndexType>
+    {
+        let slot = Self::get_wall_slot(first_field, direction, wall_direction)?;
+        self.place_wall_at(player, slot)
+    }
+    fn play_move(
+        &mut self,
+        player: Self::PlayerIndexType,
+        next_move: BoardMove<Self>,
+    ) -> GameResult<Self::FieldIndexType, Self::DirectionsType, Self::PlayerIndexType> {
+        match next_move {
+            Move::Step(direction) => self.move_player(player, direction).map_err(GameError::Move),
+            Move::Wall(slot) => self.place_wall_at(player, slot)
+                .map_err(GameError::WallPlacment),
+        }
+    }
+    /// Plays a move in place, the returned token takes it back with `unmake_move`.
+    fn make_move(
+        &mut self,
+        player: Self::PlayerIndexType,
+        next_move: BoardMove<Self>,
+    ) -> MakeMoveResult<
+        Self::UndoTokenType,
+        Self::FieldIndexType,
+        Self::DirectionsType,
+        Self::PlayerIndexType,
+    >;
+    /// Takes back the last move made by `make_move` without recomputing anything.
+    fn unmake_move(&mut self, token: Self::UndoTokenType);
+    // every wall slot of the board, placed or not
+    fn get_wall_slots() -> Vec<Self::WallSlotType>;
+    // every wall placement which is possible on an empty board
+    fn get_wall_moves() -> Vec<BoardMove<Self>> {
+        Self::get_wall_slots().into_iter().map(Move::Wall).collect()
+    }
+    // steps which are not blocked and every wall move, some of these may still be rejected
+    fn get_candidate_moves(&self, player: Self::PlayerIndexType) -> Vec<BoardMove<Self>> {
+        self.get_surrounding_possible_fields(self.get_current_field(player))
+            .into_iter()
+            .map(|(_, direction)| Move::Step(direction))
+            .chain(Self::get_wall_moves())
+            .collect()
+    }
+    // all moves the player may play together with the resulting boards
+    fn get_successors(
+        &self,
+        player: Self::PlayerIndexType,
+    ) -> Vec<(BoardMove<Self>, Self)>
+    where
+        Self: Clone,
+    {
+        self.get_candidate_moves(player)
+            .into_iter()
+            .filter_map(|next_move| {
+                let mut board = self.clone();
+                match board.play_move(player, next_move) {
+                    Ok(()) => Some((next_move, board)),
+                    Err(_) => None,
+                }
+            })
+            .collect()
+    }
+    fn get_legal_moves(
+        &self,
+        player: Self::PlayerIndexType,
+    ) -> Vec<BoardMove<Self>>
+    where
+        Self: Clone,
+    {
+        self.get_successors(player)
+            .into_iter()
+            .map(|(next_move, _)| next_move)
+            .collect()
+    }
+    /// The legal walls of the player ranked by how much longer they make the opponent's shortest
+    /// path compared to the own one, at most `count` of them.
+    ///
+    /// Equally ranked walls are ordered by the delay of the opponent, then in generation order.
+    fn most_damaging_walls(
+        &self,
+        player: Self::PlayerIndexType,
+        count: usize,
+    ) -> Vec<WallEvaluation<Self::WallSlotType>>
+    where
+        Self: Clone,
+    {
+        let opponent = player.next_player();
+        let own_length_before = self.get_path_length(player).unwrap_or(0);
+        let opponent_length_before = self.get_path_length(opponent).unwrap_or(0);
+        let mut board = self.clone();
+        let mut evaluations = vec![];
+        for next_move in self.get_candidate_moves(player) {
+            if let Move::Wall(slot) = next_move {
+                if let Ok(token) = board.make_move(player, next_move) {
+                    evaluations.push(WallEvaluation {
+                        slot,
+                        opponent_length_before,
+                        opponent_length_after: board.get_path_length(opponent).unwrap_or(0),
+                        own_length_before,
+                        own_length_after: board.get_path_length(player).unwrap_or(0),
+                    });
+                    board.unmake_move(token);
+                }
+            }
+        }
+        evaluations.sort_by(|a, b| {
+            b.get_gain()
+                .cmp(&a.get_gain())
+                .then(b.get_opponent_delay().cmp(&a.get_opponent_delay()))
+        });
+        evaluations.truncate(count);
+        evaluations
+    }
+    /// The forced outcome once no player has walls left, `player` being the one to move.
+    ///
+    /// A finished game counts as decided after 0 plies.
+    /// Pawns do not block each other, so without walls every player just follows a shortest
+    /// path and the one to move wins ties.
+    fn solve_race(
+        &self,
+        player: Self::PlayerIndexType,
+    ) -> Option<RaceResult<Self::PlayerIndexType>> {
+        if let Some(winner) = self.get_winner() {
+            return Some(RaceResult { winner, plies: 0 });
+        }
+        let opponent = player.next_player();
+        if self.has_walls_left(player) || self.has_walls_left(opponent) {
+            return None;
+        }
+        let own_length = self.get_path_length(player)?;
+        let opponent_length = self.get_path_length(opponent)?;
+        Some(if own_length <= opponent_length {
+            RaceResult {
+                winner: player,
+                plies: 2 * own_length - 1,
+            }
+        } else {
+            RaceResult {
+                winner: opponent,
+                plies: 2 * opponent_length,
+            }
+        })
+    }
+    // the player standing on a final field, if any
+    fn get_winner(&self) -> Option<Self::PlayerIndexType> {
+        Self::PlayerIndexType::get_player_index_array()
+            .find(|&&player| Self::is_final_field(self.get_current_field(player), player))
+            .cloned()
+    }
 }
@@ -0,0 +1,143 @@
+//! Reading moves and positions in the form written by their `Display` implementations,
+//! e.g. `step up` or `wall (1,2) vertical`.
+use std::str::FromStr;
+use super::*;
+
+fn parse_error(text: &str, expected: &'static str) -> ParseError {
+    ParseError {
+        text: text.to_string(),
+        expected,
+    }
+}
+
+impl FromStr for DirectionsSquare {
+    type Err = ParseError;
+    fn from_str(text: &str) -> Result<Self, ParseError> {
+        match text.trim() {
+            "up" => Ok(DirectionsSquare::Up),
+            "down" => Ok(DirectionsSquare::Down),
+            "left" => Ok(DirectionsSquare::Left),
+            "right" => Ok(DirectionsSquare::Right),
+            _ => Err(parse_error(text, "direction")),
+        }
+    }
+}
+
+impl FromStr for WallOrientation {
+    type Err = ParseError;
+    fn from_str(text: &str) -> Result<Self, ParseError> {
+        match text.trim() {
+            "horizontal" => Ok(WallOrientation::Horizontal),
+            "vertical" => Ok(WallOrientation::Vertical),
+            _ => Err(parse_error(text, "wall orientation")),
+        }
+    }
+}
+
+impl FromStr for FieldIndexSquare<usize> {
+    type Err = ParseError;
+    fn from_str(text: &str) -> Result<Self, ParseError> {
+        let trimmed = text.trim();
+        if !trimmed.starts_with('(') || !trimmed.ends_with(')') {
+            return Err(parse_error(text, "field"));
+        }
+        let mut numbers = trimmed[1..trimmed.len() - 1]
+            .split(',')
+            .map(|x| x.trim().parse::<usize>());
+        match (numbers.next(), numbers.next(), numbers.next()) {
+            (Some(Ok(column)), Some(Ok(row)), None) => Ok(FieldIndexSquare { column, row }),
+            _ => Err(parse_error(text, "field")),
+        }
+    }
+}
+
+impl FromStr for WallSlot {
+    type Err = ParseError;
+    fn from_str(text: &str) -> Result<Self, ParseError> {
+        // the field may contain spaces, the orientation is the last word
+        match text.trim().rsplit_once(char::is_whitespace) {
+            Some((anchor, orientation)) => Ok(WallSlot {
+                anchor: anchor.parse()?,
+                orientation: orientation.parse()?,
+            }),
+            None => Err(parse_error(text, "wall slot")),
+        }
+    }
+}
+
+impl FromStr for Move {
+    type Err = ParseError;
+    fn from_str(text: &str) -> Result<Self, ParseError> {
+        let trimmed = text.trim();
+        if let Some(direction) = trimmed.strip_prefix("step ") {
+            Ok(Move::Step(direction.parse()?))
+        } else if let Some(slot) = trimmed.strip_prefix("wall ") {
+            Ok(Move::Wall(slot.parse()?))
+        } else {
+            Err(parse_error(text, "move"))
+        }
+    }
+}
+
+impl Move {
+    /// Reads moves written one after the other, e.g. `step up wall (1,2) vertical step left`.
+    pub fn parse_all(text: &str) -> Result<Vec<Move>, ParseError> {
+        let mut moves = vec![];
+        let mut current = String::new();
+        for word in text.split_whitespace() {
+            if (word == "step" || word == "wall") && !current.is_empty() {
+                moves.push(current.parse()?);
+                current.clear();
+            }
+            if !current.is_empty() {
+                current.push(' ');
+            }
+            current.push_str(word);
+        }
+        if !current.is_empty() {
+            moves.push(current.parse()?);
+        }
+        Ok(moves)
+    }
+}
+
+impl FromStr for Position {
+    type Err = ParseError;
+    fn from_str(text: &str) -> Result<Self, ParseError> {
+        let words = text.split_whitespace().collect::<Vec<_>>();
+        if words.len() < 6 || words.len() % 2 != 0 {
+            return Err(parse_error(text, "position"));
+        }
+        let number = |word: &str| {
+            word.parse::<usize>()
+                .map_err(|_| parse_error(word, "number"))
+        };
+        let player = match words[1] {
+            "white" => TwoPlayerIndices::White,
+            "black" => TwoPlayerIndices::Black,
+            word => return Err(parse_error(word, "player")),
+        };
+        let board_size = number(words[0])?;
+        // fields and walls off the board would not fit the board size
+        let field = |word: &str| match word.parse::<FieldIndexSquare<usize>>()? {
+            field if field.column < board_size && field.row < board_size => Ok(field),
+            _ => Err(parse_error(word, "field on the board")),
+        };
+        let slot = |words: &[&str]| {
+            let text = words.join(" ");
+            match text.parse::<WallSlot>()? {
+                slot if slot.is_on_board(board_size) => Ok(slot),
+                _ => Err(parse_error(&text, "wall slot on the board")),
+            }
+        };
+        let mut position = Position {
+            board_size,
+            player,
+            fields: [field(words[2])?, field(words[3])?],
+            wall_counts: [number(words[4])?, number(words[5])?],
+            walls: words[6..].chunks(2).map(slot).collect::<Result<_, _>>()?,
+        };
+        position.sort_walls();
+        Ok(position)
+    }
+}
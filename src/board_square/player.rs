@@ -1,4 +1,4 @@
-pub trait PlayerIndexTrait: Clone + Copy + 'static {
+pub trait PlayerIndexTrait: Clone + Copy + PartialEq + 'static {
     const PLAYER_COUNT: usize;
     type PlayerIndexArray: Iterator<Item = &'static Self>;
     fn get_player_index_array() -> Self::PlayerIndexArray;
@@ -7,18 +7,19 @@ pub trait PlayerIndexTrait: Clone + Copy + 'static {
     fn get_color_as_string(self) -> &'static str;
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum TwoPlayerIndices {
     White,
     Black,
 }
+use std::fmt;
 use std::slice::Iter;
 impl PlayerIndexTrait for TwoPlayerIndices {
     const PLAYER_COUNT: usize = 2;
     type PlayerIndexArray = Iter<'static, TwoPlayerIndices>;
     fn get_player_index_array() -> Self::PlayerIndexArray {
         use self::TwoPlayerIndices::*;
-        [White, Black].into_iter()
+        [White, Black].iter()
     }
     fn next_player(self) -> Self {
         match self {
@@ -39,3 +40,8 @@ impl PlayerIndexTrait for TwoPlayerIndices {
         }
     }
 }
+impl fmt::Display for TwoPlayerIndices {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.get_color_as_string())
+    }
+}
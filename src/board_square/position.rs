@@ -0,0 +1,224 @@
+use super::*;
+use std::fmt;
+
+/// Placement of pawns and walls together with the player to move, see `PositionBoard`.
+///
+/// Positions which are mirror images of each other, left and right swapped, play the same and
+/// share a `get_canonical_hash`.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Position {
+    pub board_size: usize,
+    pub player: TwoPlayerIndices,
+    /// current fields in the order White, Black
+    pub fields: [FieldIndexSquare<usize>; 2],
+    /// remaining walls in the order White, Black
+    pub wall_counts: [usize; 2],
+    /// placed walls in the order of `WallSlot::all`
+    pub walls: Vec<WallSlot>,
+}
+
+/// Boards which describe their position as a `Position`.
+pub trait PositionBoard:
+    BoardTrait<
+    PlayerIndexType = TwoPlayerIndices,
+    DirectionsType = DirectionsSquare,
+    FieldIndexType = FieldIndexSquare<usize>,
+    WallSlotType = WallSlot,
+>
+{
+    fn get_position(&self, player: TwoPlayerIndices) -> Position;
+    /// Number of walls the player may still place.
+    fn get_wall_count(&self, player: TwoPlayerIndices) -> usize;
+    /// The board showing the position, `None` if it is not one of this board size, walls
+    /// overlap or a player cannot reach the goal.
+    fn from_position(position: &Position, rules: Rules) -> Option<Self>;
+}
+
+impl<SizeType, WallDataType, WallCrosingType> PositionBoard
+    for SquareBoard<usize, SizeType, WallDataType, WallCrosingType>
+where
+    SizeType: IntegerTrait<usize>,
+    WallDataType: WallPositionTrait<usize>,
+    WallCrosingType: WallCrosingTrait<usize>,
+{
+    fn get_position(&self, player: TwoPlayerIndices) -> Position {
+        let players = [TwoPlayerIndices::White, TwoPlayerIndices::Black];
+        Position {
+            board_size: Self::AVERAGE_BOARD_SIZE,
+            player,
+            fields: [
+                self.get_current_field(players[0]),
+                self.get_current_field(players[1]),
+            ],
+            wall_counts: [
+                PositionBoard::get_wall_count(self, players[0]),
+                PositionBoard::get_wall_count(self, players[1]),
+            ],
+            walls: self.get_wall_table()
+                .get_entries()
+                .iter()
+                .map(|entry| entry.slot)
+                .filter(|&slot| self.is_wall_at_unsafe(slot))
+                .collect(),
+        }
+    }
+    fn get_wall_count(&self, player: TwoPlayerIndices) -> usize {
+        self.get_player_data(player).get_wall_count()
+    }
+    fn from_position(position: &Position, rules: Rules) -> Option<Self> {
+        Self::with_position(position, rules)
+    }
+}
+
+impl fmt::Display for Position {
+    /// e.g. `5 white (2,3) (2,0) 4 5 (1,2) vertical`: board size, player to move, the fields of
+    /// White and Black, their remaining walls and the placed walls.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let player = match self.player {
+            TwoPlayerIndices::White => "white",
+            TwoPlayerIndices::Black => "black",
+        };
+        write!(
+            f,
+            "{} {} {} {} {} {}",
+            self.board_size,
+            player,
+            self.fields[0],
+            self.fields[1],
+            self.wall_counts[0],
+            self.wall_counts[1]
+        )?;
+        for slot in &self.walls {
+            write!(f, " {}", slot)?;
+        }
+        Ok(())
+    }
+}
+
+impl DirectionsSquare {
+    /// The direction after swapping left and right.
+    pub fn mirrored(self) -> Self {
+        match self {
+            DirectionsSquare::Left => DirectionsSquare::Right,
+            DirectionsSquare::Right => DirectionsSquare::Left,
+            direction => direction,
+        }
+    }
+}
+
+impl FieldIndexSquare<usize> {
+    /// The field after swapping left and right on a board of the given size.
+    pub fn mirrored(self, board_size: usize) -> Self {
+        FieldIndexSquare {
+            column: board_size - 1 - self.column,
+            row: self.row,
+        }
+    }
+}
+
+impl WallSlot {
+    /// The slot after swapping left and right on a board of the given size.
+    pub fn mirrored(self, board_size: usize) -> Self {
+        WallSlot {
+            anchor: FieldIndexSquare {
+                column: board_size - 2 - self.anchor.column,
+                row: self.anchor.row,
+            },
+            orientation: self.orientation,
+        }
+    }
+}
+
+impl Move {
+    /// The move after swapping left and right on a board of the given size.
+    pub fn mirrored(self, board_size: usize) -> Self {
+        match self {
+            Move::Step(direction) => Move::Step(direction.mirrored()),
+            Move::Wall(slot) => Move::Wall(slot.mirrored(board_size)),
+        }
+    }
+}
+
+impl Position {
+    /// The position after swapping left and right.
+    pub fn mirrored(&self) -> Self {
+        let mut walls = self.walls
+            .iter()
+            .map(|slot| slot.mirrored(self.board_size))
+            .collect::<Vec<_>>();
+        walls.sort_by_key(|slot| self.get_slot_number(*slot));
+        Position {
+            board_size: self.board_size,
+            player: self.player,
+            fields: [
+                self.fields[0].mirrored(self.board_size),
+                self.fields[1].mirrored(self.board_size),
+            ],
+            wall_counts: self.wall_counts,
+            walls,
+        }
+    }
+    /// The position or its mirror image, whichever is smaller in a fixed order, and whether it
+    /// is the mirror image.
+    pub fn get_canonical(&self) -> (Position, bool) {
+        let mirrored = self.mirrored();
+        if mirrored.get_numbers() < self.get_numbers() {
+            (mirrored, true)
+        } else {
+            (self.clone(), false)
+        }
+    }
+    /// Hash of the canonical position, the same in every build, e.g. to key files.
+    pub fn get_canonical_hash(&self) -> u64 {
+        self.get_canonical().0.get_hash()
+    }
+    /// Puts the walls in the order of `WallSlot::all`, dropping repeated ones.
+    pub fn sort_walls(&mut self) {
+        let mut walls = self.walls
+            .iter()
+            .map(|&slot| (self.get_slot_number(slot), slot))
+            .collect::<Vec<_>>();
+        walls.sort_by_key(|&(number, _)| number);
+        walls.dedup_by_key(|&mut (number, _)| number);
+        self.walls = walls.into_iter().map(|(_, slot)| slot).collect();
+    }
+    /// FNV-1a hash of the position, the order of the walls does not matter.
+    pub fn get_hash(&self) -> u64 {
+        let mut hash = 0xcbf2_9ce4_8422_2325u64;
+        for number in self.get_numbers() {
+            for &byte in &(number as u64).to_le_bytes() {
+                hash ^= u64::from(byte);
+                hash = hash.wrapping_mul(0x0100_0000_01b3);
+            }
+        }
+        hash
+    }
+    // position in the order of `WallSlot::all`
+    fn get_slot_number(&self, slot: WallSlot) -> usize {
+        let anchor_number = slot.anchor.column * (self.board_size - 1) + slot.anchor.row;
+        match slot.orientation {
+            WallOrientation::Vertical => 2 * anchor_number,
+            WallOrientation::Horizontal => 2 * anchor_number + 1,
+        }
+    }
+    // everything as numbers, the order used by `get_canonical`
+    fn get_numbers(&self) -> Vec<usize> {
+        let mut numbers = vec![
+            self.board_size,
+            (self.player == TwoPlayerIndices::Black) as usize,
+            self.fields[0].column,
+            self.fields[0].row,
+            self.fields[1].column,
+            self.fields[1].row,
+            self.wall_counts[0],
+            self.wall_counts[1],
+        ];
+        let mut walls = self.walls
+            .iter()
+            .map(|&slot| self.get_slot_number(slot))
+            .collect::<Vec<_>>();
+        walls.sort_unstable();
+        numbers.append(&mut walls);
+        numbers
+    }
+}
@@ -0,0 +1,15 @@
+use std::fmt;
+
+/// Forced outcome of a race, see `BoardTrait::solve_race`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct RaceResult<PlayerIndexType> {
+    pub winner: PlayerIndexType,
+    /// plies until the winner reaches the goal, counting the plies of both players
+    pub plies: usize,
+}
+
+impl<PlayerIndexType: fmt::Display> fmt::Display for RaceResult<PlayerIndexType> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} wins the race in {} plies", self.winner, self.plies)
+    }
+}
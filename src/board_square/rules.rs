@@ -0,0 +1,11 @@
+/// Rules which are not fixed by the board type.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Rules {
+    /// number of walls each player may place
+    pub wall_count: usize,
+}
+impl Default for Rules {
+    fn default() -> Self {
+        Rules { wall_count: 5 }
+    }
+}
@@ -0,0 +1,267 @@
+use std::fmt;
+
+/// All shortest paths of a player to the goal as a layered graph.
+///
+/// Layer `k` holds the fields at distance `k` from the current field which lie on some shortest
+/// path, so storage stays quadratic in the board size although the number of paths may be
+/// exponential. Concrete paths are produced lazily by `iter_paths`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ShortestPathDag<FieldIndexType, DirectionsType> {
+    // empty if the goal cannot be reached, otherwise the first layer holds the current field only
+    layers: Vec<Vec<DagNode<FieldIndexType, DirectionsType>>>,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+struct DagNode<FieldIndexType, DirectionsType> {
+    field: FieldIndexType,
+    // indices into the next layer together with the direction of the step
+    next: Vec<(usize, DirectionsType)>,
+    // number of shortest paths from this field to the goal
+    path_count: u64,
+}
+
+impl<F, D> Default for ShortestPathDag<F, D> {
+    fn default() -> Self {
+        ShortestPathDag { layers: vec![] }
+    }
+}
+
+impl<F, D> ShortestPathDag<F, D>
+where
+    F: Clone + Copy + PartialEq,
+    D: Clone + Copy,
+{
+    /// Builds the graph by a breadth first search from `start` until the first final field is found.
+    ///
+    /// `get_steps` yields the fields reachable in one step, `is_final` marks the goal fields.
+    pub fn compute<S, G>(start: F, get_steps: S, is_final: G) -> Self
+    where
+        S: Fn(F) -> Vec<(F, D)>,
+        G: Fn(F) -> bool,
+    {
+        let mut layers = vec![vec![DagNode {
+            field: start,
+            next: vec![],
+            path_count: 1,
+        }]];
+        if is_final(start) {
+            return ShortestPathDag { layers };
+        }
+        let mut visited_fields = vec![start];
+        // forward: all fields by distance, with every step from one layer to the next
+        loop {
+            let mut next_layer: Vec<DagNode<F, D>> = vec![];
+            {
+                let current_layer = layers.last_mut().unwrap();
+                for node in current_layer.iter_mut() {
+                    for (next_field, direction) in get_steps(node.field) {
+                        if visited_fields.contains(&next_field) {
+                            continue;
+                        }
+                        let index = match next_layer.iter().position(|x| x.field == next_field) {
+                            Some(index) => index,
+                            None => {
+                                next_layer.push(DagNode {
+                                    field: next_field,
+                                    next: vec![],
+                                    path_count: 0,
+                                });
+                                next_layer.len() - 1
+                            }
+                        };
+                        node.next.push((index, direction));
+                    }
+                }
+            }
+            if next_layer.is_empty() {
+                return ShortestPathDag::default();
+            }
+            visited_fields.extend(next_layer.iter().map(|x| x.field));
+            let reached_goal = next_layer.iter().any(|x| is_final(x.field));
+            for node in next_layer.iter_mut() {
+                if is_final(node.field) {
+                    node.path_count = 1;
+                }
+            }
+            layers.push(next_layer);
+            if reached_goal {
+                break;
+            }
+        }
+        // backward: count the paths and drop the fields which do not lead to the goal
+        for layer_index in (0..layers.len() - 1).rev() {
+            let (current, next) = layers.split_at_mut(layer_index + 1);
+            let next_layer = &next[0];
+            for node in current[layer_index].iter_mut() {
+                node.next
+                    .retain(|&(index, _)| next_layer[index].path_count > 0);
+                node.path_count = node.next
+                    .iter()
+                    .fold(0u64, |sum, &(index, _)| {
+                        sum.saturating_add(next_layer[index].path_count)
+                    });
+            }
+            // renumber the next layer without its dead ends
+            let mut new_indices = Vec::with_capacity(next_layer.len());
+            let mut kept = 0;
+            for node in next_layer.iter() {
+                new_indices.push(kept);
+                if node.path_count > 0 {
+                    kept += 1;
+                }
+            }
+            for node in current[layer_index].iter_mut() {
+                for step in node.next.iter_mut() {
+                    step.0 = new_indices[step.0];
+                }
+            }
+            next[0].retain(|x| x.path_count > 0);
+        }
+        ShortestPathDag { layers }
+    }
+    /// Whether the goal can be reached at all.
+    pub fn is_reachable(&self) -> bool {
+        !self.layers.is_empty()
+    }
+    /// Number of steps of a shortest path, `None` if the goal cannot be reached.
+    pub fn get_length(&self) -> Option<usize> {
+        if self.layers.is_empty() {
+            None
+        } else {
+            Some(self.layers.len() - 1)
+        }
+    }
+    /// Number of different shortest paths, saturating at `u64::MAX`.
+    pub fn get_path_count(&self) -> u64 {
+        match self.layers.first() {
+            Some(layer) => layer[0].path_count,
+            None => 0,
+        }
+    }
+    /// Whether the field lies on some shortest path, this includes the current field.
+    pub fn contains_field(&self, field: F) -> bool {
+        self.layers
+            .iter()
+            .any(|layer| layer.iter().any(|node| node.field == field))
+    }
+    /// Whether stepping from `field` in `direction` is part of some shortest path.
+    pub fn contains_step(&self, field: F, direction: D) -> bool
+    where
+        D: PartialEq,
+    {
+        self.layers.iter().any(|layer| {
+            layer.iter().any(|node| {
+                node.field == field && node.next.iter().any(|&(_, x)| x == direction)
+            })
+        })
+    }
+    /// Fields on some shortest path at the given distance from the current field.
+    pub fn get_fields_at_distance(&self, distance: usize) -> Vec<F> {
+        match self.layers.get(distance) {
+            Some(layer) => layer.iter().map(|node| node.field).collect(),
+            None => vec![],
+        }
+    }
+    /// Every step `(from, to, direction)` which is part of some shortest path.
+    pub fn get_steps(&self) -> Vec<(F, F, D)> {
+        let mut steps = vec![];
+        for (layer_index, layer) in self.layers.iter().enumerate() {
+            for node in layer {
+                for &(index, direction) in &node.next {
+                    steps.push((
+                        node.field,
+                        self.layers[layer_index + 1][index].field,
+                        direction,
+                    ));
+                }
+            }
+        }
+        steps
+    }
+    /// The shortest paths one after another, each as the fields entered and the directions taken.
+    pub fn iter_paths(&self) -> ShortestPathIter<'_, F, D> {
+        ShortestPathIter {
+            dag: self,
+            stack: if self.layers.is_empty() {
+                vec![]
+            } else {
+                vec![(0, 0)]
+            },
+            done: self.layers.is_empty(),
+        }
+    }
+}
+
+impl<F, D> fmt::Display for ShortestPathDag<F, D>
+where
+    F: Clone + Copy + PartialEq + fmt::Display,
+    D: Clone + Copy,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.get_length() {
+            Some(length) => write!(
+                f,
+                "{} shortest paths of length {}",
+                self.get_path_count(),
+                length
+            ),
+            None => write!(f, "goal not reachable"),
+        }
+    }
+}
+
+/// Iterator over the shortest paths of a `ShortestPathDag`, see `ShortestPathDag::iter_paths`.
+pub struct ShortestPathIter<'a, F: 'a, D: 'a> {
+    dag: &'a ShortestPathDag<F, D>,
+    // per layer the node index and the index of the next step to be taken from it
+    stack: Vec<(usize, usize)>,
+    done: bool,
+}
+
+impl<'a, F, D> Iterator for ShortestPathIter<'a, F, D>
+where
+    F: Clone + Copy + PartialEq,
+    D: Clone + Copy,
+{
+    type Item = Vec<(F, D)>;
+    fn next(&mut self) -> Option<Vec<(F, D)>> {
+        if self.done {
+            return None;
+        }
+        let layers = &self.dag.layers;
+        // descend along the first remaining steps until the goal is reached
+        while self.stack.len() < layers.len() {
+            let &(node_index, step_index) = self.stack.last().unwrap();
+            let node = &layers[self.stack.len() - 1][node_index];
+            let (next_index, _) = node.next[step_index];
+            self.stack.push((next_index, 0));
+        }
+        let path = self.stack
+            .windows(2)
+            .enumerate()
+            .map(|(layer_index, window)| {
+                let (node_index, step_index) = window[0];
+                let (next_index, direction) = layers[layer_index][node_index].next[step_index];
+                (layers[layer_index + 1][next_index].field, direction)
+            })
+            .collect();
+        // advance to the next unexplored step
+        self.stack.pop();
+        loop {
+            match self.stack.pop() {
+                Some((node_index, step_index)) => {
+                    let node = &layers[self.stack.len()][node_index];
+                    if step_index + 1 < node.next.len() {
+                        self.stack.push((node_index, step_index + 1));
+                        break;
+                    }
+                }
+                None => {
+                    self.done = true;
+                    break;
+                }
+            }
+        }
+        Some(path)
+    }
+}
@@ -1,16 +1,22 @@
+use std::fmt;
+use std::mem::{replace, take};
 use std::ops::{Add, SubAssign};
 use num_traits::One;
 use super::*;
-use super::type_level_integers::*;
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub struct FieldIndexSquare<T: Clone + Copy + PartialEq> {
     pub column: T,
     pub row: T,
 }
 impl<T: Clone + Copy + PartialEq> FieldIndexTrait for FieldIndexSquare<T> {}
+impl<T: Clone + Copy + PartialEq + fmt::Display> fmt::Display for FieldIndexSquare<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "({},{})", self.column, self.row)
+    }
+}
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum DirectionsSquare {
     Up,
     Down,
@@ -24,14 +30,40 @@ impl DirectionsTrait for DirectionsSquare {
     type DirectionsArray = Iter<'static, DirectionsSquare>;
     fn get_directions_array() -> Self::DirectionsArray {
         use self::DirectionsSquare::*;
-        [Up, Down, Left, Right].into_iter()
+        [Up, Down, Left, Right].iter()
+    }
+}
+impl DirectionsSquare {
+    pub fn get_opposite(self) -> Self {
+        use self::DirectionsSquare::*;
+        match self {
+            Up => Down,
+            Down => Up,
+            Left => Right,
+            Right => Left,
+        }
+    }
+}
+impl fmt::Display for DirectionsSquare {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::DirectionsSquare::*;
+        let name = match *self {
+            Up => "up",
+            Down => "down",
+            Left => "left",
+            Right => "right",
+        };
+        write!(f, "{}", name)
     }
 }
 
+type ShortestPathsSquare<T> = ShortestPathDag<FieldIndexSquare<T>, DirectionsSquare>;
+
+#[derive(Clone, Debug)]
 pub struct PlayerDataSquare<T: Clone + Copy + PartialEq> {
     current_field: FieldIndexSquare<T>,
     wall_count: T,
-    shortest_paths: Vec<Vec<(FieldIndexSquare<T>, DirectionsSquare)>>,
+    shortest_paths: ShortestPathsSquare<T>,
 }
 impl<T> PlayerDataTrait for PlayerDataSquare<T>
 where
@@ -52,40 +84,46 @@ where
     fn reduce_wall_count_by_one(&mut self) {
         self.wall_count -= One::one();
     }
-    fn get_shortest_paths(&self) -> &Vec<Vec<(FieldIndexSquare<T>, DirectionsSquare)>> {
+    fn get_shortest_paths(&self) -> &ShortestPathsSquare<T> {
         &self.shortest_paths
     }
-    fn change_shortest_paths(
-        &mut self,
-        new_list: Vec<Vec<(FieldIndexSquare<T>, DirectionsSquare)>>,
-    ) {
-        self.shortest_paths = new_list;
+    fn change_shortest_paths(&mut self, new_paths: ShortestPathsSquare<T>) {
+        self.shortest_paths = new_paths;
     }
 }
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
 pub enum WallDirections {
     Left,
     Right,
 }
+impl fmt::Display for WallDirections {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            WallDirections::Left => write!(f, "left"),
+            WallDirections::Right => write!(f, "right"),
+        }
+    }
+}
 
 pub trait WallPositionTrait<I> {
     fn new() -> Self;
-    fn at(&self, row: I, column: I, is_left_or_right: bool) -> WallPlaced;
-    fn at_mut(&mut self, row: I, column: I, is_left_or_right: bool) -> &mut WallPlaced;
+    fn at(&self, column: I, row: I, is_left_or_right: bool) -> WallPlaced;
+    fn at_mut(&mut self, column: I, row: I, is_left_or_right: bool) -> &mut WallPlaced;
 }
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub enum WallCrossing {
     IsWallCrossing,
     IsEmpty,
 }
 pub trait WallCrosingTrait<I> {
     fn new() -> Self;
-    fn at(&self, row: I, column: I) -> WallCrossing;
-    fn at_mut(&mut self, row: I, column: I) -> &mut WallCrossing;
+    fn at(&self, column: I, row: I) -> WallCrossing;
+    fn at_mut(&mut self, column: I, row: I) -> &mut WallCrossing;
 }
 
 use std::marker::PhantomData;
+/// Square board with two players starting at opposite sides, see `SquareBoard3`, `SquareBoard4` and `SquareBoard5`.
 pub struct SquareBoard<
     T: Clone + Copy + PartialEq,
     SizeType,
@@ -96,6 +134,73 @@ pub struct SquareBoard<
     _size: PhantomData<SizeType>,
     wall_positions: WallDataType,
     wall_crossing_positions: WallCrosingType,
+    rules: Rules,
+    // shared by all boards of the size
+    wall_table: &'static WallSlotTable,
+    // per slot of the table the number of placed walls which occupy it or conflict with it
+    blocked_slots: Vec<u8>,
+    // per slot of the table whether a wall is placed at it, the segments and crossings alone
+    // cannot tell apart a wall and the ends of two neighbouring walls crossing another one
+    placed_walls: Vec<bool>,
+}
+
+// implemented by hand, as the type level size does not need to be Clone
+impl<T, SizeType, WallDataType, WallCrosingType> Clone
+    for SquareBoard<T, SizeType, WallDataType, WallCrosingType>
+where
+    T: Clone + Copy + PartialEq,
+    WallDataType: WallPositionTrait<T> + Clone,
+    WallCrosingType: WallCrosingTrait<T> + Clone,
+{
+    fn clone(&self) -> Self {
+        SquareBoard {
+            player_data: self.player_data.clone(),
+            _size: PhantomData,
+            wall_positions: self.wall_positions.clone(),
+            wall_crossing_positions: self.wall_crossing_positions.clone(),
+            rules: self.rules,
+            wall_table: self.wall_table,
+            blocked_slots: self.blocked_slots.clone(),
+            placed_walls: self.placed_walls.clone(),
+        }
+    }
+}
+
+impl<T, SizeType, WallDataType, WallCrosingType> fmt::Debug
+    for SquareBoard<T, SizeType, WallDataType, WallCrosingType>
+where
+    T: Clone + Copy + PartialEq + fmt::Debug,
+    WallDataType: WallPositionTrait<T> + fmt::Debug,
+    WallCrosingType: WallCrosingTrait<T> + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SquareBoard")
+            .field("player_data", &self.player_data)
+            .field("wall_positions", &self.wall_positions)
+            .field("wall_crossing_positions", &self.wall_crossing_positions)
+            .field("rules", &self.rules)
+            .field("blocked_slots", &self.blocked_slots)
+            .finish()
+    }
+}
+
+/// Everything needed to take back a move played by `BoardTrait::make_move` on a `SquareBoard`.
+#[derive(Clone, Debug)]
+pub struct UndoToken<T: Clone + Copy + PartialEq = usize> {
+    player: TwoPlayerIndices,
+    change: UndoChange<T>,
+}
+#[derive(Clone, Debug)]
+enum UndoChange<T: Clone + Copy + PartialEq> {
+    Step {
+        from: FieldIndexSquare<T>,
+        shortest_paths: ShortestPathsSquare<T>,
+    },
+    // shortest paths in storage order, only those which were changed by the wall
+    Wall {
+        slot: WallSlot<T>,
+        shortest_paths: [Option<ShortestPathsSquare<T>>; 2],
+    },
 }
 
 impl<
@@ -106,97 +211,6 @@ impl<
     WallCrosingType: WallCrosingTrait<T>,
 > SquareBoard<T, SizeType, WallDataType, WallCrosingType>
 {
-    fn place_wall_unsafe(
-        &mut self,
-        lower_left_field: FieldIndexSquare<T>,
-        is_left_or_right: bool,
-    ) -> Option<WallPlacmentError> {
-        // check first wall
-        use self::WallPlaced::*;
-        if self.wall_positions.at(
-            lower_left_field.column,
-            lower_left_field.row,
-            is_left_or_right,
-        ) == IsWall
-        {
-            return Some(WallPlacmentError::WallAlreadyPlaced);
-        };
-        if match is_left_or_right {
-            true => self.wall_positions.at(
-                lower_left_field.column,
-                lower_left_field.row + One::one(),
-                is_left_or_right,
-            ),
-            false => self.wall_positions.at(
-                lower_left_field.column + One::one(),
-                lower_left_field.row,
-                is_left_or_right,
-            ),
-        } == IsWall
-        {
-            return Some(WallPlacmentError::WallAlreadyPlaced);
-        };
-        if self.wall_crossing_positions
-            .at(lower_left_field.column, lower_left_field.row)
-            == WallCrossing::IsWallCrossing
-        {
-            return Some(WallPlacmentError::WallsAlreadyCrossing);
-        }
-        // wall can be placed
-        *self.wall_positions.at_mut(
-            lower_left_field.column,
-            lower_left_field.row,
-            is_left_or_right,
-        ) = WallPlaced::IsWall;
-        match is_left_or_right {
-            true => {
-                *self.wall_positions.at_mut(
-                    lower_left_field.column,
-                    lower_left_field.row + One::one(),
-                    is_left_or_right,
-                ) = WallPlaced::IsWall
-            }
-            false => {
-                *self.wall_positions.at_mut(
-                    lower_left_field.column + One::one(),
-                    lower_left_field.row,
-                    is_left_or_right,
-                ) = WallPlaced::IsWall
-            }
-        };
-        *self.wall_crossing_positions
-            .at_mut(lower_left_field.column, lower_left_field.row) = WallCrossing::IsWallCrossing;
-        None
-    }
-    fn place_wall_unsafe_redo(
-        &mut self,
-        lower_left_field: FieldIndexSquare<T>,
-        is_left_or_right: bool,
-    ) {
-        *self.wall_positions.at_mut(
-            lower_left_field.column,
-            lower_left_field.row,
-            is_left_or_right,
-        ) = WallPlaced::IsEmpty;
-        match is_left_or_right {
-            true => {
-                *self.wall_positions.at_mut(
-                    lower_left_field.column,
-                    lower_left_field.row + One::one(),
-                    is_left_or_right,
-                ) = WallPlaced::IsEmpty
-            }
-            false => {
-                *self.wall_positions.at_mut(
-                    lower_left_field.column + One::one(),
-                    lower_left_field.row,
-                    is_left_or_right,
-                ) = WallPlaced::IsEmpty
-            }
-        };
-        *self.wall_crossing_positions
-            .at_mut(lower_left_field.column, lower_left_field.row) = WallCrossing::IsEmpty;
-    }
     pub fn wall_lookup_unsafe(
         &self,
         lower_left_field: FieldIndexSquare<T>,
@@ -228,39 +242,72 @@ where
     type FieldIndexType = FieldIndexSquare<usize>;
     type PlayerDataType = PlayerDataSquare<usize>;
     type WallDirectionType = WallDirections;
+    type WallSlotType = WallSlot;
+    type UndoTokenType = UndoToken;
 
-    fn new() -> Self {
-        const WALL_COUNT: usize = 5;
+    fn with_rules(rules: Rules) -> Self {
+        let wall_table = WallSlotTable::get_shared(Self::AVERAGE_BOARD_SIZE);
         let mut board = SquareBoard {
             _size: PhantomData,
             wall_positions: WallDataType::new(),
             wall_crossing_positions: WallCrosingType::new(),
+            rules,
+            blocked_slots: vec![0; wall_table.len()],
+            placed_walls: vec![false; wall_table.len()],
+            wall_table,
             player_data: [
                 PlayerDataSquare {
                     current_field: FieldIndexSquare {
                         column: Self::AVERAGE_BOARD_SIZE / 2,
                         row: 0,
                     },
-                    wall_count: WALL_COUNT,
-                    shortest_paths: vec![],
+                    wall_count: rules.wall_count,
+                    shortest_paths: ShortestPathDag::default(),
                 },
                 PlayerDataSquare {
                     current_field: FieldIndexSquare {
                         column: Self::AVERAGE_BOARD_SIZE / 2,
                         row: Self::AVERAGE_BOARD_SIZE - 1,
                     },
-                    wall_count: WALL_COUNT,
-                    shortest_paths: vec![],
+                    wall_count: rules.wall_count,
+                    shortest_paths: ShortestPathDag::default(),
                 },
             ],
         };
         for &player in TwoPlayerIndices::get_player_index_array() {
             let shortest_paths = board.compute_shortest_paths(player);
-            let mut data = board.get_player_data_mut(player);
+            let data = board.get_player_data_mut(player);
             data.shortest_paths = shortest_paths;
         }
         board
     }
+    fn get_rules(&self) -> Rules {
+        self.rules
+    }
+    fn has_walls_left(&self, player: TwoPlayerIndices) -> bool {
+        self.get_player_data(player).wall_count > 0
+    }
+    fn get_wall_slots() -> Vec<WallSlot> {
+        WallSlot::all(Self::AVERAGE_BOARD_SIZE).collect()
+    }
+    // walls on blocked slots are skipped by a lookup instead of being tried
+    fn get_candidate_moves(&self, player: TwoPlayerIndices) -> Vec<Move> {
+        let mut moves = self.get_surrounding_possible_fields(self.get_current_field(player))
+            .into_iter()
+            .map(|(_, direction)| Move::Step(direction))
+            .collect::<Vec<_>>();
+        if self.get_player_data(player).wall_count > 0 {
+            moves.extend(
+                self.wall_table
+                    .get_entries()
+                    .iter()
+                    .zip(self.blocked_slots.iter())
+                    .filter(|&(_, &blocked)| blocked == 0)
+                    .map(|(entry, _)| Move::Wall(entry.slot)),
+            );
+        }
+        moves
+    }
     fn get_field_in_direction(
         field: FieldIndexSquare<usize>,
         direction: DirectionsSquare,
@@ -339,81 +386,276 @@ where
         }
     }
 
-    fn place_wall(
-        &mut self,
-        player: TwoPlayerIndices,
+    fn get_wall_slot(
         first_field: FieldIndexSquare<usize>,
         direction: DirectionsSquare,
         wall_direction: WallDirections,
-    ) -> Option<WallPlacmentError> {
+    ) -> Result<WallSlot, WallPlacmentError> {
+        let boundary_error = WallPlacmentError::BoardBoundary {
+            field: first_field,
+            direction,
+        };
+        let (second_field, _) = match Self::get_field_in_direction(first_field, direction) {
+            Some(field_direction) => field_direction,
+            None => return Err(boundary_error),
+        };
+        let second_direction: DirectionsSquare = match (direction, wall_direction) {
+            (DirectionsSquare::Left, WallDirections::Left) => DirectionsSquare::Down,
+            (DirectionsSquare::Left, WallDirections::Right) => DirectionsSquare::Up,
+            (DirectionsSquare::Right, WallDirections::Left) => DirectionsSquare::Up,
+            (DirectionsSquare::Right, WallDirections::Right) => DirectionsSquare::Down,
+            (DirectionsSquare::Up, WallDirections::Left) => DirectionsSquare::Left,
+            (DirectionsSquare::Up, WallDirections::Right) => DirectionsSquare::Right,
+            (DirectionsSquare::Down, WallDirections::Left) => DirectionsSquare::Right,
+            (DirectionsSquare::Down, WallDirections::Right) => DirectionsSquare::Left,
+        };
+        let (final_field, _) = match Self::get_field_in_direction(second_field, second_direction) {
+            Some(field_direction) => field_direction,
+            None => return Err(boundary_error),
+        };
+        let (lower_left_field, is_left_or_right) = match (direction, wall_direction) {
+            (DirectionsSquare::Left, WallDirections::Left) => (final_field, true),
+            (DirectionsSquare::Left, WallDirections::Right) => (second_field, true),
+            (DirectionsSquare::Right, WallDirections::Left) => (first_field, true),
+            (DirectionsSquare::Right, WallDirections::Right) => (
+                FieldIndexSquare {
+                    column: first_field.column,
+                    row: final_field.row,
+                },
+                true,
+            ),
+            (DirectionsSquare::Up, WallDirections::Left) => (
+                FieldIndexSquare {
+                    column: final_field.column,
+                    row: first_field.row,
+                },
+                false,
+            ),
+            (DirectionsSquare::Up, WallDirections::Right) => (first_field, false),
+            (DirectionsSquare::Down, WallDirections::Left) => (second_field, false),
+            (DirectionsSquare::Down, WallDirections::Right) => (final_field, false),
+        };
+        Ok(WallSlot::from_lower_left(lower_left_field, is_left_or_right))
+    }
+    fn place_wall_at(
+        &mut self,
+        player: TwoPlayerIndices,
+        slot: WallSlot,
+    ) -> Result<(), WallPlacmentError> {
+        self.place_wall_at_replacing_paths(player, slot).map(|_| ())
+    }
+    fn make_move(
+        &mut self,
+        player: TwoPlayerIndices,
+        next_move: Move,
+    ) -> Result<UndoToken, GameError> {
+        let change = match next_move {
+            Move::Step(direction) => {
+                let from = self.get_current_field(player);
+                // the cached paths are not used by the step, so they are moved instead of cloned
+                let shortest_paths = take(&mut self.get_player_data_mut(player).shortest_paths);
+                if let Err(error) = self.move_player(player, direction) {
+                    self.get_player_data_mut(player).shortest_paths = shortest_paths;
+                    return Err(error.into());
+                }
+                UndoChange::Step {
+                    from,
+                    shortest_paths,
+                }
+            }
+            Move::Wall(slot) => UndoChange::Wall {
+                slot,
+                shortest_paths: self.place_wall_at_replacing_paths(player, slot)?,
+            },
+        };
+        Ok(UndoToken { player, change })
+    }
+    fn unmake_move(&mut self, token: UndoToken) {
+        let UndoToken { player, change } = token;
+        match change {
+            UndoChange::Step {
+                from,
+                shortest_paths,
+            } => {
+                let data = self.get_player_data_mut(player);
+                data.current_field = from;
+                data.shortest_paths = shortest_paths;
+            }
+            UndoChange::Wall {
+                slot,
+                shortest_paths: [black, white],
+            } => {
+                let index = self.wall_table.get_index(slot).unwrap();
+                self.place_wall_unsafe_redo(index);
+                if let Some(shortest_paths) = black {
+                    self.player_data[0].shortest_paths = shortest_paths;
+                }
+                if let Some(shortest_paths) = white {
+                    self.player_data[1].shortest_paths = shortest_paths;
+                }
+                self.get_player_data_mut(player).wall_count += 1;
+            }
+        }
+    }
+}
+
+impl<
+    SizeType,
+    WallDataType: WallPositionTrait<usize>,
+    WallCrosingType: WallCrosingTrait<usize>,
+> SquareBoard<usize, SizeType, WallDataType, WallCrosingType>
+where
+    SizeType: IntegerTrait<usize>,
+{
+    // returns the replaced shortest paths in storage order, none if unchanged by the wall
+    fn place_wall_at_replacing_paths(
+        &mut self,
+        player: TwoPlayerIndices,
+        slot: WallSlot,
+    ) -> Result<[Option<ShortestPathsSquare<usize>>; 2], WallPlacmentError> {
         if Self::get_player_data(self, player).wall_count == 0 {
-            Some(WallPlacmentError::NoMoreWalls)
-        } else {
-            if let Some((second_field, _)) = Self::get_field_in_direction(first_field, direction) {
-                let second_direction: DirectionsSquare = match (direction, wall_direction) {
-                    (DirectionsSquare::Left, WallDirections::Left) => DirectionsSquare::Down,
-                    (DirectionsSquare::Left, WallDirections::Right) => DirectionsSquare::Up,
-                    (DirectionsSquare::Right, WallDirections::Left) => DirectionsSquare::Up,
-                    (DirectionsSquare::Right, WallDirections::Right) => DirectionsSquare::Down,
-                    (DirectionsSquare::Up, WallDirections::Left) => DirectionsSquare::Left,
-                    (DirectionsSquare::Up, WallDirections::Right) => DirectionsSquare::Right,
-                    (DirectionsSquare::Down, WallDirections::Left) => DirectionsSquare::Right,
-                    (DirectionsSquare::Down, WallDirections::Right) => DirectionsSquare::Left,
-                };
-                if let Some((final_field, _)) =
-                    Self::get_field_in_direction(second_field, second_direction)
-                {
-                    let (lower_left_field, is_left_or_right) = match (direction, wall_direction) {
-                        (DirectionsSquare::Left, WallDirections::Left) => (final_field, true),
-                        (DirectionsSquare::Left, WallDirections::Right) => (second_field, true),
-                        (DirectionsSquare::Right, WallDirections::Left) => (first_field, true),
-                        (DirectionsSquare::Right, WallDirections::Right) => (
-                            FieldIndexSquare {
-                                column: first_field.column,
-                                row: final_field.row,
-                            },
-                            true,
-                        ),
-                        (DirectionsSquare::Up, WallDirections::Left) => (
-                            FieldIndexSquare {
-                                column: final_field.column,
-                                row: first_field.row,
-                            },
-                            false,
-                        ),
-                        (DirectionsSquare::Up, WallDirections::Right) => (first_field, false),
-                        (DirectionsSquare::Down, WallDirections::Left) => (second_field, false),
-                        (DirectionsSquare::Down, WallDirections::Right) => (final_field, false),
-                    };
-                    match self.place_wall_unsafe(lower_left_field, is_left_or_right) {
-                        Some(x) => Some(x),
-                        None => {
-                            let shortest_paths_white =
-                                self.compute_shortest_paths(TwoPlayerIndices::White);
-                            if shortest_paths_white.is_empty() {
-                                self.place_wall_unsafe_redo(lower_left_field, is_left_or_right);
-                                return Some(WallPlacmentError::PlayerBlocked);
-                            };
-                            let shortest_paths_black =
-                                self.compute_shortest_paths(TwoPlayerIndices::Black);
-                            if shortest_paths_black.is_empty() {
-                                self.place_wall_unsafe_redo(lower_left_field, is_left_or_right);
-                                return Some(WallPlacmentError::PlayerBlocked);
-                            };
-                            self.get_player_data_mut(TwoPlayerIndices::White)
-                                .change_shortest_paths(shortest_paths_white);
-                            self.get_player_data_mut(TwoPlayerIndices::Black)
-                                .change_shortest_paths(shortest_paths_black);
-                            self.get_player_data_mut(player).reduce_wall_count_by_one();
-                            None
-                        }
-                    }
-                } else {
-                    Some(WallPlacmentError::BoardBoundary)
+            return Err(WallPlacmentError::NoMoreWalls { player });
+        }
+        let index = match self.wall_table.get_index(slot) {
+            Some(index) => index,
+            None => {
+                let (field, direction, _) = slot.to_placement();
+                return Err(WallPlacmentError::BoardBoundary { field, direction });
+            }
+        };
+        self.place_wall_unsafe(index)?;
+        // a wall which cuts no shortest path of a player leaves all of them unchanged
+        let mut new_paths = [None, None];
+        for &other_player in &[TwoPlayerIndices::White, TwoPlayerIndices::Black] {
+            let cached_paths = self.get_player_data(other_player).get_shortest_paths();
+            if self.cuts_shortest_paths(index, cached_paths) {
+                let shortest_paths = self.compute_shortest_paths(other_player);
+                if !shortest_paths.is_reachable() {
+                    self.place_wall_unsafe_redo(index);
+                    return Err(WallPlacmentError::PlayerBlocked {
+                        player: other_player,
+                    });
                 }
-            } else {
-                Some(WallPlacmentError::BoardBoundary)
+                new_paths[Self::get_storage_index(other_player)] = Some(shortest_paths);
+            }
+        }
+        self.get_player_data_mut(player).reduce_wall_count_by_one();
+        let mut replaced_paths = [None, None];
+        for (index, new_paths) in new_paths.iter_mut().enumerate() {
+            if let Some(new_paths) = new_paths.take() {
+                let data = &mut self.player_data[index];
+                replaced_paths[index] = Some(replace(&mut data.shortest_paths, new_paths));
+            }
+        }
+        Ok(replaced_paths)
+    }
+    // the slot is given by its index in the wall table and has to be free
+    fn place_wall_unsafe(&mut self, index: usize) -> Result<(), WallPlacmentError> {
+        if self.blocked_slots[index] > 0 {
+            return Err(self.get_conflict_error(index));
+        }
+        let entry = self.wall_table.get_entry(index);
+        for &(field, is_left_or_right) in &entry.segments {
+            *self.wall_positions
+                .at_mut(field.column, field.row, is_left_or_right) = WallPlaced::IsWall;
+        }
+        *self.wall_crossing_positions
+            .at_mut(entry.slot.anchor.column, entry.slot.anchor.row) = WallCrossing::IsWallCrossing;
+        self.blocked_slots[index] += 1;
+        self.placed_walls[index] = true;
+        for &other in &entry.conflicting {
+            self.blocked_slots[other] += 1;
+        }
+        Ok(())
+    }
+    fn place_wall_unsafe_redo(&mut self, index: usize) {
+        let entry = self.wall_table.get_entry(index);
+        for &(field, is_left_or_right) in &entry.segments {
+            *self.wall_positions
+                .at_mut(field.column, field.row, is_left_or_right) = WallPlaced::IsEmpty;
+        }
+        *self.wall_crossing_positions
+            .at_mut(entry.slot.anchor.column, entry.slot.anchor.row) = WallCrossing::IsEmpty;
+        self.blocked_slots[index] -= 1;
+        self.placed_walls[index] = false;
+        for &other in &entry.conflicting {
+            self.blocked_slots[other] -= 1;
+        }
+    }
+    // which placed wall is in the way of the blocked slot
+    fn get_conflict_error(&self, index: usize) -> WallPlacmentError {
+        let entry = self.wall_table.get_entry(index);
+        for &(field, is_left_or_right) in &entry.segments {
+            if self.wall_lookup_unsafe(field, is_left_or_right) == WallPlaced::IsWall {
+                return WallPlacmentError::WallAlreadyPlaced { field };
             }
         }
+        WallPlacmentError::WallsAlreadyCrossing {
+            field: entry.slot.anchor,
+        }
+    }
+    // whether the wall blocks a step of some shortest path
+    fn cuts_shortest_paths(
+        &self,
+        index: usize,
+        shortest_paths: &ShortestPathsSquare<usize>,
+    ) -> bool {
+        self.wall_table
+            .get_entry(index)
+            .cut_steps
+            .iter()
+            .any(|&(field, direction)| shortest_paths.contains_step(field, direction))
+    }
+    /// Whether a wall is placed at the slot.
+    pub fn is_wall_at_unsafe(&self, slot: WallSlot) -> bool {
+        self.wall_table
+            .get_index(slot)
+            .is_some_and(|index| self.placed_walls[index])
+    }
+    /// Whether a wall could be placed at the slot, not considering blocked players or wall counts.
+    pub fn is_wall_slot_free(&self, slot: WallSlot) -> bool {
+        match self.wall_table.get_index(slot) {
+            Some(index) => self.blocked_slots[index] == 0,
+            None => false,
+        }
+    }
+    /// The board showing the position, `None` if it is not one of this board size, walls
+    /// overlap or a player cannot reach the goal.
+    pub fn with_position(position: &Position, rules: Rules) -> Option<Self> {
+        if position.board_size != Self::AVERAGE_BOARD_SIZE {
+            return None;
+        }
+        let mut board = Self::with_rules(rules);
+        for &slot in &position.walls {
+            let index = board.wall_table.get_index(slot)?;
+            board.place_wall_unsafe(index).ok()?;
+        }
+        let players = [TwoPlayerIndices::White, TwoPlayerIndices::Black];
+        for (index, &player) in players.iter().enumerate() {
+            let field = position.fields[index];
+            if field.column >= Self::AVERAGE_BOARD_SIZE || field.row >= Self::AVERAGE_BOARD_SIZE {
+                return None;
+            }
+            let data = board.get_player_data_mut(player);
+            data.current_field = field;
+            data.wall_count = position.wall_counts[index];
+        }
+        for &player in &players {
+            let shortest_paths = board.compute_shortest_paths(player);
+            if !shortest_paths.is_reachable() {
+                return None;
+            }
+            board.get_player_data_mut(player).shortest_paths = shortest_paths;
+        }
+        Some(board)
+    }
+    pub fn get_wall_table(&self) -> &WallSlotTable {
+        self.wall_table
+    }
+    fn get_storage_index(player: TwoPlayerIndices) -> usize {
+        match player {
+            TwoPlayerIndices::White => 1,
+            TwoPlayerIndices::Black => 0,
+        }
     }
 }
@@ -0,0 +1,36 @@
+use super::*;
+
+impl WallPositionTrait<usize> for [WallPlaced; 2 * 10 * (10 - 1)] {
+    fn new() -> Self {
+        [WallPlaced::IsEmpty; 2 * 10 * (10 - 1)]
+    }
+    fn at(&self, column: usize, row: usize, is_left_or_right: bool) -> WallPlaced {
+        if is_left_or_right {
+            self[row + 10 * column]
+        } else {
+            self[column + 10 * row + 10 * (10 - 1)]
+        }
+    }
+    fn at_mut(&mut self, column: usize, row: usize, is_left_or_right: bool) -> &mut WallPlaced {
+        if is_left_or_right {
+            &mut self[row + 10 * column]
+        } else {
+            &mut self[column + 10 * row + 10 * (10 - 1)]
+        }
+    }
+}
+
+impl WallCrosingTrait<usize> for [WallCrossing; (10 - 1) * (10 - 1)] {
+    fn new() -> Self {
+        [WallCrossing::IsEmpty; (10 - 1) * (10 - 1)]
+    }
+    fn at(&self, column: usize, row: usize) -> WallCrossing {
+        self[column * (10 - 1) + row]
+    }
+    fn at_mut(&mut self, column: usize, row: usize) -> &mut WallCrossing {
+        &mut self[column * (10 - 1) + row]
+    }
+}
+
+pub type SquareBoard10 =
+    SquareBoard<usize, Usize10, [WallPlaced; 2 * 10 * (10 - 1)], [WallCrossing; (10 - 1) * (10 - 1)]>;
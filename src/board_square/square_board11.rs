@@ -0,0 +1,36 @@
+use super::*;
+
+impl WallPositionTrait<usize> for [WallPlaced; 2 * 11 * (11 - 1)] {
+    fn new() -> Self {
+        [WallPlaced::IsEmpty; 2 * 11 * (11 - 1)]
+    }
+    fn at(&self, column: usize, row: usize, is_left_or_right: bool) -> WallPlaced {
+        if is_left_or_right {
+            self[row + 11 * column]
+        } else {
+            self[column + 11 * row + 11 * (11 - 1)]
+        }
+    }
+    fn at_mut(&mut self, column: usize, row: usize, is_left_or_right: bool) -> &mut WallPlaced {
+        if is_left_or_right {
+            &mut self[row + 11 * column]
+        } else {
+            &mut self[column + 11 * row + 11 * (11 - 1)]
+        }
+    }
+}
+
+impl WallCrosingTrait<usize> for [WallCrossing; (11 - 1) * (11 - 1)] {
+    fn new() -> Self {
+        [WallCrossing::IsEmpty; (11 - 1) * (11 - 1)]
+    }
+    fn at(&self, column: usize, row: usize) -> WallCrossing {
+        self[column * (11 - 1) + row]
+    }
+    fn at_mut(&mut self, column: usize, row: usize) -> &mut WallCrossing {
+        &mut self[column * (11 - 1) + row]
+    }
+}
+
+pub type SquareBoard11 =
+    SquareBoard<usize, Usize11, [WallPlaced; 2 * 11 * (11 - 1)], [WallCrossing; (11 - 1) * (11 - 1)]>;
@@ -1,22 +1,21 @@
 use super::*;
-use super::type_level_integers::*;
 
 impl WallPositionTrait<usize> for [WallPlaced; 2 * 3 * (3 - 1)] {
     fn new() -> Self {
         [WallPlaced::IsEmpty; 2 * 3 * (3 - 1)]
     }
-    fn at(&self, row: usize, column: usize, is_left_or_right: bool) -> WallPlaced {
+    fn at(&self, column: usize, row: usize, is_left_or_right: bool) -> WallPlaced {
         if is_left_or_right {
-            self[column + 3 * row]
+            self[row + 3 * column]
         } else {
-            self[row + 3 * column + 3 * (3 - 1)]
+            self[column + 3 * row + 3 * (3 - 1)]
         }
     }
-    fn at_mut(&mut self, row: usize, column: usize, is_left_or_right: bool) -> &mut WallPlaced {
+    fn at_mut(&mut self, column: usize, row: usize, is_left_or_right: bool) -> &mut WallPlaced {
         if is_left_or_right {
-            &mut self[column + 3 * row]
+            &mut self[row + 3 * column]
         } else {
-            &mut self[row + 3 * column + 3 * (3 - 1)]
+            &mut self[column + 3 * row + 3 * (3 - 1)]
         }
     }
 }
@@ -25,11 +24,11 @@ impl WallCrosingTrait<usize> for [WallCrossing; (3 - 1) * (3 - 1)] {
     fn new() -> Self {
         [WallCrossing::IsEmpty; (3 - 1) * (3 - 1)]
     }
-    fn at(&self, row: usize, column: usize) -> WallCrossing {
-        self[row * (3 - 1) + column]
+    fn at(&self, column: usize, row: usize) -> WallCrossing {
+        self[column * (3 - 1) + row]
     }
-    fn at_mut(&mut self, row: usize, column: usize) -> &mut WallCrossing {
-        &mut self[row * (3 - 1) + column]
+    fn at_mut(&mut self, column: usize, row: usize) -> &mut WallCrossing {
+        &mut self[column * (3 - 1) + row]
     }
 }
 
@@ -0,0 +1,36 @@
+use super::*;
+
+impl WallPositionTrait<usize> for [WallPlaced; 2 * 6 * (6 - 1)] {
+    fn new() -> Self {
+        [WallPlaced::IsEmpty; 2 * 6 * (6 - 1)]
+    }
+    fn at(&self, column: usize, row: usize, is_left_or_right: bool) -> WallPlaced {
+        if is_left_or_right {
+            self[row + 6 * column]
+        } else {
+            self[column + 6 * row + 6 * (6 - 1)]
+        }
+    }
+    fn at_mut(&mut self, column: usize, row: usize, is_left_or_right: bool) -> &mut WallPlaced {
+        if is_left_or_right {
+            &mut self[row + 6 * column]
+        } else {
+            &mut self[column + 6 * row + 6 * (6 - 1)]
+        }
+    }
+}
+
+impl WallCrosingTrait<usize> for [WallCrossing; (6 - 1) * (6 - 1)] {
+    fn new() -> Self {
+        [WallCrossing::IsEmpty; (6 - 1) * (6 - 1)]
+    }
+    fn at(&self, column: usize, row: usize) -> WallCrossing {
+        self[column * (6 - 1) + row]
+    }
+    fn at_mut(&mut self, column: usize, row: usize) -> &mut WallCrossing {
+        &mut self[column * (6 - 1) + row]
+    }
+}
+
+pub type SquareBoard6 =
+    SquareBoard<usize, Usize6, [WallPlaced; 2 * 6 * (6 - 1)], [WallCrossing; (6 - 1) * (6 - 1)]>;
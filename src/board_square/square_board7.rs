@@ -0,0 +1,36 @@
+use super::*;
+
+impl WallPositionTrait<usize> for [WallPlaced; 2 * 7 * (7 - 1)] {
+    fn new() -> Self {
+        [WallPlaced::IsEmpty; 2 * 7 * (7 - 1)]
+    }
+    fn at(&self, column: usize, row: usize, is_left_or_right: bool) -> WallPlaced {
+        if is_left_or_right {
+            self[row + 7 * column]
+        } else {
+            self[column + 7 * row + 7 * (7 - 1)]
+        }
+    }
+    fn at_mut(&mut self, column: usize, row: usize, is_left_or_right: bool) -> &mut WallPlaced {
+        if is_left_or_right {
+            &mut self[row + 7 * column]
+        } else {
+            &mut self[column + 7 * row + 7 * (7 - 1)]
+        }
+    }
+}
+
+impl WallCrosingTrait<usize> for [WallCrossing; (7 - 1) * (7 - 1)] {
+    fn new() -> Self {
+        [WallCrossing::IsEmpty; (7 - 1) * (7 - 1)]
+    }
+    fn at(&self, column: usize, row: usize) -> WallCrossing {
+        self[column * (7 - 1) + row]
+    }
+    fn at_mut(&mut self, column: usize, row: usize) -> &mut WallCrossing {
+        &mut self[column * (7 - 1) + row]
+    }
+}
+
+pub type SquareBoard7 =
+    SquareBoard<usize, Usize7, [WallPlaced; 2 * 7 * (7 - 1)], [WallCrossing; (7 - 1) * (7 - 1)]>;
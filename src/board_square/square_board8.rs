@@ -0,0 +1,36 @@
+use super::*;
+
+impl WallPositionTrait<usize> for [WallPlaced; 2 * 8 * (8 - 1)] {
+    fn new() -> Self {
+        [WallPlaced::IsEmpty; 2 * 8 * (8 - 1)]
+    }
+    fn at(&self, column: usize, row: usize, is_left_or_right: bool) -> WallPlaced {
+        if is_left_or_right {
+            self[row + 8 * column]
+        } else {
+            self[column + 8 * row + 8 * (8 - 1)]
+        }
+    }
+    fn at_mut(&mut self, column: usize, row: usize, is_left_or_right: bool) -> &mut WallPlaced {
+        if is_left_or_right {
+            &mut self[row + 8 * column]
+        } else {
+            &mut self[column + 8 * row + 8 * (8 - 1)]
+        }
+    }
+}
+
+impl WallCrosingTrait<usize> for [WallCrossing; (8 - 1) * (8 - 1)] {
+    fn new() -> Self {
+        [WallCrossing::IsEmpty; (8 - 1) * (8 - 1)]
+    }
+    fn at(&self, column: usize, row: usize) -> WallCrossing {
+        self[column * (8 - 1) + row]
+    }
+    fn at_mut(&mut self, column: usize, row: usize) -> &mut WallCrossing {
+        &mut self[column * (8 - 1) + row]
+    }
+}
+
+pub type SquareBoard8 =
+    SquareBoard<usize, Usize8, [WallPlaced; 2 * 8 * (8 - 1)], [WallCrossing; (8 - 1) * (8 - 1)]>;
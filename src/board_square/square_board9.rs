@@ -0,0 +1,36 @@
+use super::*;
+
+impl WallPositionTrait<usize> for [WallPlaced; 2 * 9 * (9 - 1)] {
+    fn new() -> Self {
+        [WallPlaced::IsEmpty; 2 * 9 * (9 - 1)]
+    }
+    fn at(&self, column: usize, row: usize, is_left_or_right: bool) -> WallPlaced {
+        if is_left_or_right {
+            self[row + 9 * column]
+        } else {
+            self[column + 9 * row + 9 * (9 - 1)]
+        }
+    }
+    fn at_mut(&mut self, column: usize, row: usize, is_left_or_right: bool) -> &mut WallPlaced {
+        if is_left_or_right {
+            &mut self[row + 9 * column]
+        } else {
+            &mut self[column + 9 * row + 9 * (9 - 1)]
+        }
+    }
+}
+
+impl WallCrosingTrait<usize> for [WallCrossing; (9 - 1) * (9 - 1)] {
+    fn new() -> Self {
+        [WallCrossing::IsEmpty; (9 - 1) * (9 - 1)]
+    }
+    fn at(&self, column: usize, row: usize) -> WallCrossing {
+        self[column * (9 - 1) + row]
+    }
+    fn at_mut(&mut self, column: usize, row: usize) -> &mut WallCrossing {
+        &mut self[column * (9 - 1) + row]
+    }
+}
+
+pub type SquareBoard9 =
+    SquareBoard<usize, Usize9, [WallPlaced; 2 * 9 * (9 - 1)], [WallCrossing; (9 - 1) * (9 - 1)]>;
@@ -32,3 +32,9 @@ pub type Usize2 = UsizeNext<Usize1>;
 pub type Usize3 = UsizeNext<Usize2>;
 pub type Usize4 = UsizeNext<Usize3>;
 pub type Usize5 = UsizeNext<Usize4>;
+pub type Usize6 = UsizeNext<Usize5>;
+pub type Usize7 = UsizeNext<Usize6>;
+pub type Usize8 = UsizeNext<Usize7>;
+pub type Usize9 = UsizeNext<Usize8>;
+pub type Usize10 = UsizeNext<Usize9>;
+pub type Usize11 = UsizeNext<Usize10>;
@@ -0,0 +1,42 @@
+use std::fmt;
+use super::*;
+
+/// Shortest path lengths of both players before and after a wall, see `BoardTrait::most_damaging_walls`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct WallEvaluation<WallSlotType = WallSlot> {
+    pub slot: WallSlotType,
+    pub opponent_length_before: usize,
+    pub opponent_length_after: usize,
+    pub own_length_before: usize,
+    pub own_length_after: usize,
+}
+
+impl<WallSlotType> WallEvaluation<WallSlotType> {
+    /// Steps the wall adds to the opponent's shortest path.
+    pub fn get_opponent_delay(&self) -> usize {
+        self.opponent_length_after - self.opponent_length_before
+    }
+    /// Steps the wall adds to the own shortest path.
+    pub fn get_own_delay(&self) -> usize {
+        self.own_length_after - self.own_length_before
+    }
+    /// Opponent's delay minus own delay, the walls are ranked by this.
+    pub fn get_gain(&self) -> isize {
+        self.get_opponent_delay() as isize - self.get_own_delay() as isize
+    }
+}
+
+impl<WallSlotType: fmt::Display> fmt::Display for WallEvaluation<WallSlotType> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "wall {}: opponent {} -> {}, own {} -> {}, gain {}",
+            self.slot,
+            self.opponent_length_before,
+            self.opponent_length_after,
+            self.own_length_before,
+            self.own_length_after,
+            self.get_gain()
+        )
+    }
+}
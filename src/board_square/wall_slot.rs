@@ -0,0 +1,177 @@
+use std::fmt;
+use std::ops::Add;
+use num_traits::One;
+use super::*;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum WallOrientation {
+    /// lies above two fields next to each other, blocks up and down moves
+    Horizontal,
+    /// lies right of two fields above each other, blocks left and right moves
+    Vertical,
+}
+impl fmt::Display for WallOrientation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            WallOrientation::Horizontal => write!(f, "horizontal"),
+            WallOrientation::Vertical => write!(f, "vertical"),
+        }
+    }
+}
+
+/// Canonical address of a wall: the lower left of the four fields around its center and its orientation.
+///
+/// Every wall has exactly one `WallSlot`, whereas `BoardTrait::place_wall` accepts four
+/// `(field, direction, wall_direction)` triples per wall.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct WallSlot<T: Clone + Copy + PartialEq = usize> {
+    pub anchor: FieldIndexSquare<T>,
+    pub orientation: WallOrientation,
+}
+
+impl<T: Clone + Copy + PartialEq> WallSlot<T> {
+    pub fn new(anchor: FieldIndexSquare<T>, orientation: WallOrientation) -> Self {
+        WallSlot {
+            anchor,
+            orientation,
+        }
+    }
+    /// Slot of the wall whose first segment is at `lower_left_field`, as stored by the board.
+    pub fn from_lower_left(lower_left_field: FieldIndexSquare<T>, is_left_or_right: bool) -> Self {
+        WallSlot {
+            anchor: lower_left_field,
+            orientation: if is_left_or_right {
+                WallOrientation::Vertical
+            } else {
+                WallOrientation::Horizontal
+            },
+        }
+    }
+    pub fn is_left_or_right(&self) -> bool {
+        self.orientation == WallOrientation::Vertical
+    }
+    /// The triple understood by `BoardTrait::place_wall` which places this wall.
+    pub fn to_placement(&self) -> (FieldIndexSquare<T>, DirectionsSquare, WallDirections) {
+        match self.orientation {
+            WallOrientation::Vertical => (self.anchor, DirectionsSquare::Right, WallDirections::Left),
+            WallOrientation::Horizontal => (self.anchor, DirectionsSquare::Up, WallDirections::Right),
+        }
+    }
+}
+
+impl<T: Clone + Copy + PartialEq + Add<T, Output = T> + One> WallSlot<T> {
+    /// The two wall segments covered by the slot, as `(field, is_left_or_right)`.
+    pub fn get_segments(&self) -> [(FieldIndexSquare<T>, bool); 2] {
+        let second_field = match self.orientation {
+            WallOrientation::Vertical => FieldIndexSquare {
+                column: self.anchor.column,
+                row: self.anchor.row + One::one(),
+            },
+            WallOrientation::Horizontal => FieldIndexSquare {
+                column: self.anchor.column + One::one(),
+                row: self.anchor.row,
+            },
+        };
+        let is_left_or_right = self.is_left_or_right();
+        [(self.anchor, is_left_or_right), (second_field, is_left_or_right)]
+    }
+    /// The two steps blocked by the wall, the steps in opposite direction are blocked as well.
+    pub fn get_blocked_steps(&self) -> [(FieldIndexSquare<T>, DirectionsSquare); 2] {
+        let direction = match self.orientation {
+            WallOrientation::Vertical => DirectionsSquare::Right,
+            WallOrientation::Horizontal => DirectionsSquare::Up,
+        };
+        let [(first_field, _), (second_field, _)] = self.get_segments();
+        [(first_field, direction), (second_field, direction)]
+    }
+}
+
+impl WallSlot<usize> {
+    /// All slots of a board with `board_size` fields per side, both orientations per anchor.
+    pub fn all(board_size: usize) -> WallSlots {
+        WallSlots {
+            board_size,
+            index: 0,
+        }
+    }
+    pub fn is_on_board(&self, board_size: usize) -> bool {
+        self.anchor.column + 1 < board_size && self.anchor.row + 1 < board_size
+    }
+    /// Both walls share a segment, this includes equal slots.
+    pub fn overlaps(&self, other: &WallSlot) -> bool {
+        if self.orientation != other.orientation {
+            return false;
+        }
+        let (along, other_along, across, other_across) = match self.orientation {
+            WallOrientation::Vertical => (
+                self.anchor.row,
+                other.anchor.row,
+                self.anchor.column,
+                other.anchor.column,
+            ),
+            WallOrientation::Horizontal => (
+                self.anchor.column,
+                other.anchor.column,
+                self.anchor.row,
+                other.anchor.row,
+            ),
+        };
+        across == other_across && along + 1 >= other_along && other_along + 1 >= along
+    }
+    /// Both walls have the same center but different orientations.
+    pub fn crosses(&self, other: &WallSlot) -> bool {
+        self.anchor == other.anchor && self.orientation != other.orientation
+    }
+    /// The walls cannot both be placed.
+    pub fn conflicts_with(&self, other: &WallSlot) -> bool {
+        self.overlaps(other) || self.crosses(other)
+    }
+}
+
+impl<T: Clone + Copy + PartialEq + fmt::Display> fmt::Display for WallSlot<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.anchor, self.orientation)
+    }
+}
+
+impl<T: Clone + Copy + PartialEq> From<(FieldIndexSquare<T>, bool)> for WallSlot<T> {
+    fn from((lower_left_field, is_left_or_right): (FieldIndexSquare<T>, bool)) -> Self {
+        WallSlot::from_lower_left(lower_left_field, is_left_or_right)
+    }
+}
+
+/// Iterator over all wall slots of a board, see `WallSlot::all`.
+#[derive(Clone, Debug)]
+pub struct WallSlots {
+    board_size: usize,
+    index: usize,
+}
+impl Iterator for WallSlots {
+    type Item = WallSlot;
+    fn next(&mut self) -> Option<WallSlot> {
+        let anchors_per_column = self.board_size.saturating_sub(1);
+        let anchor_index = self.index / 2;
+        if anchor_index >= anchors_per_column * anchors_per_column {
+            return None;
+        }
+        let orientation = if self.index & 1 == 0 {
+            WallOrientation::Vertical
+        } else {
+            WallOrientation::Horizontal
+        };
+        self.index += 1;
+        Some(WallSlot {
+            anchor: FieldIndexSquare {
+                column: anchor_index / anchors_per_column,
+                row: anchor_index % anchors_per_column,
+            },
+            orientation,
+        })
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let anchors_per_column = self.board_size.saturating_sub(1);
+        let remaining = (2 * anchors_per_column * anchors_per_column).saturating_sub(self.index);
+        (remaining, Some(remaining))
+    }
+}
+impl ExactSizeIterator for WallSlots {}
@@ -0,0 +1,107 @@
+use super::*;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+/// Everything a wall slot touches, see `WallSlotTable`.
+#[derive(Clone, Debug)]
+pub struct WallSlotEntry {
+    pub slot: WallSlot,
+    /// indices of the other slots which overlap or cross this one
+    pub conflicting: Vec<usize>,
+    /// the wall segments, as `(field, is_left_or_right)`
+    pub segments: [(FieldIndexSquare<usize>, bool); 2],
+    /// the steps between neighbouring fields the wall blocks, in both directions
+    pub cut_steps: [(FieldIndexSquare<usize>, DirectionsSquare); 4],
+}
+
+/// Wall slot relations of one board size, built once so that legality checks are lookups.
+///
+/// Slots are numbered in the order of `WallSlot::all`.
+#[derive(Clone, Debug)]
+pub struct WallSlotTable {
+    board_size: usize,
+    entries: Vec<WallSlotEntry>,
+}
+
+impl WallSlotTable {
+    pub fn new(board_size: usize) -> Self {
+        let slots = WallSlot::all(board_size).collect::<Vec<_>>();
+        let entries = slots
+            .iter()
+            .map(|&slot| {
+                let conflicting = slots
+                    .iter()
+                    .enumerate()
+                    .filter(|&(_, other)| *other != slot && slot.conflicts_with(other))
+                    .map(|(index, _)| index)
+                    .collect();
+                let [first_step, second_step] = slot.get_blocked_steps();
+                let opposite = |(field, direction): (FieldIndexSquare<usize>, DirectionsSquare)| {
+                    let next_field = match direction {
+                        DirectionsSquare::Right => FieldIndexSquare {
+                            column: field.column + 1,
+                            row: field.row,
+                        },
+                        _ => FieldIndexSquare {
+                            column: field.column,
+                            row: field.row + 1,
+                        },
+                    };
+                    (next_field, direction.get_opposite())
+                };
+                WallSlotEntry {
+                    slot,
+                    conflicting,
+                    segments: slot.get_segments(),
+                    cut_steps: [
+                        first_step,
+                        opposite(first_step),
+                        second_step,
+                        opposite(second_step),
+                    ],
+                }
+            })
+            .collect();
+        WallSlotTable {
+            board_size,
+            entries,
+        }
+    }
+    /// The table of the board size, built on first use and shared by all boards of the size.
+    pub fn get_shared(board_size: usize) -> &'static Self {
+        static TABLES: OnceLock<Mutex<HashMap<usize, &'static WallSlotTable>>> = OnceLock::new();
+        let mut tables = TABLES
+            .get_or_init(Default::default)
+            .lock()
+            .expect("wall table lock");
+        tables
+            .entry(board_size)
+            .or_insert_with(|| Box::leak(Box::new(WallSlotTable::new(board_size))))
+    }
+    pub fn get_board_size(&self) -> usize {
+        self.board_size
+    }
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+    /// Position of the slot in the table, `None` if it is not on the board.
+    pub fn get_index(&self, slot: WallSlot) -> Option<usize> {
+        if !slot.is_on_board(self.board_size) {
+            return None;
+        }
+        let anchor_index = slot.anchor.column * (self.board_size - 1) + slot.anchor.row;
+        Some(match slot.orientation {
+            WallOrientation::Vertical => 2 * anchor_index,
+            WallOrientation::Horizontal => 2 * anchor_index + 1,
+        })
+    }
+    pub fn get_entry(&self, index: usize) -> &WallSlotEntry {
+        &self.entries[index]
+    }
+    pub fn get_entries(&self) -> &[WallSlotEntry] {
+        &self.entries
+    }
+}
@@ -0,0 +1,267 @@
+//! Opening book: how often each move was played in the first plies of recorded games, and how
+//! these games ended.
+//!
+//! Positions are keyed by `Position::get_canonical_hash`, so a game and its mirror image add to
+//! the same entries.
+use ai::*;
+use board_square::*;
+use record::*;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+/// Statistics of a move in a position, the results are from the view of the player moving.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct BookMove {
+    pub next_move: Move,
+    pub games: u32,
+    pub wins: u32,
+    pub losses: u32,
+}
+
+impl BookMove {
+    pub fn get_draws(&self) -> u32 {
+        self.games - self.wins - self.losses
+    }
+    /// Share of the points of the games, a draw counting half.
+    pub fn get_win_rate(&self) -> f64 {
+        (2 * self.wins + self.get_draws()) as f64 / (2 * self.games) as f64
+    }
+}
+
+/// Moves played in the positions of the first plies of recorded games.
+#[derive(Clone, PartialEq, Debug)]
+pub struct OpeningBook {
+    max_plies: usize,
+    // per canonical position hash the moves in the canonical orientation
+    positions: HashMap<u64, Vec<BookMove>>,
+}
+
+const HEADER: &str = "varidor opening book 1";
+
+impl OpeningBook {
+    /// An empty book which takes the first `max_plies` plies of each game.
+    pub fn new(max_plies: usize) -> Self {
+        OpeningBook {
+            max_plies,
+            positions: HashMap::new(),
+        }
+    }
+    pub fn get_max_plies(&self) -> usize {
+        self.max_plies
+    }
+    /// Number of positions in the book.
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+    pub fn add_record<Board>(&mut self, record: &GameRecord) -> Result<(), RecordError>
+    where
+        Board: PositionBoard + Clone,
+    {
+        let boards = record.replay::<Board>()?;
+        for (index, (board, &next_move)) in boards.iter().zip(&record.moves).enumerate() {
+            if index >= self.max_plies {
+                break;
+            }
+            let player = record.get_move_player(index);
+            let (hash, canonical_move) = get_canonical(board, player, next_move);
+            let moves = self.positions.entry(hash).or_default();
+            let position = match moves.iter().position(|x| x.next_move == canonical_move) {
+                Some(position) => position,
+                None => {
+                    moves.push(BookMove {
+                        next_move: canonical_move,
+                        games: 0,
+                        wins: 0,
+                        losses: 0,
+                    });
+                    moves.len() - 1
+                }
+            };
+            let book_move = &mut moves[position];
+            book_move.games += 1;
+            match record.winner {
+                Some(winner) if winner == player => book_move.wins += 1,
+                Some(_) => book_move.losses += 1,
+                None => {}
+            }
+        }
+        Ok(())
+    }
+    /// Adds the records of all files in the directory which are played on `Board`, returns
+    /// the number of records added.
+    pub fn add_directory<Board, P>(&mut self, directory: P) -> io::Result<usize>
+    where
+        Board: PositionBoard + Clone,
+        P: AsRef<Path>,
+    {
+        let mut count = 0;
+        for record in read_records_from_directory(directory)? {
+            if record.board_size == Board::AVERAGE_BOARD_SIZE {
+                self.add_record::<Board>(&record)
+                    .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+    /// The moves known in the position in its orientation, the most played first.
+    pub fn explore<Board>(&self, board: &Board, player: TwoPlayerIndices) -> Vec<BookMove>
+    where
+        Board: PositionBoard,
+    {
+        let (canonical, is_mirrored) = board.get_position(player).get_canonical();
+        let mut moves = match self.positions.get(&canonical.get_hash()) {
+            Some(moves) => moves.clone(),
+            None => return vec![],
+        };
+        if is_mirrored {
+            for book_move in &mut moves {
+                book_move.next_move = book_move.next_move.mirrored(Board::AVERAGE_BOARD_SIZE);
+            }
+        }
+        moves.sort_by(|a, b| {
+            b.games.cmp(&a.games).then(
+                b.get_win_rate()
+                    .partial_cmp(&a.get_win_rate())
+                    .unwrap_or(Ordering::Equal),
+            )
+        });
+        moves
+    }
+    /// Draws one of the legal book moves, weighted by the points they scored plus one.
+    pub fn choose_move<Board>(
+        &self,
+        board: &Board,
+        player: TwoPlayerIndices,
+        random: &mut Random,
+    ) -> Option<Move>
+    where
+        Board: PositionBoard + Clone,
+    {
+        let legal_moves = board.get_legal_moves(player);
+        let candidates = self.explore(board, player)
+            .into_iter()
+            .filter(|x| legal_moves.contains(&x.next_move))
+            .map(|x| (x.next_move, u64::from(2 * x.wins + x.get_draws() + 1)))
+            .collect::<Vec<_>>();
+        let total = candidates.iter().map(|&(_, weight)| weight).sum::<u64>();
+        if total == 0 {
+            return None;
+        }
+        let mut drawn = random.below(total);
+        for (next_move, weight) in candidates {
+            if drawn < weight {
+                return Some(next_move);
+            }
+            drawn -= weight;
+        }
+        None
+    }
+    /// Writes the book as text, one line `hash games wins losses move` per move.
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writeln!(writer, "{}", HEADER)?;
+        writeln!(writer, "max_plies {}", self.max_plies)?;
+        let mut hashes = self.positions.keys().collect::<Vec<_>>();
+        hashes.sort();
+        for hash in hashes {
+            for book_move in &self.positions[hash] {
+                writeln!(
+                    writer,
+                    "{:016x} {} {} {} {}",
+                    hash, book_move.games, book_move.wins, book_move.losses, book_move.next_move
+                )?;
+            }
+        }
+        Ok(())
+    }
+    pub fn read_from<R: BufRead>(reader: R) -> io::Result<Self> {
+        let invalid = |line: &str| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("not an opening book line: '{}'", line),
+            )
+        };
+        let mut lines = reader.lines();
+        let line = lines.next().unwrap_or_else(|| Ok(String::new()))?;
+        if line != HEADER {
+            return Err(invalid(&line));
+        }
+        let line = lines.next().unwrap_or_else(|| Ok(String::new()))?;
+        let mut book = match line.strip_prefix("max_plies ").map(|x| x.parse()) {
+            Some(Ok(max_plies)) => OpeningBook::new(max_plies),
+            _ => return Err(invalid(&line)),
+        };
+        for line in lines {
+            let line = line?;
+            let parts = line.splitn(5, ' ').collect::<Vec<_>>();
+            if parts.len() != 5 {
+                return Err(invalid(&line));
+            }
+            let number = |text: &str| text.parse::<u32>().ok();
+            let book_move = match (
+                u64::from_str_radix(parts[0], 16),
+                number(parts[1]),
+                number(parts[2]),
+                number(parts[3]),
+                parts[4].parse(),
+            ) {
+                (Ok(hash), Some(games), Some(wins), Some(losses), Ok(next_move))
+                    if wins.checked_add(losses).is_some_and(|x| x <= games) =>
+                {
+                    (
+                        hash,
+                        BookMove {
+                            next_move,
+                            games,
+                            wins,
+                            losses,
+                        },
+                    )
+                }
+                _ => return Err(invalid(&line)),
+            };
+            book.positions
+                .entry(book_move.0)
+                .or_default()
+                .push(book_move.1);
+        }
+        Ok(book)
+    }
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_to(&mut writer)?;
+        writer.flush()
+    }
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::read_from(BufReader::new(File::open(path)?))
+    }
+}
+
+// hash of the canonical position and the move in its orientation; in positions which equal their
+// mirror image a move and its mirror image are the same and the smaller one is kept
+fn get_canonical<Board>(board: &Board, player: TwoPlayerIndices, next_move: Move) -> (u64, Move)
+where
+    Board: PositionBoard,
+{
+    let position = board.get_position(player);
+    let (canonical, is_mirrored) = position.get_canonical();
+    let mirrored_move = next_move.mirrored(Board::AVERAGE_BOARD_SIZE);
+    let canonical_move = if canonical == canonical.mirrored() {
+        if mirrored_move.to_string() < next_move.to_string() {
+            mirrored_move
+        } else {
+            next_move
+        }
+    } else if is_mirrored {
+        mirrored_move
+    } else {
+        next_move
+    };
+    (canonical.get_hash(), canonical_move)
+}
@@ -0,0 +1,182 @@
+//! Game clocks: every player has a main time, optionally a Fischer increment added after each
+//! move and a byoyomi period which every move may use once the main time is over.
+//!
+//! Time controls are written as `<main>[+<increment>][b<byoyomi>]` in seconds, e.g. `300` for
+//! sudden death, `180+2` for Fischer and `600b30` for byoyomi.
+use board_square::*;
+use protocol::*;
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// How much time the players get, in seconds.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct TimeControl {
+    pub main_time: f64,
+    /// added to the main time after every move
+    pub increment: f64,
+    /// time every move may take once the main time is over
+    pub byoyomi: f64,
+}
+
+impl TimeControl {
+    /// The game is lost once the main time is over.
+    pub fn sudden_death(main_time: f64) -> Self {
+        TimeControl {
+            main_time,
+            increment: 0.,
+            byoyomi: 0.,
+        }
+    }
+    pub fn fischer(main_time: f64, increment: f64) -> Self {
+        TimeControl {
+            increment,
+            ..Self::sudden_death(main_time)
+        }
+    }
+    pub fn byoyomi(main_time: f64, byoyomi: f64) -> Self {
+        TimeControl {
+            byoyomi,
+            ..Self::sudden_death(main_time)
+        }
+    }
+}
+
+impl fmt::Display for TimeControl {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.main_time)?;
+        if self.increment > 0. {
+            write!(f, "+{}", self.increment)?;
+        }
+        if self.byoyomi > 0. {
+            write!(f, "b{}", self.byoyomi)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for TimeControl {
+    type Err = ParseError;
+    fn from_str(text: &str) -> Result<Self, ParseError> {
+        let error = || ParseError {
+            text: text.to_string(),
+            expected: "time control",
+        };
+        let parse_seconds = |x: &str| match x.parse::<f64>() {
+            Ok(seconds) if seconds >= 0. && seconds.is_finite() => Ok(seconds),
+            _ => Err(error()),
+        };
+        let text = text.trim();
+        let (rest, byoyomi) = match text.split_once('b') {
+            Some((rest, byoyomi)) => (rest, parse_seconds(byoyomi)?),
+            None => (text, 0.),
+        };
+        let (main_time, increment) = match rest.split_once('+') {
+            Some((main_time, increment)) => (parse_seconds(main_time)?, parse_seconds(increment)?),
+            None => (parse_seconds(rest)?, 0.),
+        };
+        Ok(TimeControl {
+            main_time,
+            increment,
+            byoyomi,
+        })
+    }
+}
+
+fn get_index(player: TwoPlayerIndices) -> usize {
+    match player {
+        TwoPlayerIndices::White => 0,
+        TwoPlayerIndices::Black => 1,
+    }
+}
+
+/// The clocks of both players, only the clock of the player to move runs.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Clock {
+    pub time_control: TimeControl,
+    // [White, Black]
+    main_time: [f64; 2],
+    // byoyomi used by the current move
+    period_used: f64,
+    flagged: Option<TwoPlayerIndices>,
+}
+
+impl Clock {
+    pub fn new(time_control: TimeControl) -> Self {
+        Clock {
+            time_control,
+            main_time: [time_control.main_time; 2],
+            period_used: 0.,
+            flagged: None,
+        }
+    }
+    /// Runs the clock of the player to move, returns whether the player is out of time.
+    pub fn tick(&mut self, player: TwoPlayerIndices, seconds: f64) -> bool {
+        if self.flagged.is_some() {
+            return true;
+        }
+        let main_time = &mut self.main_time[get_index(player)];
+        *main_time -= seconds;
+        if *main_time < 0. {
+            self.period_used -= *main_time;
+            *main_time = 0.;
+        }
+        if *main_time == 0. && self.period_used >= self.time_control.byoyomi {
+            self.flagged = Some(player);
+        }
+        self.flagged.is_some()
+    }
+    /// Ends the move of the player: adds the increment and starts a new byoyomi period.
+    pub fn press(&mut self, player: TwoPlayerIndices) {
+        if self.flagged.is_none() {
+            self.main_time[get_index(player)] += self.time_control.increment;
+            self.period_used = 0.;
+        }
+    }
+    /// The player who ran out of time, if any.
+    pub fn get_flagged(&self) -> Option<TwoPlayerIndices> {
+        self.flagged
+    }
+    /// Main time left of the player.
+    pub fn get_main_time(&self, player: TwoPlayerIndices) -> f64 {
+        self.main_time[get_index(player)]
+    }
+    /// Time the player may still use for the move, if it is the turn of the player.
+    pub fn get_time_left(&self, player: TwoPlayerIndices, to_move: TwoPlayerIndices) -> f64 {
+        let byoyomi = if player == to_move {
+            (self.time_control.byoyomi - self.period_used).max(0.)
+        } else {
+            self.time_control.byoyomi
+        };
+        self.get_main_time(player) + byoyomi
+    }
+    /// Whether the main time of the player is over and the moves are played in byoyomi.
+    pub fn is_in_byoyomi(&self, player: TwoPlayerIndices) -> bool {
+        self.time_control.byoyomi > 0. && self.get_main_time(player) == 0.
+    }
+    /// The clocks as sent to an engine with `go`, times too long for its milliseconds are cut.
+    pub fn get_go_limits(&self, to_move: TwoPlayerIndices) -> GoLimits {
+        let longest = Duration::from_millis(u64::MAX);
+        let duration = |seconds: f64| {
+            let duration = Duration::try_from_secs_f64(seconds.max(0.)).unwrap_or(longest);
+            Some(duration.min(longest))
+        };
+        let increment = match self.time_control.increment {
+            increment if increment > 0. => duration(increment),
+            _ => None,
+        };
+        GoLimits {
+            white_time: duration(self.get_main_time(TwoPlayerIndices::White)),
+            black_time: duration(self.get_main_time(TwoPlayerIndices::Black)),
+            white_increment: increment,
+            black_increment: increment,
+            byoyomi: match self.time_control.byoyomi {
+                byoyomi if byoyomi > 0. => {
+                    duration(self.get_time_left(to_move, to_move) - self.get_main_time(to_move))
+                }
+                _ => None,
+            },
+            ..GoLimits::default()
+        }
+    }
+}
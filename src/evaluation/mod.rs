@@ -0,0 +1,145 @@
+//! Static evaluation of positions for the search, see `Evaluator`.
+mod tuner;
+pub use self::tuner::*;
+
+use board_square::*;
+use std::fmt;
+use std::str::FromStr;
+
+/// Score of a position for the player to move without searching, positive if it favours them.
+///
+/// The search plays with scores far below `ai::WIN_SCORE`, which marks decided games.
+pub trait Evaluator<Board> {
+    fn evaluate(&self, board: &Board, player: TwoPlayerIndices) -> i32;
+}
+
+pub const FEATURE_COUNT: usize = 6;
+
+/// Names of the features in the order of `get_features`, as used in the text form of a
+/// `LinearEvaluator`.
+pub const FEATURE_NAMES: [&str; FEATURE_COUNT] = [
+    "path_length",
+    "path_count",
+    "walls",
+    "mobility",
+    "edge_distance",
+    "side_to_move",
+];
+
+/// Properties of the position which favour the player to move, all but the last one as the
+/// difference between the player and the opponent:
+/// * how much shorter the own shortest path is,
+/// * the logarithm of the number of shortest paths,
+/// * the remaining walls,
+/// * the possible steps,
+/// * the distance of the pawn to the nearest side of the board,
+/// * 1 for having the move.
+pub fn get_features<Board>(board: &Board, player: TwoPlayerIndices) -> [f64; FEATURE_COUNT]
+where
+    Board: PositionBoard,
+{
+    let own = get_player_features(board, player);
+    let opponent = get_player_features(board, player.next_player());
+    [
+        opponent[0] - own[0],
+        own[1] - opponent[1],
+        own[2] - opponent[2],
+        own[3] - opponent[3],
+        own[4] - opponent[4],
+        1.,
+    ]
+}
+
+// path length, logarithm of the path count, walls, steps and distance to the side
+fn get_player_features<Board>(board: &Board, player: TwoPlayerIndices) -> [f64; 5]
+where
+    Board: PositionBoard,
+{
+    let field = board.get_current_field(player);
+    let paths = board.get_player_data(player).get_shortest_paths();
+    let column = field.column;
+    let edge_distance = ::std::cmp::min(column, Board::AVERAGE_BOARD_SIZE - 1 - column);
+    [
+        paths.get_length().unwrap_or(0) as f64,
+        (paths.get_path_count().max(1) as f64).ln(),
+        board.get_wall_count(player) as f64,
+        board.get_surrounding_possible_fields(field).len() as f64,
+        edge_distance as f64,
+    ]
+}
+
+/// Weighted sum of the features of `get_features`.
+///
+/// The sum estimates the logarithm of the odds of the player to move to win, the score is the
+/// sum in hundredths.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct LinearEvaluator {
+    pub weights: [f64; FEATURE_COUNT],
+}
+
+/// Largest score of a `LinearEvaluator`.
+pub const MAX_EVALUATION: i32 = 100_000;
+
+impl Default for LinearEvaluator {
+    /// Weights tuned on self-play games of the 5x5 board.
+    fn default() -> Self {
+        LinearEvaluator {
+            weights: [1.7, 0.2, 0.2, -0.2, -0.1, 0.6],
+        }
+    }
+}
+
+impl LinearEvaluator {
+    /// The estimated logarithm of the odds of the player to move to win.
+    pub fn get_logit(&self, features: &[f64; FEATURE_COUNT]) -> f64 {
+        self.weights
+            .iter()
+            .zip(features.iter())
+            .map(|(weight, feature)| weight * feature)
+            .sum()
+    }
+}
+
+impl<Board: PositionBoard> Evaluator<Board> for LinearEvaluator {
+    fn evaluate(&self, board: &Board, player: TwoPlayerIndices) -> i32 {
+        let score = (100. * self.get_logit(&get_features(board, player))).round();
+        score.max(-MAX_EVALUATION as f64).min(MAX_EVALUATION as f64) as i32
+    }
+}
+
+impl fmt::Display for LinearEvaluator {
+    /// The weights as `name=weight` separated by spaces.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let parts = FEATURE_NAMES
+            .iter()
+            .zip(self.weights.iter())
+            .map(|(name, weight)| format!("{}={}", name, weight))
+            .collect::<Vec<_>>();
+        write!(f, "{}", parts.join(" "))
+    }
+}
+
+impl FromStr for LinearEvaluator {
+    type Err = ParseError;
+    /// Reads the form written by `Display`, features which are not given get the weight 0.
+    fn from_str(text: &str) -> Result<Self, ParseError> {
+        let mut weights = [0.; FEATURE_COUNT];
+        for part in text.split_whitespace() {
+            let error = || ParseError {
+                text: part.to_string(),
+                expected: "feature weight",
+            };
+            let mut name_and_weight = part.splitn(2, '=');
+            let name = name_and_weight.next().ok_or_else(error)?;
+            let index = FEATURE_NAMES
+                .iter()
+                .position(|&x| x == name)
+                .ok_or_else(error)?;
+            weights[index] = name_and_weight
+                .next()
+                .and_then(|x| x.parse().ok())
+                .ok_or_else(error)?;
+        }
+        Ok(LinearEvaluator { weights })
+    }
+}
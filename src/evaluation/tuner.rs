@@ -0,0 +1,109 @@
+use super::*;
+use record::*;
+
+/// A position of a recorded game: its features and the result for the player to move, 1 for a
+/// win, 0 for a loss and 0.5 if the record has no winner.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct TuningSample {
+    pub features: [f64; FEATURE_COUNT],
+    pub result: f64,
+}
+
+/// Settings of the gradient descent of `Tuner::tune`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Tuner {
+    pub iterations: usize,
+    pub learning_rate: f64,
+}
+
+impl Default for Tuner {
+    fn default() -> Self {
+        Tuner {
+            iterations: 1000,
+            learning_rate: 1.,
+        }
+    }
+}
+
+/// Result of `Tuner::tune`, the errors are the mean squared differences between the predicted
+/// and the actual results.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct TuningResult {
+    pub evaluator: LinearEvaluator,
+    pub error_before: f64,
+    pub error_after: f64,
+}
+
+/// All positions of the records which are played on `Board` and not yet won.
+pub fn get_tuning_samples<Board>(records: &[GameRecord]) -> Result<Vec<TuningSample>, RecordError>
+where
+    Board: PositionBoard + Clone,
+{
+    let mut samples = vec![];
+    for record in records {
+        if record.board_size != Board::AVERAGE_BOARD_SIZE {
+            continue;
+        }
+        for (index, board) in record.replay::<Board>()?.iter().enumerate() {
+            if board.get_winner().is_some() {
+                break;
+            }
+            let player = record.get_move_player(index);
+            samples.push(TuningSample {
+                features: get_features(board, player),
+                result: match record.winner {
+                    Some(winner) if winner == player => 1.,
+                    Some(_) => 0.,
+                    None => 0.5,
+                },
+            });
+        }
+    }
+    Ok(samples)
+}
+
+fn sigmoid(logit: f64) -> f64 {
+    1. / (1. + (-logit).exp())
+}
+
+/// Mean squared difference between the results predicted by the evaluator and the actual ones.
+pub fn get_prediction_error(evaluator: &LinearEvaluator, samples: &[TuningSample]) -> f64 {
+    if samples.is_empty() {
+        return 0.;
+    }
+    let total = samples
+        .iter()
+        .map(|sample| {
+            let difference = sigmoid(evaluator.get_logit(&sample.features)) - sample.result;
+            difference * difference
+        })
+        .sum::<f64>();
+    total / samples.len() as f64
+}
+
+impl Tuner {
+    /// Fits the weights by gradient descent on the prediction error, starting from `initial`.
+    pub fn tune(&self, initial: LinearEvaluator, samples: &[TuningSample]) -> TuningResult {
+        let mut evaluator = initial;
+        if !samples.is_empty() {
+            for _ in 0..self.iterations {
+                let mut gradient = [0.; FEATURE_COUNT];
+                for sample in samples {
+                    let prediction = sigmoid(evaluator.get_logit(&sample.features));
+                    let factor = (prediction - sample.result) * prediction * (1. - prediction);
+                    for (entry, feature) in gradient.iter_mut().zip(sample.features.iter()) {
+                        *entry += factor * feature;
+                    }
+                }
+                for (weight, entry) in evaluator.weights.iter_mut().zip(gradient.iter()) {
+                    *weight -= self.learning_rate * 2. * entry / samples.len() as f64;
+                }
+            }
+        }
+        TuningResult {
+            evaluator,
+            error_before: get_prediction_error(&initial, samples),
+            error_after: get_prediction_error(&evaluator, samples),
+        }
+    }
+}
@@ -0,0 +1,522 @@
+use ai::*;
+use board_square::*;
+use clock::*;
+use logbook::*;
+use protocol::*;
+use record::*;
+use std::sync::Arc;
+use std::time::Instant;
+use tablebase::*;
+
+/// A running game: the board, whose turn it is and the logbook of everything that happened.
+pub struct GameData<Board = SquareBoard5> {
+    pub board: Board,
+    pub current_player: TwoPlayerIndices,
+    pub wall_index_selected: Option<(usize, usize, bool)>,
+    pub show_wall_hints: bool,
+    /// ends the game as soon as no walls are left, with the forced winner of the race
+    pub end_decided_races: bool,
+    pub race_result: Option<RaceResult<TwoPlayerIndices>>,
+    pub logbook: Logbook,
+    pub language: Language,
+    pub seconds: f64,
+    /// runs during `add_time`, the game is lost once it is out of time
+    pub clock: Option<Clock>,
+    pub winner: Option<TwoPlayerIndices>,
+    /// the move suggested by `request_hint`, until the next ply
+    pub hint: Option<Move>,
+    /// scores the positions it contains exactly when searching for hints
+    pub tablebase: Option<Arc<Tablebase>>,
+    // the position of a game not started from the start position, see `with_position`
+    start_position: Option<Position>,
+    // the clock before every ply still on the board was played, restored by `undo`
+    clocks: Vec<Option<Clock>>,
+}
+
+/// The wall made of two pieces as selected on the page.
+///
+/// A piece `(column, row, true)` lies right of the field, `(column, row, false)` above it.
+pub fn get_wall_slot_by_pieces(
+    first_piece: (usize, usize, bool),
+    second_piece: (usize, usize, bool),
+) -> Result<WallSlot, WallPlacmentError> {
+    use std::cmp::{max, min};
+    let (column, row, dir_is_left_or_right) = first_piece;
+    let (column_before, row_before, dir_is_left_or_right_before) = second_piece;
+    let min_column = min(column, column_before);
+    let min_row = min(row, row_before);
+    let max_column = max(column, column_before);
+    let max_row = max(row, row_before);
+    let lower_left_field = FieldIndexSquare {
+        column: min_column,
+        row: min_row,
+    };
+    if dir_is_left_or_right != dir_is_left_or_right_before {
+        Err(WallPlacmentError::NotConnected)
+    } else if (dir_is_left_or_right && max_column == min_column && min_row + 1 == max_row)
+        || (!dir_is_left_or_right && max_column == min_column + 1 && min_row == max_row)
+    {
+        Ok(WallSlot::from_lower_left(lower_left_field, dir_is_left_or_right))
+    } else {
+        Err(WallPlacmentError::NotConnected)
+    }
+}
+
+/// Name of `GameData` in the public API.
+pub type Game<Board = SquareBoard5> = GameData<Board>;
+
+impl<Board> Default for GameData<Board>
+where
+    Board: BoardTrait<
+        PlayerIndexType = TwoPlayerIndices,
+        DirectionsType = DirectionsSquare,
+        FieldIndexType = FieldIndexSquare<usize>,
+        WallDirectionType = WallDirections,
+        WallSlotType = WallSlot,
+    >,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Board> GameData<Board>
+where
+    Board: BoardTrait<
+        PlayerIndexType = TwoPlayerIndices,
+        DirectionsType = DirectionsSquare,
+        FieldIndexType = FieldIndexSquare<usize>,
+        WallDirectionType = WallDirections,
+        WallSlotType = WallSlot,
+    >,
+{
+    pub fn new() -> Self {
+        Self::with_rules(Rules::default())
+    }
+    pub fn with_rules(rules: Rules) -> Self {
+        let mut logbook = Logbook::new();
+        logbook.push(
+            Timestamp {
+                ply: 0,
+                seconds: 0.,
+            },
+            LogEntry::GameStarted,
+        );
+        GameData {
+            board: Board::with_rules(rules),
+            current_player: TwoPlayerIndices::White,
+            wall_index_selected: None,
+            show_wall_hints: false,
+            end_decided_races: false,
+            race_result: None,
+            logbook,
+            language: Language::English,
+            seconds: 0.,
+            clock: None,
+            winner: None,
+            hint: None,
+            tablebase: None,
+            start_position: None,
+            clocks: vec![],
+        }
+    }
+    /// Starts the clocks of both players with the time control.
+    pub fn set_time_control(&mut self, time_control: TimeControl) {
+        self.clock = Some(Clock::new(time_control));
+    }
+    pub fn get_current_player(&self) -> TwoPlayerIndices {
+        self.current_player
+    }
+    fn next_player(&mut self) {
+        let next_player = self.get_current_player().next_player();
+        self.current_player = next_player;
+        self.hint = None;
+    }
+    pub fn is_game_over(&self) -> bool {
+        self.winner.is_some()
+    }
+    /// Advances the game time and the clock of the current player, who loses once out of time.
+    pub fn add_time(&mut self, seconds: f64) {
+        if self.is_game_over() {
+            return;
+        }
+        self.seconds += seconds;
+        let player = self.get_current_player();
+        let out_of_time = match self.clock {
+            Some(ref mut clock) => clock.tick(player, seconds),
+            None => false,
+        };
+        if out_of_time {
+            let winner = player.next_player();
+            self.winner = Some(winner);
+            self.append_logbook(LogEntry::TimeForfeit { player });
+            self.append_logbook(LogEntry::GameOver { winner });
+        }
+    }
+    /// The player who lost on time, if any.
+    pub fn get_time_forfeit(&self) -> Option<TwoPlayerIndices> {
+        self.clock.as_ref().and_then(|clock| clock.get_flagged())
+    }
+    fn press_clock(&mut self, player: TwoPlayerIndices) {
+        self.clocks.push(self.clock.clone());
+        if let Some(ref mut clock) = self.clock {
+            clock.press(player);
+        }
+    }
+    // timestamp of the ply which is currently to be played
+    fn get_timestamp(&self) -> Timestamp {
+        Timestamp {
+            ply: self.logbook.get_played_moves().len() + 1,
+            seconds: self.seconds,
+        }
+    }
+    fn append_logbook(&mut self, entry: LogEntry) {
+        let timestamp = self.get_timestamp();
+        self.logbook.push(timestamp, entry);
+    }
+    pub fn get_logbook(&self) -> &Logbook {
+        &self.logbook
+    }
+    fn check_game_over(&self) -> Result<(), GameError> {
+        match self.winner {
+            Some(winner) => Err(GameError::GameOver { winner }),
+            None => Ok(()),
+        }
+    }
+    fn reject(&mut self, error: GameError) -> Result<(), GameError> {
+        let player = self.get_current_player();
+        self.append_logbook(LogEntry::ActionRejected { player, error });
+        Err(error)
+    }
+    /// Plays a move for the current player, rejected moves are recorded in the logbook.
+    pub fn play_move(&mut self, next_move: Move) -> Result<(), GameError> {
+        match next_move {
+            Move::Step(direction) => {
+                let current_field = self.board.get_current_field(self.get_current_player());
+                match Board::get_field_in_direction(current_field, direction) {
+                    Some((field, _)) => self.move_player_by_field(field),
+                    None => self.reject_move(MoveError::BoardBoundary {
+                        field: current_field,
+                        direction,
+                    }),
+                }
+            }
+            Move::Wall(slot) => self.place_wall_at(slot),
+        }
+    }
+    pub fn move_player_by_field(&mut self, field: FieldIndexSquare<usize>) -> Result<(), GameError> {
+        if let Err(error) = self.check_game_over() {
+            return self.reject(error);
+        }
+        let player = self.get_current_player();
+        let current_field = self.board.get_current_field(player);
+        let connected_fields = Board::get_surrounding_fields(current_field);
+        let result = match connected_fields
+            .iter()
+            .find(|&&(next_field, _)| next_field == field)
+        {
+            Some(&(_, direction)) => self.board.move_player(player, direction),
+            None => Err(MoveError::FieldsNotAdjacent {
+                from: current_field,
+                to: field,
+            }),
+        };
+        if let Err(error) = result {
+            return self.reject(error.into());
+        }
+        self.append_logbook(LogEntry::MovePlayed {
+            player,
+            from: current_field,
+            to: field,
+        });
+        if Board::is_final_field(field, player) {
+            self.winner = Some(player);
+            let timestamp = Timestamp {
+                ply: self.logbook.get_played_moves().len(),
+                seconds: self.seconds,
+            };
+            self.logbook
+                .push(timestamp, LogEntry::GameOver { winner: player });
+        }
+        self.press_clock(player);
+        self.next_player();
+        self.check_race();
+        Ok(())
+    }
+    pub fn place_wall(
+        &mut self,
+        field: FieldIndexSquare<usize>,
+        direction: DirectionsSquare,
+        wall_direction: WallDirections,
+    ) -> Result<(), GameError> {
+        match Board::get_wall_slot(field, direction, wall_direction) {
+            Ok(slot) => self.place_wall_at(slot),
+            Err(error) => self.reject_wall(error),
+        }
+    }
+    pub fn place_wall_at(&mut self, slot: WallSlot) -> Result<(), GameError> {
+        if let Err(error) = self.check_game_over() {
+            return self.reject(error);
+        }
+        let player = self.get_current_player();
+        if let Err(error) = self.board.place_wall_at(player, slot) {
+            return self.reject(error.into());
+        }
+        self.append_logbook(LogEntry::WallPlaced { player, slot });
+        self.press_clock(player);
+        self.next_player();
+        self.check_race();
+        Ok(())
+    }
+    // declares the forced winner once no walls are left, and ends the game if requested
+    fn check_race(&mut self) {
+        if self.is_game_over() || self.race_result.is_some() {
+            return;
+        }
+        let result = match self.board.solve_race(self.get_current_player()) {
+            Some(result) => result,
+            None => return,
+        };
+        self.race_result = Some(result);
+        let timestamp = Timestamp {
+            ply: self.logbook.get_played_moves().len(),
+            seconds: self.seconds,
+        };
+        self.logbook.push(
+            timestamp,
+            LogEntry::RaceDecided {
+                winner: result.winner,
+                plies: result.plies,
+            },
+        );
+        if self.end_decided_races {
+            self.winner = Some(result.winner);
+            self.logbook.push(
+                timestamp,
+                LogEntry::GameOver {
+                    winner: result.winner,
+                },
+            );
+        }
+    }
+    /// Places a wall given by its two pieces as selected on the page, see
+    /// `get_wall_slot_by_pieces`.
+    pub fn place_wall_by_pieces(
+        &mut self,
+        first_piece: (usize, usize, bool),
+        second_piece: (usize, usize, bool),
+    ) -> Result<(), GameError> {
+        match get_wall_slot_by_pieces(first_piece, second_piece) {
+            Ok(slot) => self.place_wall_at(slot),
+            Err(error) => self.reject_wall(error),
+        }
+    }
+    pub fn reject_move(&mut self, error: MoveError) -> Result<(), GameError> {
+        if let Err(error) = self.check_game_over() {
+            return self.reject(error);
+        }
+        self.reject(error.into())
+    }
+    pub fn reject_wall(&mut self, error: WallPlacmentError) -> Result<(), GameError> {
+        if let Err(error) = self.check_game_over() {
+            return self.reject(error);
+        }
+        self.reject(error.into())
+    }
+    /// Number of hints the player asked for, see `request_hint`.
+    pub fn get_hint_count(&self, player: TwoPlayerIndices) -> usize {
+        self.logbook
+            .get_entries()
+            .iter()
+            .filter(|entry| matches!(entry.1, LogEntry::HintGiven { player: x, .. } if x == player))
+            .count()
+    }
+    /// The moves played so far and the winner, if any, with the tags `TimeControl` and
+    /// `Termination` for games played on the clock.
+    ///
+    /// Moves played after a hint are commented with it and the hints of each player are counted
+    /// in the tags `WhiteHints` and `BlackHints`.
+    pub fn to_record(&self) -> GameRecord {
+        let mut record = GameRecord::new(Board::AVERAGE_BOARD_SIZE, self.board.get_rules());
+        for played_move in self.logbook.get_played_moves() {
+            match *played_move {
+                LogEntry::MovePlayed { from, to, .. } => {
+                    for (next_field, direction) in Board::get_surrounding_fields(from) {
+                        if next_field == to {
+                            record.moves.push(Move::Step(direction));
+                        }
+                    }
+                }
+                LogEntry::WallPlaced { slot, .. } => record.moves.push(Move::Wall(slot)),
+                _ => {}
+            }
+        }
+        record.winner = self.winner;
+        for &(timestamp, ref entry) in self.logbook.get_entries() {
+            if let LogEntry::HintGiven { hint, .. } = *entry {
+                if timestamp.ply <= record.moves.len() {
+                    record.get_annotation_mut(timestamp.ply - 1).comment =
+                        Some(format!("hint {}", hint));
+                }
+            }
+        }
+        for &(player, tag) in &[
+            (TwoPlayerIndices::White, "WhiteHints"),
+            (TwoPlayerIndices::Black, "BlackHints"),
+        ] {
+            let count = self.get_hint_count(player);
+            if count > 0 {
+                record.set_tag(tag, &count.to_string());
+            }
+        }
+        if let Some(ref clock) = self.clock {
+            record.set_tag("TimeControl", &clock.time_control.to_string());
+        }
+        if self.get_time_forfeit().is_some() {
+            record.set_tag("Termination", "time forfeit");
+        }
+        record.start_position = self.start_position.clone();
+        record
+    }
+    /// Lets the engine play the move of the current player: sends the rules, the position the
+    /// game started from and the moves played so far, searches within the limits and plays the
+    /// move it returns.
+    ///
+    /// With a clock the engine is sent the times left and its thinking time is added to the
+    /// game, it loses if it answers too late.
+    pub fn play_engine_move(
+        &mut self,
+        engine: &mut EngineProcess,
+        limits: GoLimits,
+    ) -> Result<Move, EngineError> {
+        self.check_game_over().map_err(EngineError::Game)?;
+        let limits = match self.clock {
+            Some(ref clock) => GoLimits {
+                depth: limits.depth,
+                move_time: limits.move_time,
+                ..clock.get_go_limits(self.get_current_player())
+            },
+            None => limits,
+        };
+        let start = Instant::now();
+        engine.send(&Command::NewGame {
+            board_size: Board::AVERAGE_BOARD_SIZE,
+            rules: self.board.get_rules(),
+        })?;
+        engine.send(&Command::Position {
+            position: self.start_position.clone(),
+            moves: self.to_record().moves,
+        })?;
+        let next_move = match engine.go(limits)? {
+            (Some(next_move), _) => next_move,
+            (None, _) => return Err(EngineError::Engine("no move in a running game".to_string())),
+        };
+        self.add_time(start.elapsed().as_secs_f64());
+        self.check_game_over().map_err(EngineError::Game)?;
+        self.play_move(next_move).map_err(EngineError::Game)?;
+        Ok(next_move)
+    }
+}
+
+impl<Board> GameData<Board>
+where
+    Board: PositionBoard<WallDirectionType = WallDirections>,
+{
+    /// A game continuing from the position with its player to move, `None` if the board
+    /// cannot show it.
+    pub fn with_position(position: &Position, rules: Rules) -> Option<Self> {
+        let mut game = Self::with_rules(rules);
+        game.board = Board::from_position(position, rules)?;
+        game.current_player = position.player;
+        game.start_position = Some(position.clone());
+        Some(game)
+    }
+    /// Takes back the last ply by replaying all remaining plies on a new board.
+    ///
+    /// The clock is set back to the moment the ply was played, so its increment is taken back
+    /// while the time used for it stays used. A game lost on time cannot be taken back.
+    pub fn undo(&mut self) -> Result<(), GameError> {
+        if let (Some(_), Some(winner)) = (self.get_time_forfeit(), self.winner) {
+            return self.reject(GameError::GameOver { winner });
+        }
+        let mut played_moves = self.logbook
+            .get_played_moves()
+            .into_iter()
+            .cloned()
+            .collect::<Vec<_>>();
+        let undone_player = match played_moves.pop() {
+            Some(LogEntry::MovePlayed { player, .. }) | Some(LogEntry::WallPlaced { player, .. }) => {
+                player
+            }
+            _ => return self.reject(GameError::NothingToUndo),
+        };
+        let rules = self.board.get_rules();
+        let mut board = match self.start_position {
+            Some(ref position) => {
+                Board::from_position(position, rules).expect("the game was started from it")
+            }
+            None => Board::with_rules(rules),
+        };
+        for played_move in played_moves.iter() {
+            match *played_move {
+                LogEntry::MovePlayed { player, from, to } => {
+                    for (next_field, direction) in Board::get_surrounding_fields(from) {
+                        if next_field == to {
+                            let _ = board.move_player(player, direction);
+                        }
+                    }
+                }
+                LogEntry::WallPlaced { player, slot } => {
+                    let _ = board.place_wall_at(player, slot);
+                }
+                _ => {}
+            }
+        }
+        let timestamp = Timestamp {
+            ply: played_moves.len() + 1,
+            seconds: self.seconds,
+        };
+        self.logbook.push(
+            timestamp,
+            LogEntry::Undo {
+                player: undone_player,
+            },
+        );
+        self.board = board;
+        if let Some(Some(clock)) = self.clocks.pop() {
+            self.clock = Some(clock);
+        }
+        self.current_player = undone_player;
+        self.wall_index_selected = None;
+        self.hint = None;
+        self.winner = None;
+        self.race_result = None;
+        self.check_race();
+        Ok(())
+    }
+}
+
+impl<Board> GameData<Board>
+where
+    Board: TablebaseBoard<WallDirectionType = WallDirections> + Clone,
+{
+    /// Searches `depth` plies ahead for a move of the current player, which is logged and kept
+    /// as `hint` until the next ply. Positions of the `tablebase` are scored exactly.
+    pub fn request_hint(&mut self, depth: usize) -> Result<Move, GameError> {
+        if let Err(error) = self.check_game_over() {
+            self.reject(error)?;
+        }
+        let player = self.get_current_player();
+        let settings = SearchSettings {
+            depth,
+            tablebase: self.tablebase.as_deref(),
+            ..SearchSettings::default()
+        };
+        let hint = search_with(&self.board, player, &settings)
+            .best_move
+            .expect("running games have a move");
+        self.append_logbook(LogEntry::HintGiven { player, hint });
+        self.hint = Some(hint);
+        Ok(hint)
+    }
+}
@@ -0,0 +1,242 @@
+//! Post-game analysis: the moves of a finished game compared with the moves the search prefers.
+//!
+//! `LinearEvaluator` scores are hundredths of the logarithm of the odds to win, so scores are
+//! turned into the chance of the player to move to win; a move is judged by how much it lowers
+//! the chance of its player compared with the best move found.
+use ai::*;
+use board_square::*;
+use evaluation::*;
+use record::*;
+use std::fmt;
+use tablebase::*;
+
+/// Least drop of the chance to win of an inaccuracy.
+pub const INACCURACY_DROP: f64 = 0.1;
+/// Least drop of the chance to win of a mistake.
+pub const MISTAKE_DROP: f64 = 0.2;
+/// Least drop of the chance to win of a blunder.
+pub const BLUNDER_DROP: f64 = 0.3;
+
+/// How bad a move is, see the drops above.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Judgement {
+    Inaccuracy,
+    Mistake,
+    Blunder,
+}
+
+impl Judgement {
+    /// The judgement of a move lowering the chance to win by `drop`, `None` for good moves.
+    pub fn from_drop(drop: f64) -> Option<Self> {
+        if drop >= BLUNDER_DROP {
+            Some(Judgement::Blunder)
+        } else if drop >= MISTAKE_DROP {
+            Some(Judgement::Mistake)
+        } else if drop >= INACCURACY_DROP {
+            Some(Judgement::Inaccuracy)
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for Judgement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let text = match *self {
+            Judgement::Inaccuracy => "inaccuracy",
+            Judgement::Mistake => "mistake",
+            Judgement::Blunder => "blunder",
+        };
+        write!(f, "{}", text)
+    }
+}
+
+/// Chance of the player to move to win for a score of the search, from 0 to 1.
+pub fn get_win_chance(score: i32) -> f64 {
+    1. / (1. + (-f64::from(score) / 100.).exp())
+}
+
+/// A move of the game compared with the best move, the scores are from the view of its player.
+#[derive(Clone, PartialEq, Debug)]
+pub struct MoveReview {
+    /// index of the move in the record
+    pub ply: usize,
+    pub player: TwoPlayerIndices,
+    pub played: Move,
+    /// score of the best move, which is the score of the position before the move
+    pub best_score: i32,
+    pub played_score: i32,
+    /// the best move and the expected replies
+    pub best_line: Vec<Move>,
+    pub judgement: Option<Judgement>,
+}
+
+impl MoveReview {
+    pub fn get_best_move(&self) -> Option<Move> {
+        self.best_line.first().cloned()
+    }
+    /// How much the move lowered the chance to win of its player, 0 for the best move.
+    pub fn get_drop(&self) -> f64 {
+        (get_win_chance(self.best_score) - get_win_chance(self.played_score)).max(0.)
+    }
+}
+
+/// The judged moves and the accuracy of a player.
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+pub struct PlayerSummary {
+    pub moves: usize,
+    pub inaccuracies: usize,
+    pub mistakes: usize,
+    pub blunders: usize,
+    /// 100 minus the average drop of the chance to win in percent
+    pub accuracy: f64,
+}
+
+impl fmt::Display for PlayerSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{:.1}% accuracy, {} inaccuracies, {} mistakes, {} blunders in {} moves",
+            self.accuracy, self.inaccuracies, self.mistakes, self.blunders, self.moves
+        )
+    }
+}
+
+/// Result of `analyze_game`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct GameReview {
+    /// the game with the judgement and the better line of every judged move as comment and
+    /// variation, and the tags `WhiteAccuracy` and `BlackAccuracy`
+    pub record: GameRecord,
+    pub moves: Vec<MoveReview>,
+    /// White's and Black's summary
+    pub summaries: [PlayerSummary; 2],
+}
+
+impl GameReview {
+    pub fn get_summary(&self, player: TwoPlayerIndices) -> &PlayerSummary {
+        match player {
+            TwoPlayerIndices::White => &self.summaries[0],
+            TwoPlayerIndices::Black => &self.summaries[1],
+        }
+    }
+}
+
+/// Searches every position of the game `depth` plies ahead, at least two, and judges the moves
+/// played.
+pub fn analyze_game<Board>(record: &GameRecord, depth: usize) -> Result<GameReview, RecordError>
+where
+    Board: TablebaseBoard + Clone,
+{
+    analyze_game_with::<Board, _>(
+        record,
+        &SearchSettings {
+            depth,
+            ..SearchSettings::default()
+        },
+    )
+}
+
+/// `analyze_game` with all settings of the search, the opening book is not used.
+pub fn analyze_game_with<Board, E>(
+    record: &GameRecord,
+    settings: &SearchSettings<E>,
+) -> Result<GameReview, RecordError>
+where
+    Board: TablebaseBoard + Clone,
+    E: Evaluator<Board> + Clone,
+{
+    let boards = record.replay::<Board>()?;
+    let depth = settings.depth.max(2);
+    let before_settings = SearchSettings {
+        depth,
+        tablebase: settings.tablebase,
+        book: None,
+        evaluator: settings.evaluator.clone(),
+        deadline: None,
+    };
+    // the move played is scored by searching one ply less after it
+    let after_settings = SearchSettings {
+        depth: depth - 1,
+        ..before_settings.clone()
+    };
+    let mut review = GameReview {
+        record: record.clone(),
+        moves: vec![],
+        summaries: [PlayerSummary::default(); 2],
+    };
+    let mut drops = [0.; 2];
+    for (ply, &played) in record.moves.iter().enumerate() {
+        let player = record.get_move_player(ply);
+        let best = search_with(&boards[ply], player, &before_settings);
+        let played_score = if best.best_move == Some(played) {
+            best.score
+        } else {
+            -search_with(&boards[ply + 1], player.next_player(), &after_settings).score
+        };
+        let mut move_review = MoveReview {
+            ply,
+            player,
+            played,
+            best_score: best.score.max(played_score),
+            played_score,
+            best_line: best.principal_variation,
+            judgement: None,
+        };
+        let drop = move_review.get_drop();
+        move_review.judgement = Judgement::from_drop(drop);
+        let index = match player {
+            TwoPlayerIndices::White => 0,
+            TwoPlayerIndices::Black => 1,
+        };
+        drops[index] += drop;
+        let summary = &mut review.summaries[index];
+        summary.moves += 1;
+        match move_review.judgement {
+            Some(Judgement::Inaccuracy) => summary.inaccuracies += 1,
+            Some(Judgement::Mistake) => summary.mistakes += 1,
+            Some(Judgement::Blunder) => summary.blunders += 1,
+            None => {}
+        }
+        if let Some(judgement) = move_review.judgement {
+            annotate(&mut review.record, &move_review, judgement);
+        }
+        review.moves.push(move_review);
+    }
+    for (summary, drop) in review.summaries.iter_mut().zip(&drops) {
+        summary.accuracy = if summary.moves == 0 {
+            100.
+        } else {
+            100. * (1. - drop / summary.moves as f64)
+        };
+    }
+    let accuracies = [review.summaries[0].accuracy, review.summaries[1].accuracy];
+    review.record.set_tag("WhiteAccuracy", &format!("{:.1}", accuracies[0]));
+    review.record.set_tag("BlackAccuracy", &format!("{:.1}", accuracies[1]));
+    Ok(review)
+}
+
+// comments the move with its judgement and the better move, and adds the better line as
+// variation, existing comments and variations are kept
+fn annotate(record: &mut GameRecord, move_review: &MoveReview, judgement: Judgement) {
+    let annotation = record.get_annotation_mut(move_review.ply);
+    let mut comment = judgement.to_string();
+    if let Some(best_move) = move_review.get_best_move() {
+        comment = format!("{}, better {}", comment, best_move);
+        let is_known = annotation
+            .variations
+            .iter()
+            .any(|variation| variation.moves.first() == Some(&best_move));
+        if !is_known {
+            annotation.variations.push(Variation {
+                moves: move_review.best_line.clone(),
+                ..Variation::default()
+            });
+        }
+    }
+    annotation.comment = Some(match annotation.comment.take() {
+        Some(ref old) if old.contains(&comment) => old.clone(),
+        Some(old) => format!("{} {}", old, comment),
+        None => comment,
+    });
+}
@@ -1,15 +1,24 @@
 mod strings;
 pub use self::strings::*;
 use std::os::raw::{c_char, c_double};
-use std::ffi::CString;
+use std::ffi::{CStr, CString};
 
-use super::{BOARDSIZE, DATA};
+use super::{BoardType, ANALYSIS, BOARDSIZE, DATA, REPLAY};
+use super::analysis::*;
 use super::board_square::*;
+use super::clock::*;
+use super::game::*;
+use super::logbook::*;
+use super::record::*;
+use super::replay::*;
 
 const FIELD_WIDTH: f64 = 50. * 5. / BOARDSIZE as f64;
 const WALL_WIDTH: f64 = 10. * 5. / BOARDSIZE as f64;
 const DISTANCE: f64 = FIELD_WIDTH + WALL_WIDTH;
 const BOARD_SIZE: f64 = DISTANCE * BOARDSIZE as f64 + WALL_WIDTH;
+const WALL_HINT_COUNT: usize = 3;
+// plies searched for `request_hint`
+const HINT_DEPTH: usize = 2;
 // These functions are provided by the runtime
 extern "C" {
     fn clear_screen(width: c_double, height: c_double);
@@ -63,125 +72,573 @@ extern "C" {
 //#[no_mangle]
 //pub extern "C" fn reset(width: c_double, height: c_double) {}
 
+// calls the function with the board shown and the player to move on it: the analysis or the
+// replay while one is open, the game otherwise
+fn with_displayed_board<T, F>(function: F) -> T
+where
+    F: FnOnce(&BoardType, TwoPlayerIndices) -> T,
+{
+    let data = DATA.lock().unwrap();
+    let analysis = ANALYSIS.lock().unwrap();
+    let replay = REPLAY.lock().unwrap();
+    match (&*analysis, &*replay) {
+        (&Some((ref analysis, _)), _) => {
+            function(analysis.get_board(), analysis.get_current_player())
+        }
+        (&None, Some(replay)) => function(replay.get_board(), replay.get_current_player()),
+        (&None, &None) => function(&data.board, data.get_current_player()),
+    }
+}
+
 #[no_mangle]
 pub fn get_walls_black() -> c_double {
-    DATA.lock()
-        .unwrap()
-        .board
-        .get_player_data(TwoPlayerIndices::Black)
-        .get_wall_count() as c_double
+    with_displayed_board(|board, _| {
+        board
+            .get_player_data(TwoPlayerIndices::Black)
+            .get_wall_count() as c_double
+    })
 }
 #[no_mangle]
 pub fn get_walls_white() -> c_double {
-    DATA.lock()
-        .unwrap()
-        .board
-        .get_player_data(TwoPlayerIndices::White)
-        .get_wall_count() as c_double
+    with_displayed_board(|board, _| {
+        board
+            .get_player_data(TwoPlayerIndices::White)
+            .get_wall_count() as c_double
+    })
 }
 #[no_mangle]
 pub fn get_current_player_string() -> *mut c_char {
-    let s = DATA.lock().unwrap().get_current_player().to_string();
+    let s = with_displayed_board(|_, player| player.to_string());
     let s = CString::new(s.to_string()).unwrap();
     s.into_raw()
 }
 #[no_mangle]
 pub fn get_current_player_color_string() -> *mut c_char {
-    let s = DATA.lock()
-        .unwrap()
-        .get_current_player()
-        .get_color_as_string();
+    let s = with_displayed_board(|_, player| player.get_color_as_string());
     let s = CString::new(s.to_string()).unwrap();
     s.into_raw()
 }
 
+/// Starts the clocks with the time control in seconds, a main time of 0 stops them.
+#[no_mangle]
+pub extern "C" fn set_time_control(main_time: c_double, increment: c_double, byoyomi: c_double) {
+    let mut data = DATA.lock().unwrap();
+    if main_time > 0. {
+        data.set_time_control(TimeControl {
+            main_time,
+            increment: increment.max(0.),
+            byoyomi: byoyomi.max(0.),
+        });
+    } else {
+        data.clock = None;
+    }
+}
+// seconds the player may still use, -1 without clocks
+fn get_clock(player: TwoPlayerIndices) -> c_double {
+    let data = DATA.lock().unwrap();
+    match data.clock {
+        Some(ref clock) => clock.get_time_left(player, data.get_current_player()),
+        None => -1.,
+    }
+}
+#[no_mangle]
+pub fn get_clock_white() -> c_double {
+    get_clock(TwoPlayerIndices::White)
+}
+#[no_mangle]
+pub fn get_clock_black() -> c_double {
+    get_clock(TwoPlayerIndices::Black)
+}
+#[no_mangle]
+pub fn is_game_over() -> c_double {
+    if DATA.lock().unwrap().is_game_over() {
+        1.
+    } else {
+        0.
+    }
+}
+// replays
+
+fn load_replay_record(record: GameRecord) -> c_double {
+    match Replay::new(record) {
+        Ok(replay) => {
+            let ply_count = replay.get_ply_count();
+            // an open analysis would be drawn instead
+            *ANALYSIS.lock().unwrap() = None;
+            *REPLAY.lock().unwrap() = Some(replay);
+            ply_count as c_double
+        }
+        Err(_) => -1.,
+    }
+}
+/// Opens the record for replay, returns its number of plies or -1 if it cannot be replayed.
+///
+/// # Safety
+///
+/// `text` has to be a nul-terminated string, it stays owned by the caller.
+#[no_mangle]
+pub unsafe extern "C" fn load_replay(text: *const c_char) -> c_double {
+    match CStr::from_ptr(text).to_str().map(|x| x.parse::<GameRecord>()) {
+        Ok(Ok(record)) => load_replay_record(record),
+        _ => -1.,
+    }
+}
+/// Opens the game played so far for replay, returns its number of plies.
+#[no_mangle]
+pub extern "C" fn load_replay_of_game() -> c_double {
+    let record = DATA.lock().unwrap().to_record();
+    load_replay_record(record)
+}
+/// Shows the running game again.
+#[no_mangle]
+pub extern "C" fn close_replay() {
+    *REPLAY.lock().unwrap() = None;
+}
+#[no_mangle]
+pub fn is_replaying() -> c_double {
+    if REPLAY.lock().unwrap().is_some() {
+        1.
+    } else {
+        0.
+    }
+}
+/// Number of plies of the replay, 0 without replay.
+#[no_mangle]
+pub fn get_ply_count() -> c_double {
+    REPLAY
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map_or(0, |replay| replay.get_ply_count()) as c_double
+}
+/// The ply shown by the replay.
+#[no_mangle]
+pub fn get_ply() -> c_double {
+    REPLAY
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map_or(0, |replay| replay.get_ply()) as c_double
+}
+#[no_mangle]
+pub extern "C" fn goto_ply(ply: c_double) {
+    if let Some(ref mut replay) = *REPLAY.lock().unwrap() {
+        replay.goto_ply(ply.max(0.) as usize);
+    }
+}
+#[no_mangle]
+pub extern "C" fn step_forward() {
+    if let Some(ref mut replay) = *REPLAY.lock().unwrap() {
+        replay.step_forward();
+    }
+}
+#[no_mangle]
+pub extern "C" fn step_backward() {
+    if let Some(ref mut replay) = *REPLAY.lock().unwrap() {
+        replay.step_backward();
+    }
+}
+/// The move which led to the position shown by the replay, empty at its start.
+#[no_mangle]
+pub fn get_replay_move_string() -> *mut c_char {
+    let s = match *REPLAY.lock().unwrap() {
+        Some(ref replay) => replay
+            .get_last_move()
+            .map(|x| x.to_string())
+            .unwrap_or_default(),
+        None => String::new(),
+    };
+    let s = CString::new(s).unwrap();
+    s.into_raw()
+}
+
+// analysis
+
+// opens the record for analysis at the given ply, returns 1 on success and 0 otherwise
+fn load_analysis_record(record: &GameRecord, ply: usize) -> c_double {
+    match AnalysisBoard::from_record(record) {
+        Ok(mut analysis) => {
+            for _ in 0..ply {
+                analysis.forward();
+            }
+            *ANALYSIS.lock().unwrap() = Some((analysis, None));
+            1.
+        }
+        Err(_) => 0.,
+    }
+}
+/// Opens the replay at its ply, or the game played so far, for analysis; the replay is closed.
+#[no_mangle]
+pub extern "C" fn start_analysis() -> c_double {
+    let replay = REPLAY.lock().unwrap().take();
+    match replay {
+        Some(replay) => load_analysis_record(replay.get_record(), replay.get_ply()),
+        None => {
+            let record = DATA.lock().unwrap().to_record();
+            load_analysis_record(&record, record.moves.len())
+        }
+    }
+}
+/// Opens a record with variations for analysis, returns 1 on success and 0 otherwise.
+///
+/// # Safety
+///
+/// `text` has to be a nul-terminated string, it stays owned by the caller.
+#[no_mangle]
+pub unsafe extern "C" fn load_analysis(text: *const c_char) -> c_double {
+    match CStr::from_ptr(text).to_str().map(|x| x.parse::<GameRecord>()) {
+        Ok(Ok(record)) => load_analysis_record(&record, 0),
+        _ => 0.,
+    }
+}
+#[no_mangle]
+pub extern "C" fn close_analysis() {
+    *ANALYSIS.lock().unwrap() = None;
+}
+#[no_mangle]
+pub fn is_analyzing() -> c_double {
+    if ANALYSIS.lock().unwrap().is_some() {
+        1.
+    } else {
+        0.
+    }
+}
+// calls the function with the open analysis board
+fn with_analysis<T, F>(function: F) -> Option<T>
+where
+    F: FnOnce(&mut AnalysisBoard<BoardType>) -> T,
+{
+    match *ANALYSIS.lock().unwrap() {
+        Some((ref mut analysis, ref mut selected)) => {
+            *selected = None;
+            Some(function(analysis))
+        }
+        None => None,
+    }
+}
+fn analysis_string<F>(function: F) -> *mut c_char
+where
+    F: FnOnce(&mut AnalysisBoard<BoardType>) -> String,
+{
+    let s = with_analysis(function).unwrap_or_default();
+    let s = CString::new(s.replace('\0', "")).unwrap();
+    s.into_raw()
+}
+#[no_mangle]
+pub extern "C" fn analysis_back() {
+    with_analysis(|analysis| analysis.back());
+}
+/// Follows the main line.
+#[no_mangle]
+pub extern "C" fn analysis_forward() {
+    with_analysis(|analysis| analysis.forward());
+}
+#[no_mangle]
+pub extern "C" fn analysis_goto_start() {
+    with_analysis(|analysis| analysis.goto_start());
+}
+#[no_mangle]
+pub extern "C" fn analysis_goto_end() {
+    with_analysis(|analysis| analysis.goto_end());
+}
+/// Makes the line to the position shown the main line.
+#[no_mangle]
+pub extern "C" fn analysis_promote() {
+    with_analysis(|analysis| analysis.promote_variation());
+}
+/// Removes the move to the position shown and everything after it.
+#[no_mangle]
+pub extern "C" fn analysis_delete() {
+    with_analysis(|analysis| analysis.delete_variation());
+}
+/// Plays a move given as text, e.g. one of the continuations, returns 1 if it was legal.
+///
+/// # Safety
+///
+/// `text` has to be a nul-terminated string, it stays owned by the caller.
+#[no_mangle]
+pub unsafe extern "C" fn analysis_play_move(text: *const c_char) -> c_double {
+    let next_move = match CStr::from_ptr(text).to_str().map(|x| x.parse::<Move>()) {
+        Ok(Ok(next_move)) => next_move,
+        _ => return 0.,
+    };
+    match with_analysis(|analysis| analysis.play_move(next_move)) {
+        Some(Ok(())) => 1.,
+        _ => 0.,
+    }
+}
+/// Comments the move to the position shown, an empty text removes the comment.
+///
+/// # Safety
+///
+/// `text` has to be a nul-terminated string, it stays owned by the caller.
+#[no_mangle]
+pub unsafe extern "C" fn set_analysis_comment(text: *const c_char) {
+    let comment = CStr::from_ptr(text).to_string_lossy().trim().to_string();
+    with_analysis(|analysis| {
+        analysis.set_comment(if comment.is_empty() {
+            None
+        } else {
+            Some(comment)
+        })
+    });
+}
+#[no_mangle]
+pub fn get_analysis_comment() -> *mut c_char {
+    analysis_string(|analysis| analysis.get_comment().unwrap_or("").to_string())
+}
+/// The moves to the position shown, one per line.
+#[no_mangle]
+pub fn get_analysis_line() -> *mut c_char {
+    analysis_string(|analysis| {
+        let line = analysis.get_line();
+        line.iter().map(|x| x.to_string()).collect::<Vec<_>>().join("\n")
+    })
+}
+/// The moves known in the position shown, the main line first, one per line.
+#[no_mangle]
+pub fn get_analysis_continuations() -> *mut c_char {
+    analysis_string(|analysis| {
+        let continuations = analysis.get_continuations();
+        continuations.iter().map(|x| x.to_string()).collect::<Vec<_>>().join("\n")
+    })
+}
+/// The analysis as a record with variations and comments, e.g. to save it.
+#[no_mangle]
+pub fn get_analysis_record() -> *mut c_char {
+    analysis_string(|analysis| analysis.to_record().to_string())
+}
+
+// what a click on the page hits
+enum ClickTarget {
+    Field(FieldIndexSquare<usize>),
+    // as in `get_wall_slot_by_pieces`
+    WallPiece((usize, usize, bool)),
+}
+
+fn get_click_target(pos_x: c_double, pos_y: c_double) -> Option<ClickTarget> {
+    if pos_x <= WALL_WIDTH || pos_y <= WALL_WIDTH {
+        return None;
+    }
+    let x = pos_x - WALL_WIDTH;
+    let y = pos_y - WALL_WIDTH;
+    let column = (x / DISTANCE) as usize;
+    let row = (y / DISTANCE) as usize;
+    if x - (x / DISTANCE).floor() * DISTANCE < FIELD_WIDTH
+        && y - (y / DISTANCE).floor() * DISTANCE < FIELD_WIDTH
+    {
+        Some(ClickTarget::Field(FieldIndexSquare { column, row }))
+    } else if x + WALL_WIDTH < BOARD_SIZE && y + WALL_WIDTH < BOARD_SIZE {
+        let dir_is_left_or_right = y - (y / DISTANCE).floor() * DISTANCE < FIELD_WIDTH;
+        Some(ClickTarget::WallPiece((column, row, dir_is_left_or_right)))
+    } else {
+        None
+    }
+}
+
 #[no_mangle]
 pub extern "C" fn on_click(pos_x: c_double, pos_y: c_double) {
-    if pos_x > WALL_WIDTH && pos_y > WALL_WIDTH {
-        let x = pos_x - WALL_WIDTH;
-        let y = pos_y - WALL_WIDTH;
-        let column = (x / DISTANCE) as usize;
-        let row = (y / DISTANCE) as usize;
-        if x - (x / DISTANCE).floor() * DISTANCE < FIELD_WIDTH
-            && y - (y / DISTANCE).floor() * DISTANCE < FIELD_WIDTH
-        {
-            let mut data = DATA.lock().unwrap();
-            data.move_player_by_field(FieldIndexSquare {
-                column: column,
-                row: row,
-            }).map(|error| data.append_logbook(error.to_string().to_string()));
-            data.wall_index_selected = None;
-        } else if x + WALL_WIDTH < BOARD_SIZE && y + WALL_WIDTH < BOARD_SIZE {
-            let dir_is_left_or_right = if y - (y / DISTANCE).floor() * DISTANCE < FIELD_WIDTH {
-                true
-            } else {
-                false
-            };
-            let mut data = DATA.lock().unwrap();
-            let selected_before = data.wall_index_selected;
-            match selected_before {
+    let target = match get_click_target(pos_x, pos_y) {
+        Some(target) => target,
+        None => return,
+    };
+    let mut data = DATA.lock().unwrap();
+    // moves in the analysis add variations
+    if let Some((ref mut analysis, ref mut selected)) = *ANALYSIS.lock().unwrap() {
+        let next_move = match target {
+            ClickTarget::Field(field) => {
+                *selected = None;
+                let current_field = analysis
+                    .get_board()
+                    .get_current_field(analysis.get_current_player());
+                BoardType::get_surrounding_fields(current_field)
+                    .iter()
+                    .find(|&&(next_field, _)| next_field == field)
+                    .map(|&(_, direction)| Move::Step(direction))
+            }
+            ClickTarget::WallPiece(piece) => match selected.take() {
                 None => {
-                    data.wall_index_selected = Some((column, row, dir_is_left_or_right));
-                }
-                Some((column_before, row_before, dir_is_left_or_right_before)) => {
-                    use std;
-                    data.wall_index_selected = None;
-                    let min_column = std::cmp::min(column, column_before);
-                    let min_row = std::cmp::min(row, row_before);
-                    let max_column = std::cmp::max(column, column_before);
-                    let max_row = std::cmp::max(row, row_before);
-                    let current_player = data.get_current_player();
-                    let error = if dir_is_left_or_right != dir_is_left_or_right_before {
-                        Some(WallPlacmentError::NotConnected)
-                    } else if max_column == min_column && min_row + 1 == max_row {
-                        data.board.place_wall(
-                            current_player,
-                            FieldIndexSquare {
-                                column: min_column,
-                                row: min_row,
-                            },
-                            DirectionsSquare::Right,
-                            WallDirections::Left,
-                        )
-                    } else if max_column == min_column + 1 && min_row == max_row {
-                        data.board.place_wall(
-                            current_player,
-                            FieldIndexSquare {
-                                column: min_column,
-                                row: min_row,
-                            },
-                            DirectionsSquare::Up,
-                            WallDirections::Right,
-                        )
-                    } else {
-                        Some(WallPlacmentError::NotConnected)
-                    };
-                    match error {
-                        None => data.next_player(),
-                        Some(err) => {
-                            data.append_logbook(err.to_string().to_string());
-                        }
-                    }
+                    *selected = Some(piece);
+                    None
                 }
-            };
+                Some(selected_before) => get_wall_slot_by_pieces(selected_before, piece)
+                    .ok()
+                    .map(Move::Wall),
+            },
+        };
+        if let Some(next_move) = next_move {
+            // illegal moves are ignored
+            let _ = analysis.play_move(next_move);
+        }
+        return;
+    }
+    // the position of a replay cannot be changed
+    if REPLAY.lock().unwrap().is_some() {
+        return;
+    }
+    match target {
+        ClickTarget::Field(field) => {
+            // errors are recorded in the logbook
+            let _ = data.move_player_by_field(field);
+            data.wall_index_selected = None;
         }
+        ClickTarget::WallPiece(piece) => match data.wall_index_selected.take() {
+            None => data.wall_index_selected = Some(piece),
+            Some(selected_before) => {
+                // errors are recorded in the logbook
+                let _ = data.place_wall_by_pieces(selected_before, piece);
+            }
+        },
     }
 }
 
 #[no_mangle]
 pub fn get_logbook() -> *mut c_char {
-    let data = DATA.lock().unwrap().get_logbook().clone();
-    let s = data.join("\n");
+    let data = DATA.lock().unwrap();
+    let s = data.get_logbook().to_text(data.language);
     let s = CString::new(s).unwrap();
     s.into_raw()
 }
+#[no_mangle]
+pub fn get_logbook_json() -> *mut c_char {
+    let s = DATA.lock().unwrap().get_logbook().to_json();
+    let s = CString::new(s).unwrap();
+    s.into_raw()
+}
+#[no_mangle]
+pub extern "C" fn set_language(index: c_double) {
+    if let Some(language) = Language::from_index(index as usize) {
+        DATA.lock().unwrap().language = language;
+    }
+}
+/// Lets the engine suggest a move for the current player, which `draw` highlights until the
+/// next ply, returns the move or an empty string once the game is over.
+#[no_mangle]
+pub fn request_hint() -> *mut c_char {
+    // errors are recorded in the logbook
+    let s = DATA.lock()
+        .unwrap()
+        .request_hint(HINT_DEPTH)
+        .map(|x| x.to_string())
+        .unwrap_or_default();
+    let s = CString::new(s).unwrap();
+    s.into_raw()
+}
+#[no_mangle]
+pub extern "C" fn toggle_wall_hints() {
+    let mut data = DATA.lock().unwrap();
+    data.show_wall_hints = !data.show_wall_hints;
+}
+#[no_mangle]
+pub extern "C" fn undo() {
+    // errors are recorded in the logbook
+    let _ = DATA.lock().unwrap().undo();
+}
 
+/// # Safety
+///
+/// Calls the drawing functions provided by the javascript runtime.
 #[no_mangle]
 pub unsafe extern "C" fn draw() {
     // reset screen
     clear_screen(BOARD_SIZE, BOARD_SIZE);
 
     let data = DATA.lock().unwrap();
+    let analysis = ANALYSIS.lock().unwrap();
+    let replay = REPLAY.lock().unwrap();
+    match (&*analysis, &*replay) {
+        (&Some((ref analysis, selected)), _) => draw_board(
+            analysis.get_board(),
+            analysis.get_current_player(),
+            selected,
+            data.show_wall_hints,
+        ),
+        (&None, Some(replay)) => draw_board(
+            replay.get_board(),
+            replay.get_current_player(),
+            None,
+            data.show_wall_hints,
+        ),
+        (&None, &None) => {
+            draw_board(
+                &data.board,
+                data.get_current_player(),
+                data.wall_index_selected,
+                data.show_wall_hints,
+            );
+            if let Some(hint) = data.hint {
+                draw_hint(&data.board, data.get_current_player(), hint);
+            }
+        }
+    }
+}
+
+// draws a wall which is not placed as a line along its slot
+unsafe fn draw_wall_slot(slot: WallSlot, (red, green, blue): (u8, u8, u8), opacity: f64) {
+    let WallSlot {
+        anchor,
+        orientation,
+    } = slot;
+    let left = anchor.column as f64 * DISTANCE + WALL_WIDTH;
+    let top = anchor.row as f64 * DISTANCE + WALL_WIDTH;
+    let (begin_x, begin_y, end_x, end_y) = match orientation {
+        WallOrientation::Vertical => (
+            left + FIELD_WIDTH + WALL_WIDTH / 2.,
+            top,
+            left + FIELD_WIDTH + WALL_WIDTH / 2.,
+            top + DISTANCE + FIELD_WIDTH,
+        ),
+        WallOrientation::Horizontal => (
+            left,
+            top + FIELD_WIDTH + WALL_WIDTH / 2.,
+            left + DISTANCE + FIELD_WIDTH,
+            top + FIELD_WIDTH + WALL_WIDTH / 2.,
+        ),
+    };
+    draw_line_stroke(
+        begin_x,
+        begin_y,
+        end_x,
+        end_y,
+        WALL_WIDTH,
+        red as f64,
+        green as f64,
+        blue as f64,
+        opacity,
+    );
+}
+
+// highlights the move suggested by the engine: the wall, or the field the pawn reaches
+unsafe fn draw_hint(board: &BoardType, current_player: TwoPlayerIndices, hint: Move) {
+    let hint_color = (30, 144, 255);
+    match hint {
+        Move::Wall(slot) => draw_wall_slot(slot, hint_color, 0.9),
+        Move::Step(_) => {
+            let mut board = board.clone();
+            if board.play_move(current_player, hint).is_err() {
+                return;
+            }
+            // a square in the middle of the field, `draw_circle` also marks the player to move
+            let field = board.get_current_field(current_player);
+            draw_rectangle(
+                WALL_WIDTH + DISTANCE * field.column as f64 + FIELD_WIDTH / 4.,
+                WALL_WIDTH + DISTANCE * field.row as f64 + FIELD_WIDTH / 4.,
+                FIELD_WIDTH / 2.,
+                FIELD_WIDTH / 2.,
+                hint_color.0 as f64,
+                hint_color.1 as f64,
+                hint_color.2 as f64,
+            );
+        }
+    }
+}
+
+unsafe fn draw_board(
+    board: &BoardType,
+    current_player: TwoPlayerIndices,
+    wall_index_selected: Option<(usize, usize, bool)>,
+    show_wall_hints: bool,
+) {
     // draw fields
     for column_index in 0..BOARDSIZE {
         for row_index in 0..BOARDSIZE {
@@ -203,9 +660,9 @@ pub unsafe extern "C" fn draw() {
     }
     // draw walls horizontally
     let wall_color = (100, 100, 100);
-    for column_index in 0..BOARDSIZE as usize - 1 {
-        for row_index in 0..BOARDSIZE as usize - 0 {
-            if data.board.wall_lookup_unsafe(
+    for column_index in 0..BOARDSIZE - 1 {
+        for row_index in 0..BOARDSIZE {
+            if board.wall_lookup_unsafe(
                 FieldIndexSquare {
                     column: column_index,
                     row: row_index,
@@ -226,9 +683,9 @@ pub unsafe extern "C" fn draw() {
         }
     }
     // draw walls vertically
-    for column_index in 0..BOARDSIZE as usize - 0 {
-        for row_index in 0..BOARDSIZE as usize - 1 {
-            if data.board.wall_lookup_unsafe(
+    for column_index in 0..BOARDSIZE {
+        for row_index in 0..BOARDSIZE - 1 {
+            if board.wall_lookup_unsafe(
                 FieldIndexSquare {
                     column: column_index,
                     row: row_index,
@@ -249,9 +706,9 @@ pub unsafe extern "C" fn draw() {
         }
     }
     // draw wall crossings
-    for column_index in 0..BOARDSIZE as usize - 1 {
-        for row_index in 0..BOARDSIZE as usize - 1 {
-            if data.board.croosing_lookup_unsafe(FieldIndexSquare {
+    for column_index in 0..BOARDSIZE - 1 {
+        for row_index in 0..BOARDSIZE - 1 {
+            if board.croosing_lookup_unsafe(FieldIndexSquare {
                 column: column_index,
                 row: row_index,
             }) == WallCrossing::IsWallCrossing
@@ -271,9 +728,7 @@ pub unsafe extern "C" fn draw() {
 
     // draw selected wall
     let wall_selected_color = (218, 165, 32);
-    let selected_before = data.wall_index_selected;
-    if selected_before.is_some() {
-        let (column_index, row_index, dir_is_left_or_right) = selected_before.unwrap();
+    if let Some((column_index, row_index, dir_is_left_or_right)) = wall_index_selected {
         if dir_is_left_or_right {
             draw_rectangle(
                 column_index as f64 * DISTANCE + WALL_WIDTH + FIELD_WIDTH,
@@ -296,26 +751,29 @@ pub unsafe extern "C" fn draw() {
             );
         }
     }
-    // add shortest path
+    // draw the most damaging walls of the current player, the best one most opaque
+    if show_wall_hints && board.get_winner().is_none() {
+        let hint_color = (50, 205, 50);
+        let hints = board.most_damaging_walls(current_player, WALL_HINT_COUNT);
+        for (rank, evaluation) in hints.iter().enumerate() {
+            draw_wall_slot(evaluation.slot, hint_color, 0.8 - 0.2 * rank as f64);
+        }
+    }
+    // add shortest path, every step which lies on some shortest path is drawn once
     let add_shortest_path = |player, red, green, blue, line_thickness, offset| {
-        let shortest_paths = data.board.get_player_data(player).get_shortest_paths();
-        for path in shortest_paths {
-            let mut previous_field = data.board.get_current_field(player);
-            for &(next_field, _) in path {
-                draw_line_stroke(
-                    previous_field.column as f64 * DISTANCE + WALL_WIDTH + FIELD_WIDTH / 2.
-                        + offset,
-                    previous_field.row as f64 * DISTANCE + WALL_WIDTH + FIELD_WIDTH / 2. + offset,
-                    next_field.column as f64 * DISTANCE + WALL_WIDTH + FIELD_WIDTH / 2. + offset,
-                    next_field.row as f64 * DISTANCE + WALL_WIDTH + FIELD_WIDTH / 2. + offset,
-                    line_thickness,
-                    red as f64,
-                    green as f64,
-                    blue as f64,
-                    0.8,
-                );
-                previous_field = next_field;
-            }
+        let shortest_paths = board.get_player_data(player).get_shortest_paths();
+        for (previous_field, next_field, _) in shortest_paths.get_steps() {
+            draw_line_stroke(
+                previous_field.column as f64 * DISTANCE + WALL_WIDTH + FIELD_WIDTH / 2. + offset,
+                previous_field.row as f64 * DISTANCE + WALL_WIDTH + FIELD_WIDTH / 2. + offset,
+                next_field.column as f64 * DISTANCE + WALL_WIDTH + FIELD_WIDTH / 2. + offset,
+                next_field.row as f64 * DISTANCE + WALL_WIDTH + FIELD_WIDTH / 2. + offset,
+                line_thickness,
+                red as f64,
+                green as f64,
+                blue as f64,
+                0.8,
+            );
         }
     };
     let player = TwoPlayerIndices::White;
@@ -329,7 +787,7 @@ pub unsafe extern "C" fn draw() {
     let offset = 0.;
     add_shortest_path(player, red, green, blue, line_thickness, offset);
     // draw player black
-    let pos_black = data.board.get_current_field(TwoPlayerIndices::Black);
+    let pos_black = board.get_current_field(TwoPlayerIndices::Black);
     draw_circle(
         WALL_WIDTH + DISTANCE * pos_black.column as f64 + FIELD_WIDTH / 2.,
         WALL_WIDTH + DISTANCE * pos_black.row as f64 + FIELD_WIDTH / 2.,
@@ -340,7 +798,7 @@ pub unsafe extern "C" fn draw() {
         0.8,
     );
     // draw player white
-    let pos_white = data.board.get_current_field(TwoPlayerIndices::White);
+    let pos_white = board.get_current_field(TwoPlayerIndices::White);
     let white_x = pos_white.column as f64;
     let white_y = pos_white.row as f64;
     let mut pos_x = [0f64; 5];
@@ -372,4 +830,6 @@ pub unsafe extern "C" fn draw() {
 }
 
 #[no_mangle]
-pub extern "C" fn update(_: c_double) {}
+pub extern "C" fn update(seconds: c_double) {
+    DATA.lock().unwrap().add_time(seconds);
+}
@@ -7,14 +7,15 @@ pub extern "C" fn alloc(size: usize) -> *mut c_void {
     let mut buf = Vec::with_capacity(size);
     let ptr = buf.as_mut_ptr();
     mem::forget(buf);
-    return ptr as *mut c_void;
+    ptr as *mut c_void
 }
 
+/// # Safety
+///
+/// `ptr` has to be a string handed out by one of the exported functions.
 #[no_mangle]
-pub extern "C" fn dealloc_str(ptr: *mut c_char) {
-    unsafe {
-        let _ = CString::from_raw(ptr);
-    }
+pub unsafe extern "C" fn dealloc_str(ptr: *mut c_char) {
+    let _ = CString::from_raw(ptr);
 }
 
 #[no_mangle]
@@ -22,8 +23,8 @@ pub fn fact(n: u32) -> u64 {
     let mut n = n as u64;
     let mut result = 1;
     while n > 0 {
-        result = result * n;
-        n = n - 1;
+        result *= n;
+        n -= 1;
     }
     result
 }
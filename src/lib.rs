@@ -1,89 +1,82 @@
+//! Rules engine for varidor, a variation of Quoridor/Blockade.
+//!
+//! The central pieces are
+//! * `Game` (`GameData`): a running game with turn order, logbook and undo,
+//! * `Board` (`BoardTrait`) and its implementations `SquareBoard3` up to `SquareBoard11`:
+//!   the position and the movement and wall rules,
+//! * `Move`: a single ply, played via `Game::play_move` or `Board::play_move`,
+//! * `Rules`: settings which are not fixed by the board type,
+//! * `GameRecord`: a finished game as text, see `record`, and `replay` to step through it,
+//! * `analysis`: a tree of moves with variations and comments for studying games,
+//! * `clock`: time controls and the clocks of the players,
+//! * `archive`: finished games stored in a local file and searched,
+//! * `GameError`, `MoveError` and `WallPlacmentError`: why a ply was rejected,
+//! * `perft`: counts of reachable positions to verify the move generator,
+//! * `ai`: search for the best move,
+//! * `game_review`: the mistakes of a finished game and the accuracy of its players,
+//! * `puzzle`: positions with a unique winning line, played against the solution,
+//! * `evaluation`: scores of positions for the search and the tuning of their weights,
+//! * `tablebase`: solved outcomes of all positions of the small boards,
+//! * `book`: moves played in the openings of recorded games,
+//! * `tournament`: matches between engine configurations with Elo ratings,
+//! * `protocol`: the text protocol through which engine programs play,
+//! * `server`: games between players on different machines over WebSocket.
+//!
+//! With the `wasm` feature the crate additionally exports the functions used by
+//! `html/varidor.html`.
+#[cfg(feature = "wasm")]
 #[macro_use]
 extern crate lazy_static;
 extern crate num_traits;
-mod board_square;
-use self::board_square::*;
+pub mod board_square;
+pub use self::board_square::*;
+pub use self::board_square::BoardTrait as Board;
+pub mod logbook;
+pub use self::logbook::*;
+pub mod record;
+pub use self::record::*;
+pub mod replay;
+pub mod analysis;
+pub mod clock;
+pub mod archive;
+mod game;
+pub use self::game::*;
+pub mod perft;
+pub mod ai;
+pub mod game_review;
+pub mod puzzle;
+pub mod evaluation;
+pub mod tablebase;
+pub mod book;
+pub mod tournament;
+pub mod protocol;
+pub mod server;
 
+#[cfg(feature = "wasm")]
 mod javascript_interaction;
+#[cfg(feature = "wasm")]
 pub use self::javascript_interaction::*;
 //mod game_logic;
 //pub use self::game_logic::*;
 
+#[cfg(feature = "wasm")]
 use std::sync::Mutex;
 
+#[cfg(feature = "wasm")]
 type BoardType = SquareBoard5;
 
-const BOARDSIZE: usize = BoardType::AVERAGE_BOARD_SIZE;
-
-impl MoveError {
-    pub fn to_string(self) -> &'static str {
-        use self::MoveError::*;
-        match self {
-            BoardBoundary => "board boundary reached",
-            Wall => "wall is blocking",
-            FieldsNotAdjacent => "fields are not adjacent",
-        }
-    }
-}
-impl WallPlacmentError {
-    pub fn to_string(self) -> &'static str {
-        use self::WallPlacmentError::*;
-        match self {
-            BoardBoundary => "BoardBoundary",
-            WallAlreadyPlaced => "WallAlreadyPlaced",
-            NoMoreWalls => "NoMoreWalls",
-            PlayerBlocked => "PlayerBlocked",
-            WallsAlreadyCrossing => "WallsAlreadyCrossing",
-            NotConnected => "NotConnected",
-        }
-    }
-}
+// an analysis board and its selected wall piece
+#[cfg(feature = "wasm")]
+type AnalysisType = (analysis::AnalysisBoard<BoardType>, Option<(usize, usize, bool)>);
 
-pub struct GameData {
-    pub board: BoardType,
-    pub current_player: TwoPlayerIndices,
-    pub wall_index_selected: Option<(usize, usize, bool)>,
-    pub logbook: Vec<String>,
-}
-impl GameData {
-    fn new() -> Self {
-        GameData {
-            board: BoardType::new(),
-            current_player: TwoPlayerIndices::White,
-            wall_index_selected: None,
-            logbook: vec!["Game started".to_string()],
-        }
-    }
-    fn get_current_player(&self) -> TwoPlayerIndices {
-        self.current_player
-    }
-    fn next_player(&mut self) {
-        let next_player = self.get_current_player().next_player();
-        self.current_player = next_player;
-    }
-    fn append_logbook(&mut self, new_line: String) {
-        self.logbook.push(new_line);
-    }
-    fn get_logbook(&self) -> &Vec<String> {
-        &self.logbook
-    }
-    fn move_player_by_field(&mut self, field: FieldIndexSquare<usize>) -> Option<MoveError> {
-        let current_field = self.board.get_current_field(self.get_current_player());
-        let connected_fields = SquareBoard5::get_surrounding_fields(current_field);
-        for (next_field, direction) in connected_fields {
-            if next_field == field {
-                if let Some(error) = self.board.move_player(self.current_player, direction) {
-                    return Some(error);
-                } else {
-                    self.next_player();
-                    return None;
-                }
-            }
-        }
-        Some(MoveError::FieldsNotAdjacent)
-    }
-}
+#[cfg(feature = "wasm")]
+const BOARDSIZE: usize = BoardType::AVERAGE_BOARD_SIZE;
 
+#[cfg(feature = "wasm")]
 lazy_static! {
-  static ref DATA: Mutex<GameData> = Mutex::new(GameData::new());
+  static ref DATA: Mutex<GameData<BoardType>> = Mutex::new(GameData::new());
+  // a loaded record, which is drawn instead of the game while it is open
+  static ref REPLAY: Mutex<Option<replay::Replay<BoardType>>> = Mutex::new(None);
+  // an analysis, which is drawn instead of the game and the replay while it is open
+  static ref ANALYSIS: Mutex<Option<AnalysisType>> = Mutex::new(None);
 }
@@ -0,0 +1,149 @@
+use board_square::*;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Language {
+    English,
+    German,
+}
+
+impl Language {
+    pub fn from_index(index: usize) -> Option<Self> {
+        match index {
+            0 => Some(Language::English),
+            1 => Some(Language::German),
+            _ => None,
+        }
+    }
+    pub fn player_name(self, player: TwoPlayerIndices) -> &'static str {
+        use self::Language::*;
+        match (self, player) {
+            (English, TwoPlayerIndices::White) => "White",
+            (English, TwoPlayerIndices::Black) => "Black",
+            (German, TwoPlayerIndices::White) => "Weiß",
+            (German, TwoPlayerIndices::Black) => "Schwarz",
+        }
+    }
+    pub fn game_started(self) -> &'static str {
+        match self {
+            Language::English => "Game started",
+            Language::German => "Spiel gestartet",
+        }
+    }
+    pub fn pawn(self) -> &'static str {
+        match self {
+            Language::English => "pawn",
+            Language::German => "Figur",
+        }
+    }
+    pub fn wall(self) -> &'static str {
+        match self {
+            Language::English => "wall at",
+            Language::German => "Wand bei",
+        }
+    }
+    pub fn has_won(self) -> &'static str {
+        match self {
+            Language::English => "has won the game",
+            Language::German => "hat das Spiel gewonnen",
+        }
+    }
+    pub fn wins_race(self, plies: usize) -> String {
+        match self {
+            Language::English => format!("wins the race in {} plies", plies),
+            Language::German => format!("gewinnt das Rennen in {} Halbzügen", plies),
+        }
+    }
+    pub fn move_taken_back(self) -> &'static str {
+        match self {
+            Language::English => "move taken back",
+            Language::German => "Zug zurückgenommen",
+        }
+    }
+    pub fn out_of_time(self) -> &'static str {
+        match self {
+            Language::English => "out of time",
+            Language::German => "Zeit abgelaufen",
+        }
+    }
+    pub fn hint(self) -> &'static str {
+        match self {
+            Language::English => "hint",
+            Language::German => "Tipp",
+        }
+    }
+    pub fn direction(self, direction: DirectionsSquare) -> &'static str {
+        use self::DirectionsSquare::*;
+        use self::Language::*;
+        match (self, direction) {
+            (English, Up) => "up",
+            (English, Down) => "down",
+            (English, Left) => "left",
+            (English, Right) => "right",
+            (German, Up) => "oben",
+            (German, Down) => "unten",
+            (German, Left) => "links",
+            (German, Right) => "rechts",
+        }
+    }
+    pub fn wall_orientation(self, orientation: WallOrientation) -> &'static str {
+        use self::Language::*;
+        match (self, orientation) {
+            (English, WallOrientation::Horizontal) => "horizontal",
+            (English, WallOrientation::Vertical) => "vertical",
+            (German, WallOrientation::Horizontal) => "waagerecht",
+            (German, WallOrientation::Vertical) => "senkrecht",
+        }
+    }
+    pub fn game_error(self, error: GameError) -> String {
+        match (self, error) {
+            (Language::English, error) => error.to_string(),
+            (Language::German, GameError::Move(error)) => self.move_error(error),
+            (Language::German, GameError::WallPlacment(error)) => self.wall_error(error),
+            (Language::German, GameError::GameOver { winner }) => format!(
+                "Spiel ist bereits beendet, {} hat gewonnen",
+                self.player_name(winner)
+            ),
+            (Language::German, GameError::NothingToUndo) => {
+                "kein Zug zum Zurücknehmen vorhanden".to_string()
+            }
+        }
+    }
+    fn move_error(self, error: MoveError) -> String {
+        use self::MoveError::*;
+        match error {
+            BoardBoundary { field, direction } => format!(
+                "Zug nach {} von {} nicht möglich: Spielfeldrand erreicht",
+                self.direction(direction),
+                field
+            ),
+            Wall { field, direction } => format!(
+                "Zug nach {} von {} nicht möglich: Wand im Weg",
+                self.direction(direction),
+                field
+            ),
+            FieldsNotAdjacent { from, to } => {
+                format!("Felder {} und {} sind nicht benachbart", from, to)
+            }
+        }
+    }
+    fn wall_error(self, error: WallPlacmentError) -> String {
+        use self::WallPlacmentError::*;
+        match error {
+            BoardBoundary { field, direction } => format!(
+                "Wand bei {} nach {} würde das Spielfeld verlassen",
+                field,
+                self.direction(direction)
+            ),
+            WallAlreadyPlaced { field } => format!("neben {} ist bereits eine Wand gesetzt", field),
+            NoMoreWalls { player } => format!("{} hat keine Wände mehr übrig", self.player_name(player)),
+            PlayerBlocked { player } => format!(
+                "Wand würde {} vom Ziel abschneiden",
+                self.player_name(player)
+            ),
+            WallsAlreadyCrossing { field } => {
+                format!("Wände würden sich an der Ecke von {} kreuzen", field)
+            }
+            NotConnected => "Wandteile sind nicht verbunden".to_string(),
+        }
+    }
+}
@@ -0,0 +1,262 @@
+mod language;
+pub use self::language::*;
+
+use board_square::*;
+
+// position of an entry within the game: the ply it belongs to and the game time in seconds
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Timestamp {
+    pub ply: usize,
+    pub seconds: f64,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum LogEntry {
+    GameStarted,
+    MovePlayed {
+        player: TwoPlayerIndices,
+        from: FieldIndexSquare<usize>,
+        to: FieldIndexSquare<usize>,
+    },
+    WallPlaced {
+        player: TwoPlayerIndices,
+        slot: WallSlot,
+    },
+    ActionRejected {
+        player: TwoPlayerIndices,
+        error: GameError,
+    },
+    GameOver {
+        winner: TwoPlayerIndices,
+    },
+    // no walls are left, the winner is forced
+    RaceDecided {
+        winner: TwoPlayerIndices,
+        plies: usize,
+    },
+    // the clock of the player ran out
+    TimeForfeit {
+        player: TwoPlayerIndices,
+    },
+    // the ply given in the timestamp was taken back
+    Undo {
+        player: TwoPlayerIndices,
+    },
+    // the engine suggested the move to the player
+    HintGiven {
+        player: TwoPlayerIndices,
+        hint: Move,
+    },
+}
+
+impl LogEntry {
+    pub fn to_text(&self, language: Language) -> String {
+        use self::LogEntry::*;
+        match *self {
+            GameStarted => language.game_started().to_string(),
+            MovePlayed { player, from, to } => format!(
+                "{}: {} {} -> {}",
+                language.player_name(player),
+                language.pawn(),
+                from,
+                to
+            ),
+            WallPlaced { player, slot } => format!(
+                "{}: {} {} {}",
+                language.player_name(player),
+                language.wall(),
+                slot.anchor,
+                language.wall_orientation(slot.orientation)
+            ),
+            ActionRejected { player, error } => format!(
+                "{}: {}",
+                language.player_name(player),
+                language.game_error(error)
+            ),
+            GameOver { winner } => format!(
+                "{} {}",
+                language.player_name(winner),
+                language.has_won()
+            ),
+            RaceDecided { winner, plies } => format!(
+                "{} {}",
+                language.player_name(winner),
+                language.wins_race(plies)
+            ),
+            TimeForfeit { player } => format!(
+                "{}: {}",
+                language.player_name(player),
+                language.out_of_time()
+            ),
+            Undo { player } => format!(
+                "{}: {}",
+                language.player_name(player),
+                language.move_taken_back()
+            ),
+            HintGiven { player, hint } => {
+                let hint_text = match hint {
+                    Move::Step(direction) => language.direction(direction).to_string(),
+                    Move::Wall(slot) => format!(
+                        "{} {} {}",
+                        language.wall(),
+                        slot.anchor,
+                        language.wall_orientation(slot.orientation)
+                    ),
+                };
+                format!(
+                    "{}: {} {}",
+                    language.player_name(player),
+                    language.hint(),
+                    hint_text
+                )
+            }
+        }
+    }
+    pub fn to_json(&self) -> String {
+        use self::LogEntry::*;
+        match *self {
+            GameStarted => "\"type\":\"game_started\"".to_string(),
+            MovePlayed { player, from, to } => format!(
+                "\"type\":\"move_played\",\"player\":\"{}\",\"from\":{},\"to\":{}",
+                player_to_json(player),
+                field_to_json(from),
+                field_to_json(to)
+            ),
+            WallPlaced { player, slot } => format!(
+                "\"type\":\"wall_placed\",\"player\":\"{}\",\"field\":{},\"orientation\":{}",
+                player_to_json(player),
+                field_to_json(slot.anchor),
+                string_to_json(&slot.orientation.to_string())
+            ),
+            ActionRejected { player, error } => format!(
+                "\"type\":\"action_rejected\",\"player\":\"{}\",\"error\":{}",
+                player_to_json(player),
+                string_to_json(&error.to_string())
+            ),
+            GameOver { winner } => format!(
+                "\"type\":\"game_over\",\"winner\":\"{}\"",
+                player_to_json(winner)
+            ),
+            RaceDecided { winner, plies } => format!(
+                "\"type\":\"race_decided\",\"winner\":\"{}\",\"plies\":{}",
+                player_to_json(winner),
+                plies
+            ),
+            TimeForfeit { player } => format!(
+                "\"type\":\"time_forfeit\",\"player\":\"{}\"",
+                player_to_json(player)
+            ),
+            Undo { player } => format!(
+                "\"type\":\"undo\",\"player\":\"{}\"",
+                player_to_json(player)
+            ),
+            HintGiven { player, hint } => format!(
+                "\"type\":\"hint\",\"player\":\"{}\",\"move\":{}",
+                player_to_json(player),
+                string_to_json(&hint.to_string())
+            ),
+        }
+    }
+}
+
+/// The text as a JSON string, quoted and with quotes, backslashes and control characters
+/// escaped.
+pub fn string_to_json(text: &str) -> String {
+    let mut json = String::from("\"");
+    for character in text.chars() {
+        match character {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            character if character.is_control() => {
+                json.push_str(&format!("\\u{:04x}", character as u32))
+            }
+            character => json.push(character),
+        }
+    }
+    json.push('"');
+    json
+}
+// JSON has no infinite numbers and no NaN, they are written as null
+fn seconds_to_json(seconds: f64) -> String {
+    if seconds.is_finite() {
+        seconds.to_string()
+    } else {
+        "null".to_string()
+    }
+}
+fn field_to_json(field: FieldIndexSquare<usize>) -> String {
+    format!("{{\"column\":{},\"row\":{}}}", field.column, field.row)
+}
+fn player_to_json(player: TwoPlayerIndices) -> &'static str {
+    match player {
+        TwoPlayerIndices::White => "white",
+        TwoPlayerIndices::Black => "black",
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct Logbook {
+    entries: Vec<(Timestamp, LogEntry)>,
+}
+impl Logbook {
+    pub fn new() -> Self {
+        Logbook {
+            entries: Vec::new(),
+        }
+    }
+    pub fn push(&mut self, timestamp: Timestamp, entry: LogEntry) {
+        self.entries.push((timestamp, entry));
+    }
+    pub fn get_entries(&self) -> &Vec<(Timestamp, LogEntry)> {
+        &self.entries
+    }
+    // moves which are still on the board, i.e. played and not taken back, in playing order
+    pub fn get_played_moves(&self) -> Vec<&LogEntry> {
+        let mut played_moves = Vec::new();
+        for (_, entry) in &self.entries {
+            match *entry {
+                LogEntry::MovePlayed { .. } | LogEntry::WallPlaced { .. } => {
+                    played_moves.push(entry)
+                }
+                LogEntry::Undo { .. } => {
+                    played_moves.pop();
+                }
+                _ => {}
+            }
+        }
+        played_moves
+    }
+    pub fn to_text(&self, language: Language) -> String {
+        self.entries
+            .iter()
+            .map(|&(timestamp, ref entry)| {
+                let seconds = timestamp.seconds as u64;
+                format!(
+                    "[{:02}:{:02}] {}. {}",
+                    seconds / 60,
+                    seconds % 60,
+                    timestamp.ply,
+                    entry.to_text(language)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+    pub fn to_json(&self) -> String {
+        let entries = self.entries
+            .iter()
+            .map(|&(timestamp, ref entry)| {
+                format!(
+                    "{{\"ply\":{},\"seconds\":{},{}}}",
+                    timestamp.ply,
+                    seconds_to_json(timestamp.seconds),
+                    entry.to_json()
+                )
+            })
+            .collect::<Vec<_>>();
+        format!("[{}]", entries.join(","))
+    }
+}
@@ -1,54 +1,859 @@
-extern crate num_traits;
-mod board_square;
-use board_square::*;
+extern crate varidor;
+use varidor::ai::*;
+use varidor::analysis::*;
+use varidor::archive::*;
+use varidor::book::*;
+use varidor::clock::*;
+use varidor::evaluation::*;
+use varidor::game_review::*;
+use varidor::perft::*;
+use varidor::protocol::*;
+use varidor::puzzle::*;
+use varidor::tablebase::*;
+use varidor::*;
 
-fn main() {
-    let mut board = SquareBoard3::new();
-    board.place_wall(
-        TwoPlayerIndices::White,
-        FieldIndexSquare { column: 2, row: 1 },
-        DirectionsSquare::Up,
-        WallDirections::Left,
+use std::env;
+use std::fs;
+use std::io::{self, BufRead};
+use std::process;
+use std::time::Instant;
+
+const USAGE: &str = "usage:
+    varidor perft <board size> <depth> [--divide]
+    varidor walls <board size> <count> [move]...
+    varidor tablebase <board size> <wall count> [file]
+    varidor book <board size> <plies> <book file> (<record directory> | --self-play <games>)
+    varidor explore <board size> <book file> [move]...
+    varidor tune <board size> <record directory> [iterations]
+    varidor play <board size> <time control> <white> <black> [--archive <archive file>]
+    varidor archive <archive file> import <record file or directory>
+    varidor archive <archive file> (search | export <record file>) [filter]...
+    varidor study <board size> [record file]
+    varidor review <record file> <depth> [annotated record file]
+    varidor puzzle (verify | solve) <puzzle file> [number]
+    varidor puzzle generate <board size> <puzzle file> (<record file> | --self-play <games>)
+        [filter]...
+    varidor demo
+
+board sizes: 3, 4, 5, 9
+tablebases are available for the board sizes 3 and 4
+moves are played from the start position, e.g. \"step up\" or \"wall (1,2) vertical\"
+time controls in seconds: 300 (sudden death), 180+2 (increment), 600b30 (byoyomi)
+players of play: an engine program or - for moves typed on the standard input
+study commands: <move>, back, forward, start, end, promote, delete, comment [text], save <file>,
+    quit
+archive filters: --player <name>, --board <size>, --walls <count>, --result <white|black|none>,
+    --opening \"<moves>\", --position \"<position>\"
+puzzle filters: --min-moves, --max-moves, --min-walls, --max-walls, each with a count";
+
+// calls the generic function with the board type belonging to the size given at runtime
+macro_rules! with_board_size {
+    ($size:expr, $function:ident($($argument:expr),*)) => {
+        match $size {
+            3 => $function::<SquareBoard3>($($argument),*),
+            4 => $function::<SquareBoard4>($($argument),*),
+            5 => $function::<SquareBoard5>($($argument),*),
+            9 => $function::<SquareBoard9>($($argument),*),
+            size => Err(format!("unsupported board size {}", size)),
+        }
+    };
+}
+
+fn parse_number(argument: Option<&String>, name: &str) -> Result<usize, String> {
+    match argument {
+        Some(argument) => argument
+            .parse()
+            .map_err(|_| format!("{} has to be a number, found '{}'", name, argument)),
+        None => Err(format!("{} is missing", name)),
+    }
+}
+
+fn run_perft<Board>(depth: usize, divide: bool) -> Result<(), String>
+where
+    Board: BoardTrait<
+            PlayerIndexType = TwoPlayerIndices,
+            FieldIndexType = FieldIndexSquare<usize>,
+            DirectionsType = DirectionsSquare,
+            WallDirectionType = WallDirections,
+            WallSlotType = WallSlot,
+        >
+        + Clone,
+{
+    let board = Board::new();
+    let player = TwoPlayerIndices::White;
+    if divide {
+        let mut total = 0;
+        for (next_move, count) in perft_divide(&board, player, depth) {
+            println!("{}: {}", next_move, count);
+            total += count;
+        }
+        println!("total: {}", total);
+    } else {
+        for current_depth in 1..depth + 1 {
+            println!("perft({}) = {}", current_depth, perft(&board, player, current_depth));
+        }
+    }
+    Ok(())
+}
+
+// plays the moves from the start position, returns the board and the player to move
+fn play_moves<Board>(moves: &[String]) -> Result<(Board, TwoPlayerIndices), String>
+where
+    Board: BoardTrait<
+        PlayerIndexType = TwoPlayerIndices,
+        FieldIndexType = FieldIndexSquare<usize>,
+        DirectionsType = DirectionsSquare,
+        WallSlotType = WallSlot,
+    >,
+{
+    let mut board = Board::new();
+    let mut player = TwoPlayerIndices::White;
+    for text in moves {
+        let next_move = text.parse::<Move>().map_err(|error| error.to_string())?;
+        board
+            .play_move(player, next_move)
+            .map_err(|error| format!("{}: {}", text, error))?;
+        player = player.next_player();
+    }
+    Ok((board, player))
+}
+
+fn run_walls<Board>(count: usize, moves: &[String]) -> Result<(), String>
+where
+    Board: BoardTrait<
+            PlayerIndexType = TwoPlayerIndices,
+            FieldIndexType = FieldIndexSquare<usize>,
+            DirectionsType = DirectionsSquare,
+            WallSlotType = WallSlot,
+        >
+        + Clone,
+{
+    let (board, player) = play_moves::<Board>(moves)?;
+    println!("{} to move", player);
+    for evaluation in board.most_damaging_walls(player, count) {
+        println!("{}", evaluation);
+    }
+    Ok(())
+}
+
+fn run_tablebase<Board>(rules: Rules, path: Option<&String>) -> Result<(), String>
+where
+    Board: TablebaseBoard + Clone,
+{
+    let tablebase = Tablebase::generate::<Board>(rules).map_err(|error| error.to_string())?;
+    println!("{}", tablebase);
+    let board = Board::with_rules(rules);
+    let player = TwoPlayerIndices::White;
+    match tablebase.get_best_move(&board, player) {
+        Some((next_move, result)) => println!("{} to move: {}, e.g. {}", player, result, next_move),
+        None => println!("{} to move: not solved", player),
+    }
+    if let Some(path) = path {
+        tablebase
+            .save(path)
+            .map_err(|error| format!("cannot write {}: {}", path, error))?;
+    }
+    Ok(())
+}
+
+// self-play games for the book: two random plies, then a shallow search
+const SELF_PLAY_RANDOM_PLIES: usize = 2;
+const SELF_PLAY_DEPTH: usize = 2;
+
+fn run_book<Board>(max_plies: usize, path: &str, source: &[String]) -> Result<(), String>
+where
+    Board: TablebaseBoard + Clone,
+{
+    let mut book = OpeningBook::new(max_plies);
+    match source.first().map(|x| x.as_str()) {
+        Some("--self-play") => {
+            let games = parse_number(source.get(1), "number of games")?;
+            let settings = SearchSettings {
+                depth: SELF_PLAY_DEPTH,
+                ..SearchSettings::default()
+            };
+            let mut random = Random::new(games as u64);
+            for _ in 0..games {
+                let record = self_play::<Board, _>(
+                    Rules::default(),
+                    &settings,
+                    SELF_PLAY_RANDOM_PLIES,
+                    &mut random,
+                );
+                book.add_record::<Board>(&record)
+                    .map_err(|error| error.to_string())?;
+            }
+            println!("{} games played", games);
+        }
+        Some(directory) => {
+            let count = book.add_directory::<Board, _>(directory)
+                .map_err(|error| format!("cannot read {}: {}", directory, error))?;
+            println!("{} records read", count);
+        }
+        None => return Err("record directory or --self-play is missing".to_string()),
+    }
+    println!("{} positions", book.len());
+    book.save(path)
+        .map_err(|error| format!("cannot write {}: {}", path, error))
+}
+
+fn run_explore<Board>(path: &str, moves: &[String]) -> Result<(), String>
+where
+    Board: TablebaseBoard + Clone,
+{
+    let book = OpeningBook::load(path).map_err(|error| format!("cannot read {}: {}", path, error))?;
+    let (board, player) = play_moves::<Board>(moves)?;
+    println!("{} to move", player);
+    for book_move in book.explore(&board, player) {
+        println!(
+            "{}: {} games, {:.1}% for {}",
+            book_move.next_move,
+            book_move.games,
+            100. * book_move.get_win_rate(),
+            player
+        );
+    }
+    Ok(())
+}
+
+fn run_tune<Board>(directory: &str, iterations: usize) -> Result<(), String>
+where
+    Board: PositionBoard + Clone,
+{
+    let records = read_records_from_directory(directory)
+        .map_err(|error| format!("cannot read {}: {}", directory, error))?;
+    let samples = get_tuning_samples::<Board>(&records).map_err(|error| error.to_string())?;
+    let tuner = Tuner {
+        iterations,
+        ..Tuner::default()
+    };
+    let result = tuner.tune(LinearEvaluator::default(), &samples);
+    println!("{} positions", samples.len());
+    println!(
+        "error {:.4} -> {:.4}",
+        result.error_before, result.error_after
     );
-    board.place_wall(
-        TwoPlayerIndices::White,
-        FieldIndexSquare { column: 2, row: 1 },
-        DirectionsSquare::Up,
-        WallDirections::Right,
+    println!("{}", result.evaluator);
+    Ok(())
+}
+
+// the next move typed on the standard input, the time taken runs the clock
+fn read_move<Board>(game: &mut GameData<Board>) -> Result<Option<Move>, String>
+where
+    Board: BoardTrait<
+        PlayerIndexType = TwoPlayerIndices,
+        DirectionsType = DirectionsSquare,
+        FieldIndexType = FieldIndexSquare<usize>,
+        WallDirectionType = WallDirections,
+        WallSlotType = WallSlot,
+    >,
+{
+    let start = Instant::now();
+    let stdin = io::stdin();
+    let mut line = String::new();
+    loop {
+        line.clear();
+        println!("{} to move:", game.get_current_player());
+        let read = stdin.lock().read_line(&mut line).map_err(|error| error.to_string())?;
+        if read == 0 {
+            return Err("standard input closed".to_string());
+        }
+        match line.trim().parse::<Move>() {
+            Ok(next_move) => {
+                game.add_time(start.elapsed().as_secs_f64());
+                return Ok(if game.is_game_over() {
+                    None
+                } else {
+                    Some(next_move)
+                });
+            }
+            Err(error) => println!("{}", error),
+        }
+    }
+}
+
+fn run_play<Board>(
+    time_control: TimeControl,
+    players: &[&String],
+    archive_path: Option<&String>,
+) -> Result<(), String>
+where
+    Board: BoardTrait<
+        PlayerIndexType = TwoPlayerIndices,
+        DirectionsType = DirectionsSquare,
+        FieldIndexType = FieldIndexSquare<usize>,
+        WallDirectionType = WallDirections,
+        WallSlotType = WallSlot,
+    >,
+{
+    // the engines of White and Black, None for players on the standard input
+    let mut engines = vec![];
+    for &program in players {
+        engines.push(match program.as_str() {
+            "-" => None,
+            program => Some(
+                EngineProcess::spawn(program, &[])
+                    .map_err(|error| format!("cannot start {}: {}", program, error))?,
+            ),
+        });
+    }
+    let mut game = GameData::<Board>::new();
+    game.set_time_control(time_control);
+    while !game.is_game_over() {
+        let player = game.get_current_player();
+        let engine = match player {
+            TwoPlayerIndices::White => &mut engines[0],
+            TwoPlayerIndices::Black => &mut engines[1],
+        };
+        let result = match *engine {
+            Some(ref mut engine) => game.play_engine_move(engine, GoLimits::default())
+                .map(Some)
+                .map_err(|error| error.to_string()),
+            None => match read_move(&mut game)? {
+                Some(next_move) => game.play_move(next_move)
+                    .map(|_| Some(next_move))
+                    .map_err(|error| error.to_string()),
+                None => Ok(None),
+            },
+        };
+        match result {
+            Ok(Some(next_move)) => {
+                let seconds = game.clock.as_ref().map_or(0., |clock| {
+                    clock.get_time_left(player, game.get_current_player())
+                });
+                println!("{}: {} ({:.1} s left)", player, next_move, seconds);
+            }
+            Ok(None) => {}
+            // a game lost on time ends the loop
+            Err(_) if game.is_game_over() => {}
+            Err(error) if engine.is_some() => return Err(error),
+            Err(error) => println!("{}", error),
+        }
+    }
+    if let Some(player) = game.get_time_forfeit() {
+        println!("{} is out of time", player);
+    }
+    let mut record = game.to_record();
+    record.set_tag("White", players[0]);
+    record.set_tag("Black", players[1]);
+    print!("{}", record);
+    if let Some(path) = archive_path {
+        let id = Archive::open(path)
+            .and_then(|mut archive| archive.add(&record))
+            .map_err(|error| format!("cannot archive the game in {}: {}", path, error))?;
+        println!("archived as game {}", id);
+    }
+    Ok(())
+}
+
+fn parse_query(filters: &[String]) -> Result<GameQuery, String> {
+    let mut query = GameQuery::default();
+    let mut filters = filters.iter();
+    while let Some(filter) = filters.next() {
+        let value = filters
+            .next()
+            .ok_or_else(|| format!("value of {} is missing", filter))?;
+        match filter.as_str() {
+            "--player" => query.player = Some(value.clone()),
+            "--board" => query.board_size = Some(parse_number(Some(value), "board size")?),
+            "--walls" => {
+                query.rules = Some(Rules {
+                    wall_count: parse_number(Some(value), "wall count")?,
+                })
+            }
+            "--result" => {
+                query.result = Some(match value.as_str() {
+                    "white" => Some(TwoPlayerIndices::White),
+                    "black" => Some(TwoPlayerIndices::Black),
+                    "none" => None,
+                    _ => return Err(format!("unknown result '{}'", value)),
+                })
+            }
+            "--opening" => query.opening = Move::parse_all(value).map_err(|x| x.to_string())?,
+            "--position" => {
+                let position = value.parse::<Position>().map_err(|x| x.to_string())?;
+                query.position_hash = Some(position.get_canonical_hash());
+            }
+            _ => return Err(format!("unknown filter '{}'\n{}", filter, USAGE)),
+        }
+    }
+    Ok(query)
+}
+
+fn run_archive(path: &str, arguments: &[String]) -> Result<(), String> {
+    let mut archive =
+        Archive::open(path).map_err(|error| format!("cannot read {}: {}", path, error))?;
+    match arguments.first().map(|x| x.as_str()) {
+        Some("import") => {
+            let source = arguments.get(1).ok_or("record file is missing")?;
+            let count = archive
+                .import(source)
+                .map_err(|error| format!("cannot import {}: {}", source, error))?;
+            println!("{} games imported, {} games archived", count, archive.len());
+        }
+        Some("search") => {
+            let query = parse_query(&arguments[1..])?;
+            for game in archive.search(&query) {
+                let result = match game.record.winner {
+                    Some(winner) => winner.get_color_as_string(),
+                    None => "none",
+                };
+                println!(
+                    "{} {} {} - {}, board {}, {} walls, result {}, {} plies",
+                    game.id,
+                    game.get_date(),
+                    game.get_player(TwoPlayerIndices::White),
+                    game.get_player(TwoPlayerIndices::Black),
+                    game.record.board_size,
+                    game.record.rules.wall_count,
+                    result,
+                    game.get_length()
+                );
+            }
+        }
+        Some("export") => {
+            let target = arguments.get(1).ok_or("record file is missing")?;
+            let query = parse_query(&arguments[2..])?;
+            let count = archive
+                .export(target, &query)
+                .map_err(|error| format!("cannot write {}: {}", target, error))?;
+            println!("{} games exported", count);
+        }
+        _ => return Err(USAGE.to_string()),
+    }
+    Ok(())
+}
+
+fn print_analysis<Board>(analysis: &AnalysisBoard<Board>)
+where
+    Board: PositionBoard + Clone,
+{
+    let line = analysis
+        .get_line()
+        .iter()
+        .map(|x| x.to_string())
+        .collect::<Vec<_>>();
+    println!("ply {}: {}", analysis.get_ply(), line.join(", "));
+    if let Some(comment) = analysis.get_comment() {
+        println!("{{{}}}", comment);
+    }
+    match analysis.get_board().get_winner() {
+        Some(winner) => println!("{} has won", winner),
+        None => println!("{} to move", analysis.get_current_player()),
+    }
+    let continuations = analysis
+        .get_continuations()
+        .iter()
+        .map(|x| x.to_string())
+        .collect::<Vec<_>>();
+    if !continuations.is_empty() {
+        println!("continuations: {}", continuations.join(" | "));
+    }
+}
+
+fn run_study<Board>(path: Option<&String>) -> Result<(), String>
+where
+    Board: PositionBoard + Clone,
+{
+    let mut analysis = match path {
+        Some(path) => {
+            let text = fs::read_to_string(path)
+                .map_err(|error| format!("cannot read {}: {}", path, error))?;
+            let record = text.parse::<GameRecord>().map_err(|error| error.to_string())?;
+            AnalysisBoard::<Board>::from_record(&record).map_err(|error| error.to_string())?
+        }
+        None => AnalysisBoard::new(Rules::default()),
+    };
+    print_analysis(&analysis);
+    let stdin = io::stdin();
+    for line in stdin.lock().lines() {
+        let line = line.map_err(|error| error.to_string())?;
+        let (command, argument) = match line.trim().split_once(' ') {
+            Some((command, argument)) => (command, argument.trim()),
+            None => (line.trim(), ""),
+        };
+        match command {
+            "" => continue,
+            "quit" => break,
+            "back" => {
+                analysis.back();
+            }
+            "forward" => {
+                analysis.forward();
+            }
+            "start" => analysis.goto_start(),
+            "end" => analysis.goto_end(),
+            "promote" => analysis.promote_variation(),
+            "delete" => {
+                analysis.delete_variation();
+            }
+            "comment" if argument.is_empty() => analysis.set_comment(None),
+            "comment" => analysis.set_comment(Some(argument.to_string())),
+            "save" => match fs::write(argument, analysis.to_record().to_string()) {
+                Ok(()) => println!("saved to {}", argument),
+                Err(error) => println!("cannot write {}: {}", argument, error),
+            },
+            _ => match line.parse::<Move>() {
+                Ok(next_move) => {
+                    if let Err(error) = analysis.play_move(next_move) {
+                        println!("{}", error);
+                    }
+                }
+                Err(error) => println!("{}", error),
+            },
+        }
+        print_analysis(&analysis);
+    }
+    Ok(())
+}
+
+fn run_review<Board>(record: &GameRecord, depth: usize) -> Result<GameRecord, String>
+where
+    Board: TablebaseBoard + Clone,
+{
+    let review = analyze_game::<Board>(record, depth).map_err(|error| error.to_string())?;
+    for move_review in &review.moves {
+        if let Some(judgement) = move_review.judgement {
+            let best_move = move_review
+                .get_best_move()
+                .map(|x| x.to_string())
+                .unwrap_or_default();
+            println!(
+                "{}. {} {}: {}, better {} ({:.0}% -> {:.0}%)",
+                move_review.ply + 1,
+                move_review.player,
+                move_review.played,
+                judgement,
+                best_move,
+                100. * get_win_chance(move_review.best_score),
+                100. * get_win_chance(move_review.played_score)
+            );
+        }
+    }
+    for &player in &[TwoPlayerIndices::White, TwoPlayerIndices::Black] {
+        println!("{}: {}", player, review.get_summary(player));
+    }
+    Ok(review.record)
+}
+
+fn run_verify_puzzle<Board>(puzzle: &Puzzle) -> Result<(), String>
+where
+    Board: TablebaseBoard + Clone,
+{
+    puzzle.verify::<Board>().map_err(|error| error.to_string())
+}
+
+fn run_solve_puzzle<Board>(puzzle: Puzzle) -> Result<(), String>
+where
+    Board: PositionBoard<WallDirectionType = WallDirections>,
+{
+    let mut game = PuzzleGame::<Board>::new(puzzle).map_err(|error| error.to_string())?;
+    println!("{}", game.puzzle.position);
+    println!(
+        "{} to move, win in {}",
+        game.puzzle.get_solver(),
+        game.puzzle.get_move_count()
     );
-    {
-        let shortest_paths = board
-            .get_player_data(TwoPlayerIndices::Black)
-            .get_shortest_paths();
-
-        let FieldIndexSquare {
-            column: cc,
-            row: rr,
-        } = board.get_current_field(TwoPlayerIndices::Black);
-        for path in shortest_paths {
-            println!("---");
-            println!("   c:{:?}, r:{:?}", cc, rr);
-            for &(FieldIndexSquare { column: c, row: r }, _) in path {
-                println!("   c:{:?}, r:{:?}", c, r);
-            }
-        }
-    }
-    board.move_player(TwoPlayerIndices::Black, DirectionsSquare::Left);
-    {
-        let shortest_paths = board
-            .get_player_data(TwoPlayerIndices::Black)
-            .get_shortest_paths();
-
-        let FieldIndexSquare {
-            column: cc,
-            row: rr,
-        } = board.get_current_field(TwoPlayerIndices::Black);
-        for path in shortest_paths {
-            println!("---");
-            println!("   c:{:?}, r:{:?}", cc, rr);
-            for &(FieldIndexSquare { column: c, row: r }, _) in path {
-                println!("   c:{:?}, r:{:?}", c, r);
+    let stdin = io::stdin();
+    for line in stdin.lock().lines() {
+        let line = line.map_err(|error| error.to_string())?;
+        let next_move = match line.trim().parse::<Move>() {
+            Ok(next_move) => next_move,
+            Err(error) => {
+                println!("{}", error);
+                continue;
+            }
+        };
+        let ply = game.get_ply();
+        match game.play_move(next_move) {
+            Ok(PuzzleState::Solving) => println!("{}", game.puzzle.solution[ply + 1]),
+            Ok(PuzzleState::Solved) => {
+                println!("solved");
+                return Ok(());
+            }
+            Ok(PuzzleState::Failed) => {
+                let solution = game.puzzle.solution[ply..]
+                    .iter()
+                    .map(|x| x.to_string())
+                    .collect::<Vec<_>>();
+                println!("failed, the solution is {}", solution.join(", "));
+                return Ok(());
+            }
+            Err(error) => println!("{}", error),
+        }
+    }
+    Ok(())
+}
+
+fn run_puzzle(command: &str, path: &str, number: Option<&String>) -> Result<(), String> {
+    let text =
+        fs::read_to_string(path).map_err(|error| format!("cannot read {}: {}", path, error))?;
+    let puzzles = Puzzle::parse_all(&text).map_err(|error| error.to_string())?;
+    match command {
+        "verify" => {
+            let mut failures = 0;
+            for (index, puzzle) in puzzles.iter().enumerate() {
+                let size = puzzle.position.board_size;
+                match with_board_size!(size, run_verify_puzzle(puzzle)) {
+                    Ok(()) => println!("{}: ok", index + 1),
+                    Err(error) => {
+                        println!("{}: {}", index + 1, error);
+                        failures += 1;
+                    }
+                }
+            }
+            if failures > 0 {
+                return Err(format!("{} of {} puzzles are not sound", failures, puzzles.len()));
+            }
+            Ok(())
+        }
+        "solve" => {
+            let number = match number {
+                Some(_) => parse_number(number, "puzzle number")?,
+                None => 1,
+            };
+            let puzzle = number
+                .checked_sub(1)
+                .and_then(|index| puzzles.get(index))
+                .ok_or_else(|| format!("there is no puzzle {}", number))?
+                .clone();
+            let size = puzzle.position.board_size;
+            with_board_size!(size, run_solve_puzzle(puzzle))
+        }
+        command => Err(format!("unknown puzzle command '{}'\n{}", command, USAGE)),
+    }
+}
+
+fn parse_puzzle_filter(filters: &[String]) -> Result<PuzzleFilter, String> {
+    let mut filter = PuzzleFilter::default();
+    let mut filters = filters.iter();
+    while let Some(name) = filters.next() {
+        let count = parse_number(filters.next(), name)?;
+        match name.as_str() {
+            "--min-moves" => filter.min_moves = count,
+            "--max-moves" => filter.max_moves = count,
+            "--min-walls" => filter.min_walls = count,
+            "--max-walls" => filter.max_walls = count,
+            _ => return Err(format!("unknown filter '{}'\n{}", name, USAGE)),
+        }
+    }
+    Ok(filter)
+}
+
+fn run_generate_puzzles<Board>(path: &str, arguments: &[String]) -> Result<(), String>
+where
+    Board: TablebaseBoard + Clone,
+{
+    // puzzles already in the file are kept and not generated again
+    let mut puzzles = match fs::read_to_string(path) {
+        Ok(text) => Puzzle::parse_all(&text).map_err(|error| error.to_string())?,
+        Err(ref error) if error.kind() == io::ErrorKind::NotFound => vec![],
+        Err(error) => return Err(format!("cannot read {}: {}", path, error)),
+    };
+    let (filter, games) = match arguments.first().map(|x| x.as_str()) {
+        Some("--self-play") => (
+            parse_puzzle_filter(arguments.get(2..).unwrap_or(&[]))?,
+            Some(parse_number(arguments.get(1), "number of games")?),
+        ),
+        Some(_) => (parse_puzzle_filter(&arguments[1..])?, None),
+        None => return Err("record file or --self-play is missing".to_string()),
+    };
+    let mut generator = PuzzleGenerator::new(filter);
+    for puzzle in &puzzles {
+        generator.add_known(puzzle);
+    }
+    match games {
+        Some(games) => {
+            let settings = SearchSettings {
+                depth: SELF_PLAY_DEPTH,
+                ..SearchSettings::default()
+            };
+            // other games for a file which already holds puzzles
+            let mut random = Random::new((games + puzzles.len()) as u64);
+            for _ in 0..games {
+                let record = self_play::<Board, _>(
+                    Rules::default(),
+                    &settings,
+                    SELF_PLAY_RANDOM_PLIES,
+                    &mut random,
+                );
+                generator
+                    .add_record::<Board>(&record)
+                    .map_err(|error| error.to_string())?;
             }
+            println!("{} games played", games);
         }
+        None => {
+            let source = &arguments[0];
+            let text = fs::read_to_string(source)
+                .map_err(|error| format!("cannot read {}: {}", source, error))?;
+            let records = GameRecord::parse_all(&text).map_err(|error| error.to_string())?;
+            // archives may hold games of several board sizes
+            let records = records
+                .iter()
+                .filter(|x| x.board_size == Board::AVERAGE_BOARD_SIZE)
+                .collect::<Vec<_>>();
+            for record in &records {
+                generator
+                    .add_record::<Board>(record)
+                    .map_err(|error| error.to_string())?;
+            }
+            println!("{} records read", records.len());
+        }
+    }
+    println!("{} puzzles found", generator.puzzles.len());
+    puzzles.append(&mut generator.puzzles);
+    let text = puzzles
+        .iter()
+        .map(|x| x.to_string())
+        .collect::<Vec<_>>()
+        .join("\n");
+    fs::write(path, text).map_err(|error| format!("cannot write {}: {}", path, error))
+}
+
+fn print_shortest_paths(board: &SquareBoard3, player: TwoPlayerIndices) {
+    let FieldIndexSquare {
+        column: cc,
+        row: rr,
+    } = board.get_current_field(player);
+    for path in board.get_player_data(player).get_shortest_paths().iter_paths() {
+        println!("---");
+        println!("   c:{:?}, r:{:?}", cc, rr);
+        for &(FieldIndexSquare { column: c, row: r }, _) in &path {
+            println!("   c:{:?}, r:{:?}", c, r);
+        }
+    }
+}
+
+fn run_demo() -> Result<(), String> {
+    let mut board = SquareBoard3::new();
+    for &wall_direction in &[WallDirections::Left, WallDirections::Right] {
+        if let Err(error) = board.place_wall(
+            TwoPlayerIndices::White,
+            FieldIndexSquare { column: 2, row: 1 },
+            DirectionsSquare::Up,
+            wall_direction,
+        ) {
+            println!("{}", error);
+        }
+    }
+    print_shortest_paths(&board, TwoPlayerIndices::Black);
+    if let Err(error) = board.move_player(TwoPlayerIndices::Black, DirectionsSquare::Left) {
+        println!("{}", error);
+    }
+    print_shortest_paths(&board, TwoPlayerIndices::Black);
+    Ok(())
+}
+
+fn run(arguments: &[String]) -> Result<(), String> {
+    match arguments.first().map(|x| x.as_str()) {
+        Some("perft") => {
+            let size = parse_number(arguments.get(1), "board size")?;
+            let depth = parse_number(arguments.get(2), "depth")?;
+            let divide = arguments.iter().any(|x| x == "--divide");
+            with_board_size!(size, run_perft(depth, divide))
+        }
+        Some("walls") => {
+            let size = parse_number(arguments.get(1), "board size")?;
+            let count = parse_number(arguments.get(2), "count")?;
+            let moves = arguments.get(3..).unwrap_or(&[]);
+            with_board_size!(size, run_walls(count, moves))
+        }
+        Some("tablebase") => {
+            let size = parse_number(arguments.get(1), "board size")?;
+            let rules = Rules {
+                wall_count: parse_number(arguments.get(2), "wall count")?,
+            };
+            let path = arguments.get(3);
+            match size {
+                3 => run_tablebase::<SquareBoard3>(rules, path),
+                4 => run_tablebase::<SquareBoard4>(rules, path),
+                size => Err(format!("no tablebase for board size {}", size)),
+            }
+        }
+        Some("book") => {
+            let size = parse_number(arguments.get(1), "board size")?;
+            let max_plies = parse_number(arguments.get(2), "plies")?;
+            let path = arguments.get(3).ok_or("book file is missing")?;
+            let source = arguments.get(4..).unwrap_or(&[]);
+            with_board_size!(size, run_book(max_plies, path, source))
+        }
+        Some("explore") => {
+            let size = parse_number(arguments.get(1), "board size")?;
+            let path = arguments.get(2).ok_or("book file is missing")?;
+            let moves = arguments.get(3..).unwrap_or(&[]);
+            with_board_size!(size, run_explore(path, moves))
+        }
+        Some("tune") => {
+            let size = parse_number(arguments.get(1), "board size")?;
+            let directory = arguments.get(2).ok_or("record directory is missing")?;
+            let iterations = match arguments.get(3) {
+                Some(_) => parse_number(arguments.get(3), "iterations")?,
+                None => Tuner::default().iterations,
+            };
+            with_board_size!(size, run_tune(directory, iterations))
+        }
+        Some("play") => {
+            let size = parse_number(arguments.get(1), "board size")?;
+            let time_control = arguments
+                .get(2)
+                .ok_or("time control is missing")?
+                .parse::<TimeControl>()
+                .map_err(|error| error.to_string())?;
+            let white = arguments.get(3).ok_or("white player is missing")?;
+            let black = arguments.get(4).ok_or("black player is missing")?;
+            let archive_path = match arguments.get(5).map(|x| x.as_str()) {
+                Some("--archive") => Some(arguments.get(6).ok_or("archive file is missing")?),
+                Some(option) => return Err(format!("unknown option '{}'\n{}", option, USAGE)),
+                None => None,
+            };
+            with_board_size!(size, run_play(time_control, &[white, black], archive_path))
+        }
+        Some("study") => {
+            let size = parse_number(arguments.get(1), "board size")?;
+            with_board_size!(size, run_study(arguments.get(2)))
+        }
+        Some("review") => {
+            let path = arguments.get(1).ok_or("record file is missing")?;
+            let depth = parse_number(arguments.get(2), "depth")?;
+            let text = fs::read_to_string(path)
+                .map_err(|error| format!("cannot read {}: {}", path, error))?;
+            let records = GameRecord::parse_all(&text).map_err(|error| error.to_string())?;
+            let mut annotated = String::new();
+            for record in &records {
+                let record = with_board_size!(record.board_size, run_review(record, depth))?;
+                annotated += &format!("{}\n", record);
+            }
+            match arguments.get(3) {
+                Some(output) => fs::write(output, annotated)
+                    .map_err(|error| format!("cannot write {}: {}", output, error)),
+                None => Ok(()),
+            }
+        }
+        Some("puzzle") if arguments.get(1).map(|x| x.as_str()) == Some("generate") => {
+            let size = parse_number(arguments.get(2), "board size")?;
+            let path = arguments.get(3).ok_or("puzzle file is missing")?;
+            let source = arguments.get(4..).unwrap_or(&[]);
+            with_board_size!(size, run_generate_puzzles(path, source))
+        }
+        Some("puzzle") => {
+            let command = arguments.get(1).ok_or("puzzle command is missing")?;
+            let path = arguments.get(2).ok_or("puzzle file is missing")?;
+            run_puzzle(command, path, arguments.get(3))
+        }
+        Some("archive") => {
+            let path = arguments.get(1).ok_or("archive file is missing")?;
+            run_archive(path, arguments.get(2..).unwrap_or(&[]))
+        }
+        Some("demo") => run_demo(),
+        _ => Err(USAGE.to_string()),
+    }
+}
+
+fn main() {
+    let arguments = env::args().skip(1).collect::<Vec<_>>();
+    if let Err(error) = run(&arguments) {
+        eprintln!("{}", error);
+        process::exit(1);
     }
 }
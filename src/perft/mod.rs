@@ -0,0 +1,63 @@
+//! Move generator verification: counts the positions reachable in a given number of plies.
+use board_square::*;
+
+/// Number of move sequences of exactly `depth` plies, starting with `player` to move.
+///
+/// Games which are won before `depth` plies are played do not contribute.
+pub fn perft<Board>(board: &Board, player: Board::PlayerIndexType, depth: usize) -> u64
+where
+    Board: BoardTrait + Clone,
+{
+    perft_in_place(&mut board.clone(), player, depth)
+}
+
+// walks the tree with make_move and unmake_move, the board is unchanged afterwards
+fn perft_in_place<Board>(board: &mut Board, player: Board::PlayerIndexType, depth: usize) -> u64
+where
+    Board: BoardTrait,
+{
+    if depth == 0 {
+        return 1;
+    }
+    if board.get_winner().is_some() {
+        return 0;
+    }
+    let mut count = 0;
+    for next_move in board.get_candidate_moves(player) {
+        if let Ok(token) = board.make_move(player, next_move) {
+            count += if depth == 1 {
+                1
+            } else {
+                perft_in_place(board, player.next_player(), depth - 1)
+            };
+            board.unmake_move(token);
+        }
+    }
+    count
+}
+
+/// `perft` split by the first move, in move generation order.
+pub fn perft_divide<Board>(
+    board: &Board,
+    player: Board::PlayerIndexType,
+    depth: usize,
+) -> Vec<(BoardMove<Board>, u64)>
+where
+    Board: BoardTrait + Clone,
+{
+    if depth == 0 || board.get_winner().is_some() {
+        return vec![];
+    }
+    let mut board = board.clone();
+    let mut counts = vec![];
+    for next_move in board.get_candidate_moves(player) {
+        if let Ok(token) = board.make_move(player, next_move) {
+            counts.push((
+                next_move,
+                perft_in_place(&mut board, player.next_player(), depth - 1),
+            ));
+            board.unmake_move(token);
+        }
+    }
+    counts
+}
@@ -0,0 +1,291 @@
+//! Line based protocol between a program managing games and an engine choosing the moves, so
+//! that engines outside this crate can play.
+//!
+//! The program writes `Command`s to the standard input of the engine, one per line, the engine
+//! answers with `Response`s on its standard output:
+//! ```text
+//! > varidor
+//! < id name varidor
+//! < id author Michael <v.mi@gmx.de>
+//! < varidorok
+//! > newgame 5 5
+//! > position startpos moves step down wall (1,2) vertical
+//! > go depth 3 wtime 60000 btime 60000 winc 1000 binc 1000
+//! < info depth 1 score -40 nodes 37 pv step down
+//! < info depth 2 score 150 nodes 2210 pv step down wall (2,1) horizontal
+//! < bestmove step down
+//! > quit
+//! ```
+//! Positions are written as by `Position`'s `Display`, moves as by `Move`'s `Display`, times
+//! are in milliseconds and scores are from the view of the player to move, see `ai::WIN_SCORE`.
+//! Lines which cannot be read are answered with `error <message>`.
+//!
+//! `EngineSession` implements the engine side around `ai::search_with`, `EngineProcess` runs an
+//! engine program, see `GameData::play_engine_move`.
+mod process;
+pub use self::process::*;
+mod session;
+pub use self::session::*;
+
+use board_square::*;
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// A line sent to the engine.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Command {
+    /// `varidor`: starts the protocol, answered by `id` lines and `varidorok`
+    Varidor,
+    /// `isready`: answered by `readyok`
+    IsReady,
+    /// `newgame <board size> <walls per player>`: starts a game from the start position
+    NewGame { board_size: usize, rules: Rules },
+    /// `position startpos [moves <move>...]` or `position <position> [moves <move>...]`, the
+    /// position `None` is the start position
+    Position {
+        position: Option<Position>,
+        moves: Vec<Move>,
+    },
+    /// `go [depth <plies>] [movetime <ms>] [wtime <ms>] [btime <ms>] [winc <ms>] [binc <ms>]
+    /// [byoyomi <ms>]`: searches the position, answered by `info` lines and `bestmove`
+    Go(GoLimits),
+    /// `quit`: ends the engine
+    Quit,
+}
+
+/// How long the engine may search, see `Command::Go`.
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+pub struct GoLimits {
+    pub depth: Option<usize>,
+    /// time for this move
+    pub move_time: Option<Duration>,
+    /// time left on the clock of White
+    pub white_time: Option<Duration>,
+    /// time left on the clock of Black
+    pub black_time: Option<Duration>,
+    /// added to the clock of White after every move
+    pub white_increment: Option<Duration>,
+    /// added to the clock of Black after every move
+    pub black_increment: Option<Duration>,
+    /// time the move may take in addition to the clock
+    pub byoyomi: Option<Duration>,
+}
+
+/// A line sent by the engine.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Response {
+    /// `id <key> <value>`, e.g. `id name varidor`
+    Id { key: String, value: String },
+    /// `varidorok`: the `id` lines are complete
+    VaridorOk,
+    /// `readyok`
+    ReadyOk,
+    /// `info depth <plies> score <score> nodes <count> pv <move>...`
+    Info(SearchInfo),
+    /// `bestmove <move>` or `bestmove none` if the game is over
+    BestMove(Option<Move>),
+    /// `error <message>`
+    Error(String),
+}
+
+/// Progress of a search, sent after every finished depth.
+#[derive(Clone, PartialEq, Debug)]
+pub struct SearchInfo {
+    pub depth: usize,
+    pub score: i32,
+    pub nodes: u64,
+    pub principal_variation: Vec<Move>,
+}
+
+fn parse_error(text: &str, expected: &'static str) -> ParseError {
+    ParseError {
+        text: text.to_string(),
+        expected,
+    }
+}
+
+fn parse_number<T: FromStr>(text: Option<&str>, expected: &'static str) -> Result<T, ParseError> {
+    let text = text.unwrap_or("");
+    text.parse().map_err(|_| parse_error(text, expected))
+}
+
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Command::Varidor => write!(f, "varidor"),
+            Command::IsReady => write!(f, "isready"),
+            Command::NewGame { board_size, rules } => {
+                write!(f, "newgame {} {}", board_size, rules.wall_count)
+            }
+            Command::Position {
+                ref position,
+                ref moves,
+            } => {
+                match *position {
+                    Some(ref position) => write!(f, "position {}", position)?,
+                    None => write!(f, "position startpos")?,
+                }
+                if !moves.is_empty() {
+                    write!(f, " moves")?;
+                    for next_move in moves {
+                        write!(f, " {}", next_move)?;
+                    }
+                }
+                Ok(())
+            }
+            Command::Go(limits) => {
+                write!(f, "go")?;
+                if let Some(depth) = limits.depth {
+                    write!(f, " depth {}", depth)?;
+                }
+                let times = [
+                    ("movetime", limits.move_time),
+                    ("wtime", limits.white_time),
+                    ("btime", limits.black_time),
+                    ("winc", limits.white_increment),
+                    ("binc", limits.black_increment),
+                    ("byoyomi", limits.byoyomi),
+                ];
+                for &(name, time) in &times {
+                    if let Some(time) = time {
+                        write!(f, " {} {}", name, time.as_millis())?;
+                    }
+                }
+                Ok(())
+            }
+            Command::Quit => write!(f, "quit"),
+        }
+    }
+}
+
+impl FromStr for Command {
+    type Err = ParseError;
+    fn from_str(text: &str) -> Result<Self, ParseError> {
+        let text = text.trim();
+        let (name, arguments) = match text.split_once(char::is_whitespace) {
+            Some((name, arguments)) => (name, arguments.trim()),
+            None => (text, ""),
+        };
+        let mut words = arguments.split_whitespace();
+        match name {
+            "varidor" => Ok(Command::Varidor),
+            "isready" => Ok(Command::IsReady),
+            "quit" => Ok(Command::Quit),
+            "newgame" => Ok(Command::NewGame {
+                board_size: parse_number(words.next(), "board size")?,
+                rules: Rules {
+                    wall_count: parse_number(words.next(), "wall count")?,
+                },
+            }),
+            "position" => {
+                let (position, moves) = match arguments.find("moves") {
+                    Some(index) => (arguments[..index].trim(), &arguments[index + 5..]),
+                    None => (arguments, ""),
+                };
+                Ok(Command::Position {
+                    position: match position {
+                        "startpos" => None,
+                        position => Some(position.parse()?),
+                    },
+                    moves: Move::parse_all(moves)?,
+                })
+            }
+            "go" => {
+                let mut limits = GoLimits::default();
+                while let Some(word) = words.next() {
+                    let value = words.next();
+                    let time = || {
+                        parse_number(value, "time in milliseconds").map(Duration::from_millis)
+                    };
+                    match word {
+                        "depth" => limits.depth = Some(parse_number(value, "depth")?),
+                        "movetime" => limits.move_time = Some(time()?),
+                        "wtime" => limits.white_time = Some(time()?),
+                        "btime" => limits.black_time = Some(time()?),
+                        "winc" => limits.white_increment = Some(time()?),
+                        "binc" => limits.black_increment = Some(time()?),
+                        "byoyomi" => limits.byoyomi = Some(time()?),
+                        _ => return Err(parse_error(word, "search limit")),
+                    }
+                }
+                Ok(Command::Go(limits))
+            }
+            _ => Err(parse_error(text, "command")),
+        }
+    }
+}
+
+impl fmt::Display for Response {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Response::Id { ref key, ref value } => write!(f, "id {} {}", key, value),
+            Response::VaridorOk => write!(f, "varidorok"),
+            Response::ReadyOk => write!(f, "readyok"),
+            Response::Info(ref info) => {
+                write!(
+                    f,
+                    "info depth {} score {} nodes {} pv",
+                    info.depth, info.score, info.nodes
+                )?;
+                for next_move in &info.principal_variation {
+                    write!(f, " {}", next_move)?;
+                }
+                Ok(())
+            }
+            Response::BestMove(Some(next_move)) => write!(f, "bestmove {}", next_move),
+            Response::BestMove(None) => write!(f, "bestmove none"),
+            Response::Error(ref message) => write!(f, "error {}", message),
+        }
+    }
+}
+
+impl FromStr for Response {
+    type Err = ParseError;
+    fn from_str(text: &str) -> Result<Self, ParseError> {
+        let text = text.trim();
+        let (name, arguments) = match text.split_once(char::is_whitespace) {
+            Some((name, arguments)) => (name, arguments.trim()),
+            None => (text, ""),
+        };
+        match name {
+            "varidorok" => Ok(Response::VaridorOk),
+            "readyok" => Ok(Response::ReadyOk),
+            "error" => Ok(Response::Error(arguments.to_string())),
+            "id" => match arguments.split_once(char::is_whitespace) {
+                Some((key, value)) => Ok(Response::Id {
+                    key: key.to_string(),
+                    value: value.trim().to_string(),
+                }),
+                None => Err(parse_error(text, "id")),
+            },
+            "bestmove" => match arguments {
+                "none" => Ok(Response::BestMove(None)),
+                next_move => Ok(Response::BestMove(Some(next_move.parse()?))),
+            },
+            "info" => {
+                let (numbers, moves) = match arguments.find("pv") {
+                    Some(index) => (&arguments[..index], &arguments[index + 2..]),
+                    None => (arguments, ""),
+                };
+                let mut info = SearchInfo {
+                    depth: 0,
+                    score: 0,
+                    nodes: 0,
+                    principal_variation: Move::parse_all(moves)?,
+                };
+                let mut words = numbers.split_whitespace();
+                while let Some(word) = words.next() {
+                    match word {
+                        "depth" => info.depth = parse_number(words.next(), "depth")?,
+                        "score" => info.score = parse_number(words.next(), "score")?,
+                        "nodes" => info.nodes = parse_number(words.next(), "node count")?,
+                        _ => return Err(parse_error(word, "search information")),
+                    }
+                }
+                Ok(Response::Info(info))
+            }
+            _ => Err(parse_error(text, "response")),
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use super::*;
+use std::error::Error;
+use std::ffi::OsStr;
+use std::io::{self, BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, ChildStdout, Command as ProcessCommand, Stdio};
+
+/// Why an engine did not deliver a move.
+#[derive(Debug)]
+pub enum EngineError {
+    /// the engine could not be started or stopped talking
+    Io(io::Error),
+    /// the engine answered with `error` or without a move
+    Engine(String),
+    /// the game rejected the move or is already over
+    Game(GameError),
+}
+impl fmt::Display for EngineError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            EngineError::Io(ref error) => write!(f, "engine communication failed: {}", error),
+            EngineError::Engine(ref message) => write!(f, "engine error: {}", message),
+            EngineError::Game(ref error) => write!(f, "{}", error),
+        }
+    }
+}
+impl Error for EngineError {}
+impl From<io::Error> for EngineError {
+    fn from(error: io::Error) -> Self {
+        EngineError::Io(error)
+    }
+}
+
+/// An engine program running as a child process and talking the protocol on its standard
+/// input and output.
+///
+/// The engine is sent `quit` and waited for when this is dropped.
+pub struct EngineProcess {
+    child: Child,
+    input: ChildStdin,
+    output: BufReader<ChildStdout>,
+    name: Option<String>,
+}
+
+impl EngineProcess {
+    /// Starts the program and waits for the answer to `varidor`.
+    pub fn spawn<S: AsRef<OsStr>>(program: S, arguments: &[&str]) -> io::Result<Self> {
+        let mut child = ProcessCommand::new(program)
+            .args(arguments)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+        let input = child.stdin.take().expect("standard input is piped");
+        let output = BufReader::new(child.stdout.take().expect("standard output is piped"));
+        let mut engine = EngineProcess {
+            child,
+            input,
+            output,
+            name: None,
+        };
+        engine.send(&Command::Varidor)?;
+        loop {
+            match engine.receive()? {
+                Response::VaridorOk => break,
+                Response::Id { ref key, ref value } if key == "name" => {
+                    engine.name = Some(value.clone())
+                }
+                _ => {}
+            }
+        }
+        Ok(engine)
+    }
+    /// The name from the `id name` line, if the engine sent one.
+    pub fn get_name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+    pub fn send(&mut self, command: &Command) -> io::Result<()> {
+        writeln!(self.input, "{}", command)?;
+        self.input.flush()
+    }
+    /// Waits for the next line of the engine.
+    pub fn receive(&mut self) -> io::Result<Response> {
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if self.output.read_line(&mut line)? == 0 {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "engine closed its output",
+                ));
+            }
+            if !line.trim().is_empty() {
+                return line
+                    .parse()
+                    .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error));
+            }
+        }
+    }
+    /// Waits until the engine answers `isready`.
+    pub fn wait_until_ready(&mut self) -> io::Result<()> {
+        self.send(&Command::IsReady)?;
+        while self.receive()? != Response::ReadyOk {}
+        Ok(())
+    }
+    /// Searches the position set before, returns the best move and the `info` lines.
+    pub fn go(&mut self, limits: GoLimits) -> Result<(Option<Move>, Vec<SearchInfo>), EngineError> {
+        self.send(&Command::Go(limits))?;
+        let mut infos = vec![];
+        loop {
+            match self.receive()? {
+                Response::Info(info) => infos.push(info),
+                Response::BestMove(best_move) => return Ok((best_move, infos)),
+                Response::Error(message) => return Err(EngineError::Engine(message)),
+                _ => {}
+            }
+        }
+    }
+}
+
+impl Drop for EngineProcess {
+    fn drop(&mut self) {
+        if self.send(&Command::Quit).is_err() {
+            let _ = self.child.kill();
+        }
+        let _ = self.child.wait();
+    }
+}
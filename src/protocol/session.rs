@@ -0,0 +1,197 @@
+use super::*;
+use ai::*;
+use evaluation::*;
+use std::io::{self, BufRead, Write};
+use std::time::Instant;
+use tablebase::*;
+
+/// Depth searched by `go` without limits.
+pub const DEFAULT_GO_DEPTH: usize = 3;
+
+// share of the remaining clock used for a move
+const CLOCK_SHARE: u32 = 20;
+
+/// The engine side of the protocol: keeps the game sent by the commands and answers `go` with
+/// an iterative deepening `search_with`.
+#[derive(Clone, Debug)]
+pub struct EngineSession {
+    /// sent in the `id name` line
+    pub name: String,
+    pub evaluator: LinearEvaluator,
+    board_size: usize,
+    rules: Rules,
+    position: Option<Position>,
+    moves: Vec<Move>,
+}
+
+impl EngineSession {
+    /// A session for the 5x5 board with the default rules.
+    pub fn new(name: &str) -> Self {
+        EngineSession {
+            name: name.to_string(),
+            evaluator: LinearEvaluator::default(),
+            board_size: 5,
+            rules: Rules::default(),
+            position: None,
+            moves: vec![],
+        }
+    }
+    /// Reads commands until `quit` or the end of the input, lines which are not commands are
+    /// answered by `error`.
+    pub fn run<R: BufRead, W: Write>(&mut self, input: R, output: &mut W) -> io::Result<()> {
+        for line in input.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            match line.parse::<Command>() {
+                Ok(Command::Quit) => break,
+                Ok(command) => self.handle(&command, output)?,
+                Err(error) => writeln!(output, "{}", Response::Error(error.to_string()))?,
+            }
+            output.flush()?;
+        }
+        Ok(())
+    }
+    /// Writes the responses to the command.
+    pub fn handle<W: Write>(&mut self, command: &Command, output: &mut W) -> io::Result<()> {
+        let responses = match *command {
+            Command::Varidor => vec![
+                Response::Id {
+                    key: "name".to_string(),
+                    value: self.name.clone(),
+                },
+                Response::Id {
+                    key: "author".to_string(),
+                    value: env!("CARGO_PKG_AUTHORS").to_string(),
+                },
+                Response::VaridorOk,
+            ],
+            Command::IsReady => vec![Response::ReadyOk],
+            Command::NewGame { board_size, rules } => {
+                self.board_size = board_size;
+                self.rules = rules;
+                self.position = None;
+                self.moves.clear();
+                vec![]
+            }
+            Command::Position {
+                ref position,
+                ref moves,
+            } => {
+                self.position = position.clone();
+                self.moves = moves.clone();
+                vec![]
+            }
+            Command::Go(limits) => {
+                return match self.board_size {
+                    3 => self.go::<SquareBoard3, W>(limits, output),
+                    4 => self.go::<SquareBoard4, W>(limits, output),
+                    5 => self.go::<SquareBoard5, W>(limits, output),
+                    9 => self.go::<SquareBoard9, W>(limits, output),
+                    size => {
+                        let message = format!("unsupported board size {}", size);
+                        writeln!(output, "{}", Response::Error(message))
+                    }
+                };
+            }
+            Command::Quit => vec![],
+        };
+        for response in responses {
+            writeln!(output, "{}", response)?;
+        }
+        Ok(())
+    }
+    // the board and the player to move after the moves
+    fn get_board<Board>(&self) -> Result<(Board, TwoPlayerIndices), String>
+    where
+        Board: PositionBoard,
+    {
+        let (mut board, mut player) = match self.position {
+            Some(ref position) => match Board::from_position(position, self.rules) {
+                Some(board) => (board, position.player),
+                None => return Err(format!("invalid position {}", position)),
+            },
+            None => (Board::with_rules(self.rules), TwoPlayerIndices::White),
+        };
+        for &next_move in &self.moves {
+            if let Some(winner) = board.get_winner() {
+                return Err(format!("{} is played after {} won", next_move, winner));
+            }
+            board
+                .play_move(player, next_move)
+                .map_err(|error| format!("{}: {}", next_move, error))?;
+            player = player.next_player();
+        }
+        Ok((board, player))
+    }
+    // searches one ply deeper as long as half of the time for the move is left, writes an
+    // `info` line after every depth; a depth not finished in time is dropped
+    fn go<Board, W>(&self, limits: GoLimits, output: &mut W) -> io::Result<()>
+    where
+        Board: TablebaseBoard + Clone,
+        W: Write,
+    {
+        let (board, player) = match self.get_board::<Board>() {
+            Ok(board) => board,
+            Err(message) => return writeln!(output, "{}", Response::Error(message)),
+        };
+        if board.get_winner().is_some() {
+            return writeln!(output, "{}", Response::BestMove(None));
+        }
+        let (clock, increment) = match player {
+            TwoPlayerIndices::White => (limits.white_time, limits.white_increment),
+            TwoPlayerIndices::Black => (limits.black_time, limits.black_increment),
+        };
+        // a share of the clock and the time which is given back after the move, but never more
+        // than is left
+        let byoyomi = limits.byoyomi.unwrap_or_default();
+        let clock_time = clock.map(|clock| {
+            let time = clock / CLOCK_SHARE + increment.unwrap_or_default() + byoyomi;
+            time.min(clock + byoyomi)
+        });
+        let time = limits
+            .move_time
+            .or(clock_time)
+            .or(limits.byoyomi);
+        let max_depth = match (limits.depth, time) {
+            (Some(depth), _) => depth.max(1),
+            (None, Some(_)) => MAX_GAME_PLIES,
+            (None, None) => DEFAULT_GO_DEPTH,
+        };
+        let start = Instant::now();
+        let deadline = time.and_then(|time| start.checked_add(time));
+        let mut best_move = None;
+        for depth in 1..max_depth + 1 {
+            let settings = SearchSettings {
+                depth,
+                tablebase: None,
+                book: None,
+                evaluator: self.evaluator,
+                deadline,
+            };
+            let result = search_with(&board, player, &settings);
+            if result.stopped {
+                break;
+            }
+            best_move = result.best_move;
+            let info = SearchInfo {
+                depth,
+                score: result.score,
+                nodes: result.nodes,
+                principal_variation: result.principal_variation,
+            };
+            writeln!(output, "{}", Response::Info(info))?;
+            output.flush()?;
+            let decided = result.score.abs() > WIN_SCORE - MAX_GAME_PLIES as i32;
+            let out_of_time = match time {
+                Some(time) => start.elapsed() * 2 >= time,
+                None => false,
+            };
+            if decided || out_of_time {
+                break;
+            }
+        }
+        writeln!(output, "{}", Response::BestMove(best_move))
+    }
+}
@@ -0,0 +1,141 @@
+use super::*;
+use std::collections::HashSet;
+
+/// Difficulty of the puzzles kept by `PuzzleGenerator`, the bounds are inclusive.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct PuzzleFilter {
+    /// moves of the solver
+    pub min_moves: usize,
+    pub max_moves: usize,
+    /// walls placed in the solution by both players
+    pub min_walls: usize,
+    pub max_walls: usize,
+}
+
+impl Default for PuzzleFilter {
+    fn default() -> Self {
+        PuzzleFilter {
+            min_moves: 1,
+            max_moves: 2,
+            min_walls: 0,
+            max_walls: usize::MAX,
+        }
+    }
+}
+
+impl PuzzleFilter {
+    pub fn matches(&self, puzzle: &Puzzle) -> bool {
+        let moves = puzzle.get_move_count();
+        let walls = puzzle.solution.iter().filter(|x| matches!(x, Move::Wall(_))).count();
+        self.min_moves <= moves
+            && moves <= self.max_moves
+            && self.min_walls <= walls
+            && walls <= self.max_walls
+    }
+}
+
+// moves of the solver which win against every defence within `moves` moves
+fn get_winning_moves<Board>(board: &Board, solver: TwoPlayerIndices, moves: usize) -> Vec<Move>
+where
+    Board: TablebaseBoard + Clone,
+{
+    board
+        .get_legal_moves(solver)
+        .into_iter()
+        .filter(|&next_move| {
+            let mut after = board.clone();
+            after.play_move(solver, next_move).is_ok()
+                && is_forced_win(&after, solver, moves - 1)
+        })
+        .collect()
+}
+
+/// The puzzle of the position with `player` to move: a single move wins against every defence
+/// in the least number of moves, at most `max_moves`, the opponent replying with the longest
+/// defence found by the search. `None` if there is no such move or the solution is not
+/// unique at a later move.
+pub fn find_puzzle<Board>(
+    board: &Board,
+    player: TwoPlayerIndices,
+    max_moves: usize,
+) -> Option<Puzzle>
+where
+    Board: TablebaseBoard + Clone,
+{
+    if board.get_winner().is_some() {
+        return None;
+    }
+    let opponent = player.next_player();
+    let moves =
+        (1..=max_moves).find(|&moves| !get_winning_moves(board, player, moves).is_empty())?;
+    let mut after = board.clone();
+    let mut solution = vec![];
+    for remaining in (1..=moves).rev() {
+        let winning = get_winning_moves(&after, player, remaining);
+        if winning.len() != 1 {
+            return None;
+        }
+        after.play_move(player, winning[0]).ok()?;
+        solution.push(winning[0]);
+        if is_won(&after, player, opponent) {
+            break;
+        }
+        let reply = search(&after, opponent, 2 * (remaining - 1)).best_move?;
+        after.play_move(opponent, reply).ok()?;
+        solution.push(reply);
+    }
+    let puzzle = Puzzle {
+        tags: vec![(
+            "Title".to_string(),
+            format!("{} to move, win in {}", player, solution.len().div_ceil(2)),
+        )],
+        position: board.get_position(player),
+        rules: board.get_rules(),
+        solution,
+        alternatives: BTreeMap::new(),
+    };
+    puzzle.verify::<Board>().ok().map(|()| puzzle)
+}
+
+/// Collects the puzzles found in the positions of games, every position is searched once up
+/// to mirroring, see `Position::get_canonical_hash`.
+pub struct PuzzleGenerator {
+    pub filter: PuzzleFilter,
+    pub puzzles: Vec<Puzzle>,
+    // canonical hashes of the positions searched or known
+    hashes: HashSet<u64>,
+}
+
+impl PuzzleGenerator {
+    pub fn new(filter: PuzzleFilter) -> Self {
+        PuzzleGenerator {
+            filter,
+            puzzles: vec![],
+            hashes: HashSet::new(),
+        }
+    }
+    /// Marks the position of a puzzle found before, e.g. in the file the puzzles are added to,
+    /// so that it is not generated again.
+    pub fn add_known(&mut self, puzzle: &Puzzle) {
+        self.hashes.insert(puzzle.position.get_canonical_hash());
+    }
+    /// Searches the positions of the game for puzzles, returns the number of puzzles added.
+    pub fn add_record<Board>(&mut self, record: &GameRecord) -> Result<usize, RecordError>
+    where
+        Board: TablebaseBoard + Clone,
+    {
+        let count = self.puzzles.len();
+        for (ply, board) in record.replay::<Board>()?.iter().enumerate() {
+            let player = record.get_move_player(ply);
+            if !self.hashes.insert(board.get_position(player).get_canonical_hash()) {
+                continue;
+            }
+            if let Some(puzzle) = find_puzzle(board, player, self.filter.max_moves) {
+                if self.filter.matches(&puzzle) {
+                    self.puzzles.push(puzzle);
+                }
+            }
+        }
+        Ok(self.puzzles.len() - count)
+    }
+}
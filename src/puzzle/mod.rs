@@ -0,0 +1,375 @@
+//! Puzzles: a position in which the player to move wins with a unique line, e.g. "White to
+//! move, win in 2".
+//!
+//! A puzzle is written as tag lines, of which `[Position "..."]` in the form of `Position`'s
+//! `Display` is required and `[Walls "..."]`, the wall count of the rules, defaults to the
+//! default rules, followed by the solution with one ply per line, starting with the player to
+//! move. Further moves which solve the puzzle as well may follow a move of the
+//! solver, separated by `|`:
+//! ```text
+//! [Position "5 white (2,1) (2,3) 1 0"]
+//! [Walls "5"]
+//! [Title "White to move, win in 2"]
+//! wall (1,2) horizontal | wall (2,2) horizontal
+//! step up
+//! step down
+//! ```
+//! A puzzle is solved once the solver has played all moves of the solution, after which the
+//! solver has reached the goal or wins the race without walls.
+//!
+//! `PuzzleGenerator` finds puzzles in the positions of recorded or self-played games.
+mod generator;
+pub use self::generator::*;
+use ai::*;
+use board_square::*;
+use game::*;
+use record::*;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+use tablebase::*;
+
+/// A position with the solution for its player to move, see the module documentation.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Puzzle {
+    /// all tags but `Position` and `Walls`
+    pub tags: Vec<(String, String)>,
+    pub position: Position,
+    pub rules: Rules,
+    /// the moves of the solver and the replies, ending with a move of the solver
+    pub solution: Vec<Move>,
+    /// moves accepted instead of the solution move with the same index
+    pub alternatives: BTreeMap<usize, Vec<Move>>,
+}
+
+/// Why a puzzle cannot be played or is not sound.
+#[derive(Clone, PartialEq, Debug)]
+pub enum PuzzleError {
+    /// the board cannot show the position
+    Position,
+    /// a move of the solution, or of the solution with an alternative at `ply`, is illegal
+    IllegalMove { ply: usize, error: GameError },
+    /// the solution ends before the solver has won
+    NotWon,
+    /// the move of the solver at the ply does not force a win in time
+    NotForced { ply: usize, next_move: Move },
+    /// further moves win at the ply, which are neither solution nor alternative
+    NotUnique { ply: usize, moves: Vec<Move> },
+}
+
+impl fmt::Display for PuzzleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PuzzleError::Position => write!(f, "the position does not fit the board"),
+            PuzzleError::IllegalMove { ply, ref error } => {
+                write!(f, "illegal move at ply {}: {}", ply, error)
+            }
+            PuzzleError::NotWon => write!(f, "the solver has not won at the end of the solution"),
+            PuzzleError::NotForced { ply, next_move } => {
+                write!(f, "{} at ply {} does not force a win", next_move, ply)
+            }
+            PuzzleError::NotUnique { ply, ref moves } => {
+                let moves = moves.iter().map(|x| x.to_string()).collect::<Vec<_>>();
+                write!(f, "{} win at ply {} as well", moves.join(", "), ply)
+            }
+        }
+    }
+}
+
+impl Error for PuzzleError {}
+
+// whether the solver has won or wins the race, with `player` to move
+fn is_won<Board>(board: &Board, solver: TwoPlayerIndices, player: TwoPlayerIndices) -> bool
+where
+    Board: PositionBoard,
+{
+    match board.get_winner() {
+        Some(winner) => winner == solver,
+        None => board.solve_race(player).is_some_and(|x| x.winner == solver),
+    }
+}
+
+// whether the solver wins with at most `moves` further moves against every defence, the
+// opponent being to move
+fn is_forced_win<Board>(board: &Board, solver: TwoPlayerIndices, moves: usize) -> bool
+where
+    Board: TablebaseBoard + Clone,
+{
+    let opponent = solver.next_player();
+    if is_won(board, solver, opponent) {
+        return true;
+    }
+    moves > 0 && search(board, opponent, 2 * moves).score <= -(WIN_SCORE - MAX_GAME_PLIES as i32)
+}
+
+impl Puzzle {
+    /// The player to move in the position, who solves the puzzle.
+    pub fn get_solver(&self) -> TwoPlayerIndices {
+        self.position.player
+    }
+    /// Number of moves of the solver.
+    pub fn get_move_count(&self) -> usize {
+        self.solution.len().div_ceil(2)
+    }
+    pub fn get_tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(tag, _)| tag == name)
+            .map(|(_, value)| value.as_str())
+    }
+    /// Whether the move solves the puzzle at the ply, being the solution or an alternative.
+    pub fn is_accepted(&self, ply: usize, next_move: Move) -> bool {
+        self.solution.get(ply) == Some(&next_move)
+            || self.alternatives
+                .get(&ply)
+                .is_some_and(|moves| moves.contains(&next_move))
+    }
+    /// The board showing the position.
+    pub fn get_board<Board: PositionBoard>(&self) -> Result<Board, PuzzleError> {
+        Board::from_position(&self.position, self.rules).ok_or(PuzzleError::Position)
+    }
+    /// Proves with the search that the solution and its alternatives are legal and win against
+    /// every defence within the moves of the solver, and that no other move of the solver
+    /// does.
+    pub fn verify<Board>(&self) -> Result<(), PuzzleError>
+    where
+        Board: TablebaseBoard + Clone,
+    {
+        let start = self.get_board::<Board>()?;
+        let solver = self.get_solver();
+        // the solution and every line with one alternative are played to their end
+        let mut lines = vec![self.solution.clone()];
+        for (&ply, moves) in &self.alternatives {
+            for &next_move in moves {
+                let mut line = self.solution.clone();
+                line[ply] = next_move;
+                lines.push(line);
+            }
+        }
+        let mut boards = vec![];
+        for (line_index, line) in lines.iter().enumerate() {
+            let mut board = start.clone();
+            let mut player = solver;
+            for (index, &next_move) in line.iter().enumerate() {
+                // the positions of the solution
+                if line_index == 0 {
+                    boards.push(board.clone());
+                }
+                let result = match board.get_winner() {
+                    Some(winner) => Err(GameError::GameOver { winner }),
+                    None => board.play_move(player, next_move),
+                };
+                result.map_err(|error| PuzzleError::IllegalMove {
+                    ply: index + 1,
+                    error,
+                })?;
+                player = player.next_player();
+            }
+            if !is_won(&board, solver, player) {
+                return Err(PuzzleError::NotWon);
+            }
+        }
+        // the moves of the solver before the solution move which win in time
+        for (ply, board) in boards.iter().enumerate().step_by(2) {
+            let remaining = (self.solution.len() - ply) / 2;
+            let mut winning = vec![];
+            for next_move in board.get_legal_moves(solver) {
+                let mut after = board.clone();
+                if after.play_move(solver, next_move).is_ok()
+                    && is_forced_win(&after, solver, remaining)
+                {
+                    winning.push(next_move);
+                }
+            }
+            let mut accepted = vec![self.solution[ply]];
+            accepted.extend(self.alternatives.get(&ply).cloned().unwrap_or_default());
+            if let Some(&next_move) = accepted.iter().find(|x| !winning.contains(x)) {
+                return Err(PuzzleError::NotForced {
+                    ply: ply + 1,
+                    next_move,
+                });
+            }
+            winning.retain(|x| !accepted.contains(x));
+            if !winning.is_empty() {
+                return Err(PuzzleError::NotUnique {
+                    ply: ply + 1,
+                    moves: winning,
+                });
+            }
+        }
+        Ok(())
+    }
+    /// Reads all puzzles of a text, each one starting with its tags.
+    pub fn parse_all(text: &str) -> Result<Vec<Puzzle>, ParseError> {
+        let mut puzzles = vec![];
+        let mut lines = vec![];
+        let mut in_moves = false;
+        for line in text.lines().map(|x| x.trim()).filter(|x| !x.is_empty()) {
+            let is_tag = line.starts_with('[');
+            if is_tag && in_moves {
+                puzzles.push(Self::parse_lines(&lines)?);
+                lines.clear();
+            }
+            in_moves = !is_tag;
+            lines.push(line);
+        }
+        if !lines.is_empty() {
+            puzzles.push(Self::parse_lines(&lines)?);
+        }
+        Ok(puzzles)
+    }
+    fn parse_lines(lines: &[&str]) -> Result<Puzzle, ParseError> {
+        let mut position = None;
+        let mut rules = Rules::default();
+        let mut tags = vec![];
+        let mut solution = vec![];
+        let mut alternatives = BTreeMap::new();
+        for &line in lines {
+            if line.starts_with('[') {
+                let (name, value) = parse_tag(line)?;
+                match name.as_str() {
+                    "Position" => position = Some(value.parse()?),
+                    "Walls" => {
+                        rules.wall_count = value.parse().map_err(|_| ParseError {
+                            text: value.clone(),
+                            expected: "wall count",
+                        })?
+                    }
+                    _ => tags.push((name, value)),
+                }
+                continue;
+            }
+            let mut moves = line.split('|')
+                .map(|x| x.trim().parse())
+                .collect::<Result<Vec<Move>, _>>()?;
+            let is_reply = solution.len() & 1 == 1;
+            if moves.len() > 1 && is_reply {
+                return Err(ParseError {
+                    text: line.to_string(),
+                    expected: "single reply",
+                });
+            }
+            solution.push(moves.remove(0));
+            if !moves.is_empty() {
+                alternatives.insert(solution.len() - 1, moves);
+            }
+        }
+        let error = |expected| ParseError {
+            text: lines.join("\n"),
+            expected,
+        };
+        if solution.len() & 1 == 0 {
+            return Err(error("solution ending with a move of the solver"));
+        }
+        Ok(Puzzle {
+            tags,
+            position: position.ok_or_else(|| error("puzzle with a Position tag"))?,
+            rules,
+            solution,
+            alternatives,
+        })
+    }
+}
+
+impl fmt::Display for Puzzle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "[Position \"{}\"]", self.position)?;
+        writeln!(f, "[Walls \"{}\"]", self.rules.wall_count)?;
+        for (name, value) in &self.tags {
+            writeln!(f, "[{} \"{}\"]", name, value)?;
+        }
+        for (ply, next_move) in self.solution.iter().enumerate() {
+            write!(f, "{}", next_move)?;
+            for alternative in self.alternatives.get(&ply).into_iter().flatten() {
+                write!(f, " | {}", alternative)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Puzzle {
+    type Err = ParseError;
+    fn from_str(text: &str) -> Result<Self, ParseError> {
+        let mut puzzles = Self::parse_all(text)?;
+        if puzzles.len() != 1 {
+            return Err(ParseError {
+                text: text.to_string(),
+                expected: "single puzzle",
+            });
+        }
+        Ok(puzzles.remove(0))
+    }
+}
+
+/// Progress of a `PuzzleGame`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PuzzleState {
+    Solving,
+    Solved,
+    /// the solver played a move which does not solve the puzzle
+    Failed,
+}
+
+/// A puzzle played in a `GameData`, the opponent replying with the moves of the solution.
+pub struct PuzzleGame<Board = SquareBoard5> {
+    pub puzzle: Puzzle,
+    pub game: GameData<Board>,
+    // index of the next move of the solver in the solution
+    ply: usize,
+    state: PuzzleState,
+}
+
+impl<Board> PuzzleGame<Board>
+where
+    Board: PositionBoard<WallDirectionType = WallDirections>,
+{
+    /// Loads the position of the puzzle into a new game.
+    pub fn new(puzzle: Puzzle) -> Result<Self, PuzzleError> {
+        let game = GameData::with_position(&puzzle.position, puzzle.rules)
+            .ok_or(PuzzleError::Position)?;
+        Ok(PuzzleGame {
+            puzzle,
+            game,
+            ply: 0,
+            state: PuzzleState::Solving,
+        })
+    }
+    pub fn get_state(&self) -> PuzzleState {
+        self.state
+    }
+    /// Number of moves of the solution played so far.
+    pub fn get_ply(&self) -> usize {
+        self.ply
+    }
+    /// Plays a move of the solver: the solution move or an alternative is answered by the
+    /// next move of the solution, any other legal move fails the puzzle. Illegal moves are
+    /// rejected and moves after the end of the puzzle are ignored.
+    ///
+    /// A reply which is illegal, in a puzzle which was not verified, fails the puzzle as well
+    /// and is returned as the error.
+    pub fn play_move(&mut self, next_move: Move) -> Result<PuzzleState, GameError> {
+        if self.state != PuzzleState::Solving {
+            return Ok(self.state);
+        }
+        self.game.play_move(next_move)?;
+        if !self.puzzle.is_accepted(self.ply, next_move) {
+            self.state = PuzzleState::Failed;
+            return Ok(self.state);
+        }
+        self.ply += 1;
+        match self.puzzle.solution.get(self.ply) {
+            Some(&reply) => {
+                if let Err(error) = self.game.play_move(reply) {
+                    self.state = PuzzleState::Failed;
+                    return Err(error);
+                }
+                self.ply += 1;
+            }
+            None => self.state = PuzzleState::Solved,
+        }
+        Ok(self.state)
+    }
+}
@@ -0,0 +1,391 @@
+//! Game records: the rules, the moves and the result of a game as text.
+//!
+//! A record consists of tag lines `[Name "value"]` followed by one move per line in the form of
+//! `Move`'s `Display`, e.g.
+//! ```text
+//! [Board "5"]
+//! [Walls "5"]
+//! [Result "white"]
+//! step down
+//! wall (1,2) vertical
+//! ```
+//! The tags `Board`, `Walls` and `Result` are required, all others are kept as they are. Games
+//! started from another position than the start position carry it in the tag `Position`, in the
+//! form of `Position`'s `Display`. Files may hold several records, each one starting with its
+//! tags.
+//!
+//! A move may be followed by a comment line `{text}` and by variations, moves played instead of
+//! it, each one between the lines `(` and `)` and annotated the same way:
+//! ```text
+//! step down
+//! {the usual start}
+//! (
+//! wall (1,2) vertical
+//! step up
+//! )
+//! step up
+//! ```
+use board_square::*;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::iter::Peekable;
+use std::path::Path;
+use std::str::FromStr;
+
+/// A played game: board size, rules, moves from the start position and the winner, if any.
+#[derive(Clone, PartialEq, Debug)]
+pub struct GameRecord {
+    pub board_size: usize,
+    pub rules: Rules,
+    /// the position the moves are played from, `None` for the start position of the rules
+    pub start_position: Option<Position>,
+    /// further tags such as the names of the players, in the order of the text
+    pub tags: Vec<(String, String)>,
+    pub moves: Vec<Move>,
+    pub winner: Option<TwoPlayerIndices>,
+    /// comments and variations by the index of the move they belong to
+    pub annotations: BTreeMap<usize, Annotation>,
+}
+
+/// What is noted at a move of a record or a variation.
+#[derive(Clone, PartialEq, Default, Debug)]
+pub struct Annotation {
+    /// on the move played
+    pub comment: Option<String>,
+    /// lines played instead of the move
+    pub variations: Vec<Variation>,
+}
+
+/// A line of moves branching off a record, with annotations of its own.
+#[derive(Clone, PartialEq, Default, Debug)]
+pub struct Variation {
+    pub moves: Vec<Move>,
+    /// by the index of the move in the variation
+    pub annotations: BTreeMap<usize, Annotation>,
+}
+
+/// Why a record cannot be replayed on a board.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum RecordError {
+    BoardSize { expected: usize, found: usize },
+    /// the start position cannot be shown on the board
+    Position,
+    /// `ply` counts from 1
+    IllegalMove { ply: usize, error: GameError },
+}
+impl fmt::Display for RecordError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            RecordError::BoardSize { expected, found } => write!(
+                f,
+                "record for board size {} cannot be replayed on board size {}",
+                found, expected
+            ),
+            RecordError::Position => write!(f, "the start position does not fit the board"),
+            RecordError::IllegalMove { ply, ref error } => {
+                write!(f, "illegal move at ply {}: {}", ply, error)
+            }
+        }
+    }
+}
+impl Error for RecordError {}
+
+impl GameRecord {
+    pub fn new(board_size: usize, rules: Rules) -> Self {
+        GameRecord {
+            board_size,
+            rules,
+            start_position: None,
+            tags: vec![],
+            moves: vec![],
+            winner: None,
+            annotations: BTreeMap::new(),
+        }
+    }
+    /// The annotation of the move with the given index, created if missing.
+    pub fn get_annotation_mut(&mut self, index: usize) -> &mut Annotation {
+        self.annotations.entry(index).or_default()
+    }
+    pub fn get_tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(tag, _)| tag == name)
+            .map(|(_, value)| value.as_str())
+    }
+    /// Replaces the value of the tag or adds it.
+    pub fn set_tag(&mut self, name: &str, value: &str) {
+        match self.tags.iter_mut().find(|(tag, _)| tag == name) {
+            Some(tag) => tag.1 = value.to_string(),
+            None => self.tags.push((name.to_string(), value.to_string())),
+        }
+    }
+    /// The player who plays the move with the given index in games from the start position,
+    /// White starts.
+    pub fn get_player(index: usize) -> TwoPlayerIndices {
+        if index & 1 == 0 {
+            TwoPlayerIndices::White
+        } else {
+            TwoPlayerIndices::Black
+        }
+    }
+    /// The player who plays the move with the given index in this game, the player to move in
+    /// the start position starts.
+    pub fn get_move_player(&self, index: usize) -> TwoPlayerIndices {
+        match self.start_position {
+            Some(ref position) if index & 1 == 0 => position.player,
+            Some(ref position) => position.player.next_player(),
+            None => Self::get_player(index),
+        }
+    }
+    /// The boards before every move and after the last one.
+    pub fn replay<Board>(&self) -> Result<Vec<Board>, RecordError>
+    where
+        Board: PositionBoard + Clone,
+    {
+        if Board::AVERAGE_BOARD_SIZE != self.board_size {
+            return Err(RecordError::BoardSize {
+                expected: Board::AVERAGE_BOARD_SIZE,
+                found: self.board_size,
+            });
+        }
+        let mut board = match self.start_position {
+            Some(ref position) => {
+                Board::from_position(position, self.rules).ok_or(RecordError::Position)?
+            }
+            None => Board::with_rules(self.rules),
+        };
+        let mut boards = vec![board.clone()];
+        for (index, &next_move) in self.moves.iter().enumerate() {
+            let result = match board.get_winner() {
+                Some(winner) => Err(GameError::GameOver { winner }),
+                None => board.play_move(self.get_move_player(index), next_move),
+            };
+            result.map_err(|error| RecordError::IllegalMove {
+                ply: index + 1,
+                error,
+            })?;
+            boards.push(board.clone());
+        }
+        Ok(boards)
+    }
+    /// Reads all records of a text, see the module documentation.
+    pub fn parse_all(text: &str) -> Result<Vec<GameRecord>, ParseError> {
+        let mut records = vec![];
+        let mut lines = vec![];
+        let mut in_moves = false;
+        for line in text.lines().map(|x| x.trim()).filter(|x| !x.is_empty()) {
+            let is_tag = line.starts_with('[');
+            if is_tag && in_moves {
+                records.push(Self::parse_lines(&lines)?);
+                lines.clear();
+            }
+            in_moves = !is_tag;
+            lines.push(line);
+        }
+        if !lines.is_empty() {
+            records.push(Self::parse_lines(&lines)?);
+        }
+        Ok(records)
+    }
+    fn parse_lines(lines: &[&str]) -> Result<GameRecord, ParseError> {
+        let mut board_size = None;
+        let mut wall_count = None;
+        let mut winner = None;
+        let mut start_position = None;
+        let mut tags = vec![];
+        let mut move_lines = vec![];
+        for &line in lines {
+            if !line.starts_with('[') {
+                move_lines.push(line);
+                continue;
+            }
+            let (name, value) = parse_tag(line)?;
+            let parse_number = |expected| {
+                value.parse::<usize>().map_err(|_| ParseError {
+                    text: value.clone(),
+                    expected,
+                })
+            };
+            match name.as_str() {
+                "Board" => board_size = Some(parse_number("board size")?),
+                "Walls" => wall_count = Some(parse_number("wall count")?),
+                "Result" => winner = Some(parse_result(&value)?),
+                "Position" => start_position = Some(value.parse()?),
+                _ => tags.push((name, value)),
+            }
+        }
+        let mut move_lines = move_lines.into_iter().peekable();
+        let main_line = parse_variation(&mut move_lines)?;
+        if let Some(line) = move_lines.next() {
+            return Err(ParseError {
+                text: line.to_string(),
+                expected: "move",
+            });
+        }
+        let missing = |expected| ParseError {
+            text: lines.join("\n"),
+            expected,
+        };
+        Ok(GameRecord {
+            board_size: board_size.ok_or_else(|| missing("record with a Board tag"))?,
+            rules: Rules {
+                wall_count: wall_count.ok_or_else(|| missing("record with a Walls tag"))?,
+            },
+            start_position,
+            tags,
+            moves: main_line.moves,
+            winner: winner.ok_or_else(|| missing("record with a Result tag"))?,
+            annotations: main_line.annotations,
+        })
+    }
+}
+
+/// All records of the files in the directory, the files in the order of their names.
+pub fn read_records_from_directory<P: AsRef<Path>>(directory: P) -> io::Result<Vec<GameRecord>> {
+    let mut paths = fs::read_dir(directory)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<Vec<_>>>()?;
+    paths.sort();
+    let mut records = vec![];
+    for path in paths.into_iter().filter(|path| path.is_file()) {
+        let mut text = String::new();
+        File::open(&path)?.read_to_string(&mut text)?;
+        let file_records = GameRecord::parse_all(&text).map_err(|error| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: {}", path.display(), error),
+            )
+        })?;
+        records.extend(file_records);
+    }
+    Ok(records)
+}
+
+// moves with their comments and variations up to the end of the lines or a `)` line, which is
+// left for the caller
+fn parse_variation<'a, I>(lines: &mut Peekable<I>) -> Result<Variation, ParseError>
+where
+    I: Iterator<Item = &'a str>,
+{
+    let mut variation = Variation::default();
+    while let Some(&line) = lines.peek() {
+        if line == ")" {
+            break;
+        }
+        lines.next();
+        let annotated = |expected| ParseError {
+            text: line.to_string(),
+            expected,
+        };
+        let index = variation.moves.len().checked_sub(1);
+        if line == "(" {
+            let index = index.ok_or_else(|| annotated("variation after a move"))?;
+            let inner = parse_variation(lines)?;
+            if lines.next() != Some(")") {
+                return Err(annotated("variation ending with )"));
+            }
+            let annotation = variation.annotations.entry(index).or_default();
+            annotation.variations.push(inner);
+        } else if let Some(comment) = line.strip_prefix('{').and_then(|x| x.strip_suffix('}')) {
+            let index = index.ok_or_else(|| annotated("comment after a move"))?;
+            let annotation = variation.annotations.entry(index).or_default();
+            annotation.comment = Some(comment.to_string());
+        } else {
+            variation.moves.push(line.parse()?);
+        }
+    }
+    Ok(variation)
+}
+
+fn write_variation(
+    f: &mut fmt::Formatter,
+    moves: &[Move],
+    annotations: &BTreeMap<usize, Annotation>,
+) -> fmt::Result {
+    for (index, next_move) in moves.iter().enumerate() {
+        writeln!(f, "{}", next_move)?;
+        if let Some(annotation) = annotations.get(&index) {
+            if let Some(ref comment) = annotation.comment {
+                // comments are single lines
+                writeln!(f, "{{{}}}", comment.replace('\n', " "))?;
+            }
+            for variation in &annotation.variations {
+                writeln!(f, "(")?;
+                write_variation(f, &variation.moves, &variation.annotations)?;
+                writeln!(f, ")")?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Name and value of a tag line `[Name "value"]`.
+pub fn parse_tag(line: &str) -> Result<(String, String), ParseError> {
+    let error = || ParseError {
+        text: line.to_string(),
+        expected: "tag",
+    };
+    let inner = line.strip_prefix('[')
+        .and_then(|x| x.strip_suffix(']'))
+        .ok_or_else(error)?;
+    let space = inner.find(' ').ok_or_else(error)?;
+    let value = inner[space..].trim();
+    if value.len() < 2 || !value.starts_with('"') || !value.ends_with('"') {
+        return Err(error());
+    }
+    Ok((
+        inner[..space].to_string(),
+        value[1..value.len() - 1].to_string(),
+    ))
+}
+
+fn parse_result(value: &str) -> Result<Option<TwoPlayerIndices>, ParseError> {
+    match value {
+        "white" => Ok(Some(TwoPlayerIndices::White)),
+        "black" => Ok(Some(TwoPlayerIndices::Black)),
+        "none" => Ok(None),
+        _ => Err(ParseError {
+            text: value.to_string(),
+            expected: "result",
+        }),
+    }
+}
+
+impl FromStr for GameRecord {
+    type Err = ParseError;
+    /// Reads a text holding exactly one record.
+    fn from_str(text: &str) -> Result<Self, ParseError> {
+        let mut records = Self::parse_all(text)?;
+        if records.len() == 1 {
+            Ok(records.remove(0))
+        } else {
+            Err(ParseError {
+                text: text.to_string(),
+                expected: "single record",
+            })
+        }
+    }
+}
+
+impl fmt::Display for GameRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let result = match self.winner {
+            Some(TwoPlayerIndices::White) => "white",
+            Some(TwoPlayerIndices::Black) => "black",
+            None => "none",
+        };
+        writeln!(f, "[Board \"{}\"]", self.board_size)?;
+        writeln!(f, "[Walls \"{}\"]", self.rules.wall_count)?;
+        writeln!(f, "[Result \"{}\"]", result)?;
+        if let Some(ref position) = self.start_position {
+            writeln!(f, "[Position \"{}\"]", position)?;
+        }
+        for (name, value) in &self.tags {
+            writeln!(f, "[{} \"{}\"]", name, value)?;
+        }
+        write_variation(f, &self.moves, &self.annotations)
+    }
+}
@@ -0,0 +1,69 @@
+//! Replays of recorded games: the position after any ply of a record, for reviewing games
+//! independently of a running `GameData`.
+use board_square::*;
+use record::*;
+
+/// A record replayed on `Board` with the ply shown, ply 0 being the start position.
+#[derive(Clone)]
+pub struct Replay<Board> {
+    record: GameRecord,
+    boards: Vec<Board>,
+    ply: usize,
+}
+
+impl<Board> Replay<Board>
+where
+    Board: PositionBoard + Clone,
+{
+    /// Replays the record, showing the start position.
+    pub fn new(record: GameRecord) -> Result<Self, RecordError> {
+        let boards = record.replay::<Board>()?;
+        Ok(Replay {
+            record,
+            boards,
+            ply: 0,
+        })
+    }
+    pub fn get_record(&self) -> &GameRecord {
+        &self.record
+    }
+    /// Number of plies of the game, the last ply which can be shown.
+    pub fn get_ply_count(&self) -> usize {
+        self.record.moves.len()
+    }
+    pub fn get_ply(&self) -> usize {
+        self.ply
+    }
+    /// Shows the position after `ply` plies, plies beyond the end show the final position.
+    pub fn goto_ply(&mut self, ply: usize) {
+        self.ply = ply.min(self.get_ply_count());
+    }
+    /// Shows the next ply, returns false at the end of the game.
+    pub fn step_forward(&mut self) -> bool {
+        let is_at_end = self.ply == self.get_ply_count();
+        self.goto_ply(self.ply + 1);
+        !is_at_end
+    }
+    /// Shows the previous ply, returns false at the start of the game.
+    pub fn step_backward(&mut self) -> bool {
+        match self.ply.checked_sub(1) {
+            Some(ply) => {
+                self.goto_ply(ply);
+                true
+            }
+            None => false,
+        }
+    }
+    /// The board after the ply shown.
+    pub fn get_board(&self) -> &Board {
+        &self.boards[self.ply]
+    }
+    /// The player to move after the ply shown.
+    pub fn get_current_player(&self) -> TwoPlayerIndices {
+        self.record.get_move_player(self.ply)
+    }
+    /// The move which led to the position shown, `None` at the start.
+    pub fn get_last_move(&self) -> Option<Move> {
+        self.ply.checked_sub(1).map(|index| self.record.moves[index])
+    }
+}
@@ -0,0 +1,362 @@
+//! Game server: hosts games for players on other machines over WebSocket, every move is checked
+//! on the board of the server.
+//!
+//! Clients send `ClientMessage`s and receive `ServerMessage`s, one per WebSocket text message:
+//! ```text
+//! > join lobby white
+//! < joined lobby white
+//! < state 5 white (2,4) (2,0) 5 5
+//! > move step down
+//! < played white step down
+//! < state 5 black (2,3) (2,0) 5 5
+//! > move step up
+//! < error it is not the turn of white
+//! ```
+//! Games are created by the first client joining them. Every client of a game, players and
+//! spectators, receives `played`, `state` and `gameover`; `rejected` with the error of the
+//! board only goes to the player whose move it was.
+pub mod websocket;
+
+use self::websocket::*;
+use board_square::*;
+use game::*;
+use std::collections::HashMap;
+use std::fmt;
+use std::io;
+use std::marker::PhantomData;
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::str::FromStr;
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// Part a client takes in a game.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Role {
+    Player(TwoPlayerIndices),
+    Spectator,
+}
+
+/// A message of a client to the server.
+#[derive(Clone, PartialEq, Debug)]
+pub enum ClientMessage {
+    /// `join <game> <white|black|spectator>`, game names have no spaces
+    Join { game: String, role: Role },
+    /// `move <move>`, e.g. `move wall (1,2) vertical`
+    Move(Move),
+}
+
+/// A message of the server to a client.
+#[derive(Clone, PartialEq, Debug)]
+pub enum ServerMessage {
+    /// `joined <game> <role>`: answers `join`
+    Joined { game: String, role: Role },
+    /// `state <position>`: the position after joining and after every move
+    State(Position),
+    /// `played <player> <move>`
+    Played {
+        player: TwoPlayerIndices,
+        next_move: Move,
+    },
+    /// `rejected <reason>`: the board did not accept the move
+    Rejected(String),
+    /// `gameover <winner>`
+    GameOver(TwoPlayerIndices),
+    /// `error <message>`: the message could not be read or was not allowed
+    Error(String),
+}
+
+fn parse_error(text: &str, expected: &'static str) -> ParseError {
+    ParseError {
+        text: text.to_string(),
+        expected,
+    }
+}
+
+fn player_name(player: TwoPlayerIndices) -> &'static str {
+    match player {
+        TwoPlayerIndices::White => "white",
+        TwoPlayerIndices::Black => "black",
+    }
+}
+
+fn parse_player(text: &str) -> Result<TwoPlayerIndices, ParseError> {
+    match text {
+        "white" => Ok(TwoPlayerIndices::White),
+        "black" => Ok(TwoPlayerIndices::Black),
+        _ => Err(parse_error(text, "player")),
+    }
+}
+
+// the first word and the rest
+fn split_message(text: &str) -> (&str, &str) {
+    let text = text.trim();
+    match text.split_once(char::is_whitespace) {
+        Some((name, rest)) => (name, rest.trim()),
+        None => (text, ""),
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Role::Player(player) => write!(f, "{}", player_name(player)),
+            Role::Spectator => write!(f, "spectator"),
+        }
+    }
+}
+
+impl FromStr for Role {
+    type Err = ParseError;
+    fn from_str(text: &str) -> Result<Self, ParseError> {
+        match text.trim() {
+            "spectator" => Ok(Role::Spectator),
+            player => parse_player(player)
+                .map(Role::Player)
+                .map_err(|_| parse_error(text, "role")),
+        }
+    }
+}
+
+impl fmt::Display for ClientMessage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ClientMessage::Join { ref game, role } => write!(f, "join {} {}", game, role),
+            ClientMessage::Move(next_move) => write!(f, "move {}", next_move),
+        }
+    }
+}
+
+impl FromStr for ClientMessage {
+    type Err = ParseError;
+    fn from_str(text: &str) -> Result<Self, ParseError> {
+        match split_message(text) {
+            ("join", arguments) => match arguments.split_once(char::is_whitespace) {
+                Some((game, role)) => Ok(ClientMessage::Join {
+                    game: game.to_string(),
+                    role: role.parse()?,
+                }),
+                None => Err(parse_error(text, "join message")),
+            },
+            ("move", next_move) => Ok(ClientMessage::Move(next_move.parse()?)),
+            _ => Err(parse_error(text, "message")),
+        }
+    }
+}
+
+impl fmt::Display for ServerMessage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ServerMessage::Joined { ref game, role } => write!(f, "joined {} {}", game, role),
+            ServerMessage::State(ref position) => write!(f, "state {}", position),
+            ServerMessage::Played { player, next_move } => {
+                write!(f, "played {} {}", player_name(player), next_move)
+            }
+            ServerMessage::Rejected(ref reason) => write!(f, "rejected {}", reason),
+            ServerMessage::GameOver(winner) => write!(f, "gameover {}", player_name(winner)),
+            ServerMessage::Error(ref message) => write!(f, "error {}", message),
+        }
+    }
+}
+
+impl FromStr for ServerMessage {
+    type Err = ParseError;
+    fn from_str(text: &str) -> Result<Self, ParseError> {
+        match split_message(text) {
+            ("joined", arguments) => match arguments.split_once(char::is_whitespace) {
+                Some((game, role)) => Ok(ServerMessage::Joined {
+                    game: game.to_string(),
+                    role: role.parse()?,
+                }),
+                None => Err(parse_error(text, "joined message")),
+            },
+            ("state", position) => Ok(ServerMessage::State(position.parse()?)),
+            ("played", arguments) => match arguments.split_once(char::is_whitespace) {
+                Some((player, next_move)) => Ok(ServerMessage::Played {
+                    player: parse_player(player)?,
+                    next_move: next_move.parse()?,
+                }),
+                None => Err(parse_error(text, "played message")),
+            },
+            ("rejected", reason) => Ok(ServerMessage::Rejected(reason.to_string())),
+            ("gameover", winner) => Ok(ServerMessage::GameOver(parse_player(winner)?)),
+            ("error", message) => Ok(ServerMessage::Error(message.to_string())),
+            _ => Err(parse_error(text, "message")),
+        }
+    }
+}
+
+// a hosted game and the connections of its clients
+struct Room<Board> {
+    game: GameData<Board>,
+    // messages are queued for a thread writing to the client, a slow client does not keep the
+    // lock of all games while its connection is written
+    clients: Vec<(usize, Role, Sender<String>)>,
+    next_client: usize,
+}
+
+impl<Board> Room<Board>
+where
+    Board: PositionBoard<WallDirectionType = WallDirections>,
+{
+    fn get_state(&self) -> ServerMessage {
+        ServerMessage::State(self.game.board.get_position(self.game.get_current_player()))
+    }
+    fn send_to(&mut self, client: usize, message: &ServerMessage) {
+        for &(id, _, ref sender) in &self.clients {
+            if id == client {
+                let _ = sender.send(message.to_string());
+            }
+        }
+    }
+    // clients which have left are removed when their connection ends
+    fn broadcast(&mut self, message: &ServerMessage) {
+        for (_, _, sender) in &self.clients {
+            let _ = sender.send(message.to_string());
+        }
+    }
+}
+
+type Rooms<Board> = Arc<Mutex<HashMap<String, Room<Board>>>>;
+
+/// Accepts WebSocket clients and hosts their games on boards of type `Board`.
+pub struct GameServer<Board> {
+    listener: TcpListener,
+    rules: Rules,
+    _board: PhantomData<Board>,
+}
+
+impl<Board> GameServer<Board>
+where
+    Board: PositionBoard<WallDirectionType = WallDirections> + Send + 'static,
+{
+    /// Listens on the address, e.g. `127.0.0.1:0` for a free port; new games use the rules.
+    pub fn bind<A: ToSocketAddrs>(address: A, rules: Rules) -> io::Result<Self> {
+        Ok(GameServer {
+            listener: TcpListener::bind(address)?,
+            rules,
+            _board: PhantomData,
+        })
+    }
+    pub fn get_address(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+    /// Serves clients until accepting fails, every client in its own thread.
+    pub fn run(self) -> io::Result<()> {
+        let rooms: Rooms<Board> = Arc::new(Mutex::new(HashMap::new()));
+        for stream in self.listener.incoming() {
+            let stream = stream?;
+            let rooms = rooms.clone();
+            let rules = self.rules;
+            thread::spawn(move || {
+                let _ = serve_client(stream, &rooms, rules);
+            });
+        }
+        Ok(())
+    }
+}
+
+// writes the queued messages to the client until it has left its room
+fn spawn_writer(mut writer: WebSocketWriter) -> Sender<String> {
+    let (sender, receiver) = mpsc::channel::<String>();
+    thread::spawn(move || {
+        for text in receiver {
+            if writer.send(&text).is_err() {
+                break;
+            }
+        }
+    });
+    sender
+}
+
+// answers the messages of one client until it disconnects
+fn serve_client<Board>(stream: TcpStream, rooms: &Rooms<Board>, rules: Rules) -> io::Result<()>
+where
+    Board: PositionBoard<WallDirectionType = WallDirections>,
+{
+    let (mut socket, _) = WebSocket::accept(stream)?;
+    let mut seat: Option<(String, usize, Role)> = None;
+    let result = loop {
+        let text = match socket.receive() {
+            Ok(Some(text)) => text,
+            Ok(None) => break Ok(()),
+            Err(error) => break Err(error),
+        };
+        let reply = match (text.parse::<ClientMessage>(), &seat) {
+            (Err(error), _) => Some(ServerMessage::Error(error.to_string())),
+            (Ok(ClientMessage::Join { .. }), &Some(_)) => {
+                Some(ServerMessage::Error("already joined a game".to_string()))
+            }
+            (Ok(ClientMessage::Join { game, role }), &None) => {
+                let mut rooms = rooms.lock().expect("game server lock");
+                let room = rooms.entry(game.clone()).or_insert_with(|| Room {
+                    game: GameData::with_rules(rules),
+                    clients: vec![],
+                    next_client: 0,
+                });
+                if role != Role::Spectator && room.clients.iter().any(|x| x.1 == role) {
+                    Some(ServerMessage::Error(format!("{} is taken", role)))
+                } else {
+                    let id = room.next_client;
+                    room.next_client += 1;
+                    room.clients.push((id, role, spawn_writer(socket.get_writer()?)));
+                    room.send_to(id, &ServerMessage::Joined {
+                        game: game.clone(),
+                        role,
+                    });
+                    let state = room.get_state();
+                    room.send_to(id, &state);
+                    if let Some(winner) = room.game.winner {
+                        room.send_to(id, &ServerMessage::GameOver(winner));
+                    }
+                    seat = Some((game, id, role));
+                    None
+                }
+            }
+            (Ok(ClientMessage::Move(_)), &None) => {
+                Some(ServerMessage::Error("join a game first".to_string()))
+            }
+            (Ok(ClientMessage::Move(next_move)), &Some((ref game, id, role))) => {
+                let mut rooms = rooms.lock().expect("game server lock");
+                let room = rooms.get_mut(game).expect("joined games are kept");
+                let player = room.game.get_current_player();
+                if role != Role::Player(player) {
+                    let message = match role {
+                        Role::Spectator => "spectators cannot move".to_string(),
+                        _ => format!("it is not the turn of {}", role),
+                    };
+                    room.send_to(id, &ServerMessage::Error(message));
+                } else if let Err(error) = room.game.play_move(next_move) {
+                    room.send_to(id, &ServerMessage::Rejected(error.to_string()));
+                } else {
+                    room.broadcast(&ServerMessage::Played { player, next_move });
+                    let state = room.get_state();
+                    room.broadcast(&state);
+                    if let Some(winner) = room.game.winner {
+                        room.broadcast(&ServerMessage::GameOver(winner));
+                    }
+                }
+                None
+            }
+        };
+        // once joined, all messages go through the room so that they are not interleaved
+        match (reply, &seat) {
+            (Some(reply), &Some((ref game, id, _))) => {
+                let mut rooms = rooms.lock().expect("game server lock");
+                if let Some(room) = rooms.get_mut(game) {
+                    room.send_to(id, &reply);
+                }
+            }
+            (Some(reply), &None) => socket.send(&reply.to_string())?,
+            (None, _) => {}
+        }
+    };
+    // the seat is free again once the client is gone
+    if let Some((game, id, _)) = seat {
+        let mut rooms = rooms.lock().expect("game server lock");
+        if let Some(room) = rooms.get_mut(&game) {
+            room.clients.retain(|x| x.0 != id);
+        }
+    }
+    result
+}
@@ -0,0 +1,332 @@
+//! Minimal WebSocket connections (RFC 6455) over `TcpStream`: the opening handshake and text
+//! messages, enough for the game server and its clients.
+use ai::Random;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+/// Longest message accepted, longer ones close the connection.
+pub const MAX_MESSAGE_LENGTH: usize = 1 << 20;
+
+// longest handshake header accepted
+const MAX_HEADER_LENGTH: u64 = 1 << 14;
+
+const CONTINUATION: u8 = 0x0;
+const TEXT: u8 = 0x1;
+const CLOSE: u8 = 0x8;
+const PING: u8 = 0x9;
+const PONG: u8 = 0xa;
+
+/// An open WebSocket connection, either accepted by a server or connected by a client.
+pub struct WebSocket {
+    reader: BufReader<TcpStream>,
+    writer: WebSocketWriter,
+}
+
+/// The sending half of a `WebSocket`, which may be used from other threads.
+pub struct WebSocketWriter {
+    stream: TcpStream,
+    // frames of clients are masked, frames of servers are not
+    random: Option<Random>,
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+/// The value of `Sec-WebSocket-Accept` answering the key of the client.
+pub fn get_accept_key(key: &str) -> String {
+    encode_base64(&sha1(format!("{}{}", key.trim(), GUID).as_bytes()))
+}
+
+// reads the lines of a request or response header up to the empty line
+fn read_header<R: BufRead>(reader: &mut R) -> io::Result<Vec<String>> {
+    let mut lines = vec![];
+    let mut remaining = MAX_HEADER_LENGTH;
+    loop {
+        let mut line = String::new();
+        remaining -= reader.by_ref().take(remaining).read_line(&mut line)? as u64;
+        if !line.ends_with('\n') {
+            return Err(invalid_data(if remaining == 0 {
+                "handshake header too long"
+            } else {
+                "connection closed during the handshake"
+            }));
+        }
+        let line = line.trim_end().to_string();
+        if line.is_empty() {
+            return Ok(lines);
+        }
+        lines.push(line);
+    }
+}
+
+fn get_header_value<'a>(lines: &'a [String], name: &str) -> Option<&'a str> {
+    lines.iter().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        if key.trim().eq_ignore_ascii_case(name) {
+            Some(value.trim())
+        } else {
+            None
+        }
+    })
+}
+
+impl WebSocket {
+    /// Answers the opening handshake of a client, returns the connection and the requested
+    /// path.
+    pub fn accept(stream: TcpStream) -> io::Result<(Self, String)> {
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let lines = read_header(&mut reader)?;
+        let path = lines
+            .first()
+            .and_then(|line| line.split_whitespace().nth(1))
+            .unwrap_or("/")
+            .to_string();
+        let mut stream = stream;
+        let key = match get_header_value(&lines, "Sec-WebSocket-Key") {
+            Some(key) => key,
+            None => {
+                write!(stream, "HTTP/1.1 400 Bad Request\r\nContent-Length: 0\r\n\r\n")?;
+                return Err(invalid_data("request without Sec-WebSocket-Key"));
+            }
+        };
+        write!(
+            stream,
+            "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\n\
+             Connection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n\r\n",
+            get_accept_key(key)
+        )?;
+        let socket = WebSocket {
+            reader,
+            writer: WebSocketWriter {
+                stream,
+                random: None,
+            },
+        };
+        Ok((socket, path))
+    }
+    /// Opens a connection to a server, e.g. `connect("127.0.0.1:8080", "/")`.
+    pub fn connect<A: ToSocketAddrs>(address: A, path: &str) -> io::Result<Self> {
+        let mut stream = TcpStream::connect(address)?;
+        let host = stream.peer_addr()?;
+        let nanoseconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|x| x.subsec_nanos())
+            .unwrap_or(0);
+        let mut random = Random::new(u64::from(nanoseconds) ^ u64::from(host.port()));
+        let key_bytes = (0..16)
+            .map(|_| random.next_u64() as u8)
+            .collect::<Vec<_>>();
+        let key = encode_base64(&key_bytes);
+        write!(
+            stream,
+            "GET {} HTTP/1.1\r\nHost: {}\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\
+             Sec-WebSocket-Key: {}\r\nSec-WebSocket-Version: 13\r\n\r\n",
+            path, host, key
+        )?;
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let lines = read_header(&mut reader)?;
+        let status = lines.first().and_then(|line| line.split_whitespace().nth(1));
+        let accept_key = get_accept_key(&key);
+        let accepted = get_header_value(&lines, "Sec-WebSocket-Accept") == Some(&accept_key[..]);
+        if status != Some("101") || !accepted {
+            return Err(invalid_data("server did not accept the WebSocket handshake"));
+        }
+        Ok(WebSocket {
+            reader,
+            writer: WebSocketWriter {
+                stream,
+                random: Some(random),
+            },
+        })
+    }
+    /// Waits for the next text message, `None` once the other side closed the connection.
+    ///
+    /// Pings are answered while waiting.
+    pub fn receive(&mut self) -> io::Result<Option<String>> {
+        let mut message = vec![];
+        loop {
+            let (is_final, opcode, payload) = match self.read_frame() {
+                Ok(frame) => frame,
+                Err(ref error) if error.kind() == io::ErrorKind::UnexpectedEof => {
+                    return Ok(None)
+                }
+                Err(error) => return Err(error),
+            };
+            match opcode {
+                TEXT | CONTINUATION => {
+                    message.extend(payload);
+                    if message.len() > MAX_MESSAGE_LENGTH {
+                        return Err(invalid_data("message too long"));
+                    }
+                    if is_final {
+                        return String::from_utf8(message)
+                            .map(Some)
+                            .map_err(|_| invalid_data("message is not UTF-8"));
+                    }
+                }
+                CLOSE => {
+                    let _ = self.writer.send_frame(CLOSE, &[]);
+                    return Ok(None);
+                }
+                PING => self.writer.send_frame(PONG, &payload)?,
+                PONG => {}
+                _ => return Err(invalid_data("unsupported WebSocket frame")),
+            }
+        }
+    }
+    pub fn send(&mut self, text: &str) -> io::Result<()> {
+        self.writer.send(text)
+    }
+    /// A second sending half of the connection.
+    pub fn get_writer(&self) -> io::Result<WebSocketWriter> {
+        self.writer.try_clone()
+    }
+    /// Sends the closing frame, the other side answers with its own.
+    pub fn close(&mut self) -> io::Result<()> {
+        self.writer.send_frame(CLOSE, &[])
+    }
+    fn read_frame(&mut self) -> io::Result<(bool, u8, Vec<u8>)> {
+        let mut header = [0u8; 2];
+        self.reader.read_exact(&mut header)?;
+        let is_final = header[0] & 0x80 != 0;
+        let opcode = header[0] & 0x0f;
+        let is_masked = header[1] & 0x80 != 0;
+        let length = match header[1] & 0x7f {
+            126 => {
+                let mut bytes = [0u8; 2];
+                self.reader.read_exact(&mut bytes)?;
+                u64::from(u16::from_be_bytes(bytes))
+            }
+            127 => {
+                let mut bytes = [0u8; 8];
+                self.reader.read_exact(&mut bytes)?;
+                u64::from_be_bytes(bytes)
+            }
+            length => u64::from(length),
+        };
+        if length > MAX_MESSAGE_LENGTH as u64 {
+            return Err(invalid_data("message too long"));
+        }
+        let mut mask = [0u8; 4];
+        if is_masked {
+            self.reader.read_exact(&mut mask)?;
+        }
+        let mut payload = vec![0u8; length as usize];
+        self.reader.read_exact(&mut payload)?;
+        if is_masked {
+            for (index, byte) in payload.iter_mut().enumerate() {
+                *byte ^= mask[index % 4];
+            }
+        }
+        Ok((is_final, opcode, payload))
+    }
+}
+
+impl WebSocketWriter {
+    pub fn send(&mut self, text: &str) -> io::Result<()> {
+        self.send_frame(TEXT, text.as_bytes())
+    }
+    pub fn try_clone(&self) -> io::Result<Self> {
+        Ok(WebSocketWriter {
+            stream: self.stream.try_clone()?,
+            random: self.random.clone(),
+        })
+    }
+    fn send_frame(&mut self, opcode: u8, payload: &[u8]) -> io::Result<()> {
+        let mut frame = vec![0x80 | opcode];
+        let mask_bit = if self.random.is_some() { 0x80 } else { 0 };
+        match payload.len() {
+            length if length < 126 => frame.push(mask_bit | length as u8),
+            length if length <= 0xffff => {
+                frame.push(mask_bit | 126);
+                frame.extend_from_slice(&(length as u16).to_be_bytes());
+            }
+            length => {
+                frame.push(mask_bit | 127);
+                frame.extend_from_slice(&(length as u64).to_be_bytes());
+            }
+        }
+        match self.random {
+            Some(ref mut random) => {
+                let mask = (random.next_u64() as u32).to_be_bytes();
+                frame.extend_from_slice(&mask);
+                frame.extend(payload.iter().enumerate().map(|(i, byte)| byte ^ mask[i % 4]));
+            }
+            None => frame.extend_from_slice(payload),
+        }
+        self.stream.write_all(&frame)?;
+        self.stream.flush()
+    }
+}
+
+fn encode_base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut text = String::new();
+    for chunk in bytes.chunks(3) {
+        let number = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |number, (index, &byte)| number | u32::from(byte) << (16 - 8 * index));
+        for index in 0..4 {
+            if index <= chunk.len() {
+                text.push(ALPHABET[(number >> (18 - 6 * index) & 0x3f) as usize] as char);
+            } else {
+                text.push('=');
+            }
+        }
+    }
+    text
+}
+
+fn sha1(message: &[u8]) -> [u8; 20] {
+    let mut state: [u32; 5] = [0x6745_2301, 0xefcd_ab89, 0x98ba_dcfe, 0x1032_5476, 0xc3d2_e1f0];
+    let mut data = message.to_vec();
+    data.push(0x80);
+    while data.len() % 64 != 56 {
+        data.push(0);
+    }
+    data.extend_from_slice(&(message.len() as u64 * 8).to_be_bytes());
+    for block in data.chunks(64) {
+        let mut words = [0u32; 80];
+        for (index, word) in block.chunks(4).enumerate() {
+            words[index] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for index in 16..80 {
+            words[index] =
+                (words[index - 3] ^ words[index - 8] ^ words[index - 14] ^ words[index - 16])
+                    .rotate_left(1);
+        }
+        let [mut a, mut b, mut c, mut d, mut e] = state;
+        for (index, &word) in words.iter().enumerate() {
+            let (f, k) = match index / 20 {
+                0 => ((b & c) | (!b & d), 0x5a82_7999),
+                1 => (b ^ c ^ d, 0x6ed9_eba1),
+                2 => ((b & c) | (b & d) | (c & d), 0x8f1b_bcdc),
+                _ => (b ^ c ^ d, 0xca62_c1d6),
+            };
+            let next = a
+                .rotate_left(5)
+                .wrapping_add(f)
+                .wrapping_add(e)
+                .wrapping_add(k)
+                .wrapping_add(word);
+            e = d;
+            d = c;
+            c = b.rotate_left(30);
+            b = a;
+            a = next;
+        }
+        for (value, added) in state.iter_mut().zip(&[a, b, c, d, e]) {
+            *value = value.wrapping_add(*added);
+        }
+    }
+    let mut digest = [0u8; 20];
+    for (bytes, value) in digest.chunks_mut(4).zip(&state) {
+        bytes.copy_from_slice(&value.to_be_bytes());
+    }
+    digest
+}
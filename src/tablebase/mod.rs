@@ -0,0 +1,435 @@
+//! Endgame tablebase: the solved outcome of every position reachable on a small board.
+//!
+//! Positions are enumerated from the start position, then solved by retrograde analysis:
+//! positions where the previous player has reached the goal are lost, a position is won if some
+//! move leads to a lost one and lost if all moves lead to won ones.
+use board_square::*;
+use std::collections::{HashMap, VecDeque};
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+/// Solved outcome of a position from the point of view of the player to move.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TablebaseResult {
+    /// the player to move reaches the goal after `plies` plies, counting the plies of both players
+    Win { plies: usize },
+    /// the opponent reaches the goal after `plies` plies
+    Loss { plies: usize },
+    /// neither player can force a win
+    Draw,
+}
+
+impl fmt::Display for TablebaseResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            TablebaseResult::Win { plies } => write!(f, "win in {} plies", plies),
+            TablebaseResult::Loss { plies } => write!(f, "loss in {} plies", plies),
+            TablebaseResult::Draw => write!(f, "draw"),
+        }
+    }
+}
+
+/// Why a tablebase cannot be generated.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TablebaseError {
+    /// a position is decided after more plies than a stored result can hold
+    DistanceTooLong(TablebaseResult),
+}
+
+impl fmt::Display for TablebaseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            TablebaseError::DistanceTooLong(result) => {
+                write!(f, "distance too long for the tablebase: {}", result)
+            }
+        }
+    }
+}
+
+impl Error for TablebaseError {}
+
+// stored as one byte: 0 for a draw, 2 * plies + 1 for a win and 2 * plies + 2 for a loss
+fn encode(result: TablebaseResult) -> Result<u8, TablebaseError> {
+    let value = match result {
+        TablebaseResult::Win { plies } => 2 * plies + 1,
+        TablebaseResult::Loss { plies } => 2 * plies + 2,
+        TablebaseResult::Draw => 0,
+    };
+    if value > u8::MAX as usize {
+        return Err(TablebaseError::DistanceTooLong(result));
+    }
+    Ok(value as u8)
+}
+fn decode(value: u8) -> TablebaseResult {
+    match value {
+        0 => TablebaseResult::Draw,
+        _ if value & 1 == 1 => TablebaseResult::Win {
+            plies: (value as usize - 1) / 2,
+        },
+        _ => TablebaseResult::Loss {
+            plies: (value as usize - 2) / 2,
+        },
+    }
+}
+
+/// Boards whose positions can be stored in a `Tablebase`.
+pub trait TablebaseBoard: PositionBoard {
+    /// Number identifying the position with `player` to move, `None` if it does not fit.
+    fn get_position_key(&self, player: TwoPlayerIndices) -> Option<u64>;
+}
+
+impl<SizeType, WallDataType, WallCrosingType> TablebaseBoard
+    for SquareBoard<usize, SizeType, WallDataType, WallCrosingType>
+where
+    SizeType: IntegerTrait<usize>,
+    WallDataType: WallPositionTrait<usize>,
+    WallCrosingType: WallCrosingTrait<usize>,
+{
+    // mixed radix: placed walls as bits, remaining walls, fields and the player to move
+    fn get_position_key(&self, player: TwoPlayerIndices) -> Option<u64> {
+        let table = self.get_wall_table();
+        if table.len() >= 64 {
+            return None;
+        }
+        let mut key = 0u64;
+        for entry in table.get_entries().iter().rev() {
+            key = (key << 1) | self.is_wall_at_unsafe(entry.slot) as u64;
+        }
+        let wall_radix = self.get_rules().wall_count as u64 + 1;
+        let field_radix = (table.get_board_size() * table.get_board_size()) as u64;
+        let field_number = |field: FieldIndexSquare<usize>| {
+            (field.column * table.get_board_size() + field.row) as u64
+        };
+        let mut digits = vec![];
+        for &other_player in &[TwoPlayerIndices::White, TwoPlayerIndices::Black] {
+            let wall_count = self.get_player_data(other_player).get_wall_count() as u64;
+            digits.push((wall_radix, wall_count));
+        }
+        for &other_player in &[TwoPlayerIndices::White, TwoPlayerIndices::Black] {
+            let field = self.get_current_field(other_player);
+            digits.push((field_radix, field_number(field)));
+        }
+        digits.push((2, (player == TwoPlayerIndices::Black) as u64));
+        for (radix, digit) in digits {
+            key = key.checked_mul(radix)?.checked_add(digit)?;
+        }
+        Some(key)
+    }
+}
+
+/// Solved outcomes of all positions reachable from the start position with the given rules.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Tablebase {
+    board_size: usize,
+    rules: Rules,
+    // sorted, with the encoded result at the same index
+    keys: Vec<u64>,
+    values: Vec<u8>,
+}
+
+const MAGIC: &[u8; 4] = b"VTB1";
+
+impl Tablebase {
+    /// Enumerates and solves all positions reachable from the start position, White moving first.
+    ///
+    /// Feasible for `SquareBoard3` and `SquareBoard4`, the latter takes some seconds.
+    pub fn generate<Board>(rules: Rules) -> Result<Self, TablebaseError>
+    where
+        Board: TablebaseBoard,
+    {
+        let (keys, successors) = enumerate_positions(Board::with_rules(rules));
+        let values = solve(&successors)?;
+        let mut entries = keys.into_iter().zip(values).collect::<Vec<_>>();
+        entries.sort_unstable();
+        Ok(Tablebase {
+            board_size: Board::AVERAGE_BOARD_SIZE,
+            rules,
+            keys: entries.iter().map(|&(key, _)| key).collect(),
+            values: entries.iter().map(|&(_, value)| value).collect(),
+        })
+    }
+    pub fn get_board_size(&self) -> usize {
+        self.board_size
+    }
+    pub fn get_rules(&self) -> Rules {
+        self.rules
+    }
+    /// Number of stored positions.
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+    /// The outcome with `player` to move, `None` if the position is not in the tablebase.
+    pub fn probe<Board>(&self, board: &Board, player: TwoPlayerIndices) -> Option<TablebaseResult>
+    where
+        Board: TablebaseBoard,
+    {
+        if Board::AVERAGE_BOARD_SIZE != self.board_size || board.get_rules() != self.rules {
+            return None;
+        }
+        let key = board.get_position_key(player)?;
+        self.keys
+            .binary_search(&key)
+            .ok()
+            .map(|index| decode(self.values[index]))
+    }
+    /// A move keeping the best outcome: the fastest win, or else the slowest loss.
+    pub fn get_best_move<Board>(
+        &self,
+        board: &Board,
+        player: TwoPlayerIndices,
+    ) -> Option<(BoardMove<Board>, TablebaseResult)>
+    where
+        Board: TablebaseBoard + Clone,
+    {
+        if board.get_winner().is_some() {
+            return None;
+        }
+        let mut board = board.clone();
+        let mut best: Option<(BoardMove<Board>, TablebaseResult)> = None;
+        for next_move in board.get_candidate_moves(player) {
+            if let Ok(token) = board.make_move(player, next_move) {
+                let result = self.probe(&board, player.next_player()).map(|result| match result {
+                    TablebaseResult::Win { plies } => TablebaseResult::Loss { plies: plies + 1 },
+                    TablebaseResult::Loss { plies } => TablebaseResult::Win { plies: plies + 1 },
+                    TablebaseResult::Draw => TablebaseResult::Draw,
+                });
+                board.unmake_move(token);
+                match (result, best) {
+                    (Some(result), Some((_, best_result))) if !is_better(result, best_result) => {}
+                    (Some(result), _) => best = Some((next_move, result)),
+                    (None, _) => {}
+                }
+            }
+        }
+        best
+    }
+    /// Writes the tablebase: a header, then per position the key difference to the previous one
+    /// as a variable length number and the result as one byte. Board sizes and wall counts
+    /// above 255 do not fit the header.
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        let header_byte = |number: usize, name: &str| {
+            u8::try_from(number).map_err(|_| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("{} {} does not fit the tablebase header", name, number),
+                )
+            })
+        };
+        let board_size = header_byte(self.board_size, "board size")?;
+        let wall_count = header_byte(self.rules.wall_count, "wall count")?;
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[board_size, wall_count])?;
+        writer.write_all(&(self.keys.len() as u64).to_le_bytes())?;
+        let mut previous_key = 0;
+        for (&key, &value) in self.keys.iter().zip(self.values.iter()) {
+            let mut difference = key - previous_key;
+            while difference >= 0x80 {
+                writer.write_all(&[(difference & 0x7f) as u8 | 0x80])?;
+                difference >>= 7;
+            }
+            writer.write_all(&[difference as u8, value])?;
+            previous_key = key;
+        }
+        Ok(())
+    }
+    pub fn read_from<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let invalid =
+            |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+        let mut header = [0u8; 14];
+        reader.read_exact(&mut header)?;
+        if &header[..4] != MAGIC {
+            return Err(invalid("not a varidor tablebase"));
+        }
+        let mut length = [0u8; 8];
+        length.copy_from_slice(&header[6..]);
+        let length = u64::from_le_bytes(length) as usize;
+        // the length is not trusted before the entries are read
+        let mut keys = Vec::with_capacity(length.min(1 << 20));
+        let mut values = Vec::with_capacity(length.min(1 << 20));
+        let mut previous_key = 0u64;
+        let mut byte = [0u8; 1];
+        for _ in 0..length {
+            let mut difference = 0u64;
+            let mut shift = 0;
+            loop {
+                reader.read_exact(&mut byte)?;
+                // the last of ten bytes holds a single bit
+                let bits = (byte[0] & 0x7f) as u64;
+                if shift > 63 || (bits << shift) >> shift != bits {
+                    return Err(invalid("key out of range"));
+                }
+                difference |= bits << shift;
+                shift += 7;
+                if byte[0] & 0x80 == 0 {
+                    break;
+                }
+            }
+            // keys are stored once, in increasing order
+            if difference == 0 && !keys.is_empty() {
+                return Err(invalid("repeated key"));
+            }
+            previous_key = previous_key
+                .checked_add(difference)
+                .ok_or_else(|| invalid("key out of range"))?;
+            reader.read_exact(&mut byte)?;
+            keys.push(previous_key);
+            values.push(byte[0]);
+        }
+        Ok(Tablebase {
+            board_size: header[4] as usize,
+            rules: Rules {
+                wall_count: header[5] as usize,
+            },
+            keys,
+            values,
+        })
+    }
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_to(&mut writer)?;
+        writer.flush()
+    }
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::read_from(&mut BufReader::new(File::open(path)?))
+    }
+}
+
+// wins beat draws beat losses, faster wins and slower losses are better
+fn is_better(result: TablebaseResult, other: TablebaseResult) -> bool {
+    use self::TablebaseResult::*;
+    match (result, other) {
+        (Win { plies }, Win { plies: other_plies }) => plies < other_plies,
+        (Loss { plies }, Loss { plies: other_plies }) => plies > other_plies,
+        (Win { .. }, _) | (Draw, Loss { .. }) => true,
+        _ => false,
+    }
+}
+
+// depth first search with make_move and unmake_move, returns the key and the successor indices
+// of every position, positions with a winner have no successors
+fn enumerate_positions<Board>(mut board: Board) -> (Vec<u64>, Vec<Vec<u32>>)
+where
+    Board: TablebaseBoard,
+{
+    let start_key = board
+        .get_position_key(TwoPlayerIndices::White)
+        .expect("board too large for a tablebase");
+    let mut indices = HashMap::new();
+    indices.insert(start_key, 0u32);
+    let mut keys = vec![start_key];
+    let mut successors = vec![vec![]];
+    // per visited position: its index, the player to move, its moves, the next move to try and
+    // the token to return to the previous position
+    let mut stack = vec![(
+        0u32,
+        TwoPlayerIndices::White,
+        board.get_candidate_moves(TwoPlayerIndices::White),
+        0,
+        None,
+    )];
+    while let Some((index, player, moves, next, token)) = stack.pop() {
+        if next == moves.len() {
+            if let Some(token) = token {
+                board.unmake_move(token);
+            }
+            continue;
+        }
+        let next_move = moves[next];
+        stack.push((index, player, moves, next + 1, token));
+        let child_token = match board.make_move(player, next_move) {
+            Ok(token) => token,
+            Err(_) => continue,
+        };
+        let next_player = player.next_player();
+        let key = board
+            .get_position_key(next_player)
+            .expect("board too large for a tablebase");
+        let (child_index, is_new) = match indices.get(&key) {
+            Some(&child_index) => (child_index, false),
+            None => (keys.len() as u32, true),
+        };
+        successors[index as usize].push(child_index);
+        if !is_new {
+            board.unmake_move(child_token);
+            continue;
+        }
+        indices.insert(key, child_index);
+        keys.push(key);
+        successors.push(vec![]);
+        let child_moves = if board.get_winner().is_some() {
+            vec![]
+        } else {
+            board.get_candidate_moves(next_player)
+        };
+        stack.push((child_index, next_player, child_moves, 0, Some(child_token)));
+    }
+    (keys, successors)
+}
+
+// retrograde analysis: decided positions are taken from a queue in the order of their
+// distance, a predecessor is won by the first lost position found and lost once all of its
+// successors are won, the last of them having the longest distance
+fn solve(successors: &[Vec<u32>]) -> Result<Vec<u8>, TablebaseError> {
+    let mut predecessors = vec![vec![]; successors.len()];
+    for (index, next) in successors.iter().enumerate() {
+        for &child in next {
+            predecessors[child as usize].push(index as u32);
+        }
+    }
+    // undecided successors per position
+    let mut remaining = successors.iter().map(|next| next.len()).collect::<Vec<_>>();
+    let mut results: Vec<Option<TablebaseResult>> = vec![None; successors.len()];
+    let mut queue = VecDeque::new();
+    for (index, next) in successors.iter().enumerate() {
+        if next.is_empty() {
+            results[index] = Some(TablebaseResult::Loss { plies: 0 });
+            queue.push_back(index);
+        }
+    }
+    while let Some(index) = queue.pop_front() {
+        let result = results[index].expect("queued positions are decided");
+        for &previous in &predecessors[index] {
+            let previous = previous as usize;
+            if results[previous].is_some() {
+                continue;
+            }
+            match result {
+                TablebaseResult::Loss { plies } => {
+                    results[previous] = Some(TablebaseResult::Win { plies: plies + 1 });
+                    queue.push_back(previous);
+                }
+                TablebaseResult::Win { plies } => {
+                    remaining[previous] -= 1;
+                    if remaining[previous] == 0 {
+                        results[previous] = Some(TablebaseResult::Loss { plies: plies + 1 });
+                        queue.push_back(previous);
+                    }
+                }
+                TablebaseResult::Draw => {}
+            }
+        }
+    }
+    results
+        .into_iter()
+        .map(|result| encode(result.unwrap_or(TablebaseResult::Draw)))
+        .collect()
+}
+
+impl fmt::Display for Tablebase {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "tablebase for board size {} with {} walls: {} positions",
+            self.board_size,
+            self.rules.wall_count,
+            self.len()
+        )
+    }
+}
@@ -0,0 +1,228 @@
+//! Matches between two engine configurations: games with alternating colours, played in
+//! parallel, with the result as win/draw/loss counts and an Elo difference.
+//!
+//! The board size is the one of the board type, the `tournament` binary picks the board type
+//! of the size given on its command line, `SquareBoard3` up to `SquareBoard11`.
+use ai::*;
+use board_square::*;
+use book::*;
+use evaluation::*;
+use record::*;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::Path;
+use std::thread;
+use tablebase::*;
+
+/// Settings of `choose_move` for one side of a tournament.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Engine {
+    /// written to the `White` and `Black` tags of the records
+    pub name: String,
+    pub depth: usize,
+    pub evaluator: LinearEvaluator,
+    pub book: Option<OpeningBook>,
+}
+
+impl Engine {
+    /// An engine searching `depth` plies with the default evaluator and no book.
+    pub fn new(name: &str, depth: usize) -> Self {
+        Engine {
+            name: name.to_string(),
+            depth,
+            evaluator: LinearEvaluator::default(),
+            book: None,
+        }
+    }
+    pub fn get_settings(&self) -> SearchSettings<'_> {
+        SearchSettings {
+            depth: self.depth,
+            tablebase: None,
+            book: self.book.as_ref(),
+            evaluator: self.evaluator,
+            deadline: None,
+        }
+    }
+}
+
+/// How a tournament is played, see `Tournament::run`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Tournament {
+    pub games: usize,
+    pub rules: Rules,
+    /// plies drawn at random at the start of every game to vary the games
+    pub random_plies: usize,
+    /// number of games played at the same time
+    pub threads: usize,
+    /// game `i` draws its random numbers from the seed `seed + i`
+    pub seed: u64,
+}
+
+impl Default for Tournament {
+    fn default() -> Self {
+        Tournament {
+            games: 100,
+            rules: Rules::default(),
+            random_plies: 2,
+            threads: 1,
+            seed: 1,
+        }
+    }
+}
+
+impl Tournament {
+    /// Plays the games, the first engine has White in the games with an even index.
+    ///
+    /// The records do not depend on the number of threads.
+    pub fn run<Board>(&self, engines: &[Engine; 2]) -> TournamentResult
+    where
+        Board: TablebaseBoard + Clone,
+    {
+        let threads = self.threads.max(1);
+        let mut records = thread::scope(|scope| {
+            let handles = (0..threads)
+                .map(|first| {
+                    scope.spawn(move || {
+                        (first..self.games)
+                            .step_by(threads)
+                            .map(|index| (index, self.play::<Board>(engines, index)))
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect::<Vec<_>>();
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().expect("tournament game panicked"))
+                .collect::<Vec<_>>()
+        });
+        records.sort_by_key(|(index, _)| *index);
+        TournamentResult::new(
+            [engines[0].name.clone(), engines[1].name.clone()],
+            records.into_iter().map(|(_, record)| record).collect(),
+        )
+    }
+    fn play<Board>(&self, engines: &[Engine; 2], index: usize) -> GameRecord
+    where
+        Board: TablebaseBoard + Clone,
+    {
+        let (white, black) = if index & 1 == 0 {
+            (&engines[0], &engines[1])
+        } else {
+            (&engines[1], &engines[0])
+        };
+        let settings = [white.get_settings(), black.get_settings()];
+        let mut random = Random::new(self.seed.wrapping_add(index as u64));
+        let mut record = play_game::<Board, _>(
+            self.rules,
+            [&settings[0], &settings[1]],
+            self.random_plies,
+            &mut random,
+        );
+        record.set_tag("Round", &(index + 1).to_string());
+        record.set_tag("White", &white.name);
+        record.set_tag("Black", &black.name);
+        record
+    }
+}
+
+/// Games of a tournament, the counts are from the view of the first engine.
+#[derive(Clone, PartialEq, Debug)]
+pub struct TournamentResult {
+    pub names: [String; 2],
+    /// in the order they were started, the first engine has White in the even ones
+    pub records: Vec<GameRecord>,
+    pub wins: usize,
+    pub draws: usize,
+    pub losses: usize,
+}
+
+impl TournamentResult {
+    pub fn new(names: [String; 2], records: Vec<GameRecord>) -> Self {
+        let mut result = TournamentResult {
+            names,
+            records: vec![],
+            wins: 0,
+            draws: 0,
+            losses: 0,
+        };
+        for (index, record) in records.iter().enumerate() {
+            let first_player = GameRecord::get_player(index);
+            match record.winner {
+                Some(winner) if winner == first_player => result.wins += 1,
+                Some(_) => result.losses += 1,
+                None => result.draws += 1,
+            }
+        }
+        result.records = records;
+        result
+    }
+    pub fn get_games(&self) -> usize {
+        self.wins + self.draws + self.losses
+    }
+    /// Points per game of the first engine, a draw counting half.
+    pub fn get_score(&self) -> f64 {
+        if self.get_games() == 0 {
+            return 0.5;
+        }
+        (self.wins as f64 + 0.5 * self.draws as f64) / self.get_games() as f64
+    }
+    /// Rating difference of the first engine to the second one which predicts its score,
+    /// infinite if one engine won every game.
+    pub fn get_elo_difference(&self) -> f64 {
+        get_elo(self.get_score())
+    }
+    /// Bounds of the 95% confidence interval of `get_elo_difference`, from the standard
+    /// error of the score.
+    pub fn get_elo_interval(&self) -> (f64, f64) {
+        let games = self.get_games() as f64;
+        if games == 0. {
+            return (f64::NEG_INFINITY, f64::INFINITY);
+        }
+        let score = self.get_score();
+        let squares = (self.wins as f64 + 0.25 * self.draws as f64) / games;
+        let error = ((squares - score * score).max(0.) / games).sqrt();
+        (get_elo(score - 1.96 * error), get_elo(score + 1.96 * error))
+    }
+    /// Writes each record to its own file `game-<round>.txt` in the directory, which is
+    /// created if needed.
+    pub fn save_records<P: AsRef<Path>>(&self, directory: P) -> io::Result<()> {
+        fs::create_dir_all(&directory)?;
+        for (index, record) in self.records.iter().enumerate() {
+            let path = directory
+                .as_ref()
+                .join(format!("game-{:04}.txt", index + 1));
+            write!(File::create(path)?, "{}", record)?;
+        }
+        Ok(())
+    }
+}
+
+fn get_elo(score: f64) -> f64 {
+    if score <= 0. {
+        f64::NEG_INFINITY
+    } else if score >= 1. {
+        f64::INFINITY
+    } else {
+        -400. * (1. / score - 1.).log10()
+    }
+}
+
+impl fmt::Display for TournamentResult {
+    /// e.g. `new - old: +12 =3 -5, Elo +124 (+8 .. +262)`
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (lower, upper) = self.get_elo_interval();
+        write!(
+            f,
+            "{} - {}: +{} ={} -{}, Elo {:+.0} ({:+.0} .. {:+.0})",
+            self.names[0],
+            self.names[1],
+            self.wins,
+            self.draws,
+            self.losses,
+            self.get_elo_difference(),
+            lower,
+            upper
+        )
+    }
+}
@@ -0,0 +1,172 @@
+extern crate varidor;
+use varidor::*;
+
+fn board_after(moves: &[&str]) -> SquareBoard5 {
+    let mut board = SquareBoard5::new();
+    let mut player = TwoPlayerIndices::White;
+    for text in moves {
+        board.play_move(player, text.parse().unwrap()).unwrap();
+        player = player.next_player();
+    }
+    board
+}
+
+#[test]
+fn most_damaging_walls_are_ranked_by_gain() {
+    let board = board_after(&["step down", "step up", "wall (1,1) horizontal"]);
+    let player = TwoPlayerIndices::Black;
+    let opponent = TwoPlayerIndices::White;
+    let all = board.most_damaging_walls(player, usize::MAX);
+    assert_eq!(all.len(), board.get_legal_moves(player).len() - 3);
+    for evaluation in &all {
+        let mut after = board.clone();
+        after
+            .place_wall_at(player, evaluation.slot)
+            .unwrap();
+        assert_eq!(evaluation.own_length_before, board.get_path_length(player).unwrap());
+        assert_eq!(
+            evaluation.opponent_length_before,
+            board.get_path_length(opponent).unwrap()
+        );
+        assert_eq!(evaluation.own_length_after, after.get_path_length(player).unwrap());
+        assert_eq!(
+            evaluation.opponent_length_after,
+            after.get_path_length(opponent).unwrap()
+        );
+    }
+    for pair in all.windows(2) {
+        assert!(
+            (pair[0].get_gain(), pair[0].get_opponent_delay())
+                >= (pair[1].get_gain(), pair[1].get_opponent_delay())
+        );
+    }
+    assert_eq!(board.most_damaging_walls(player, 3), all[..3].to_vec());
+    assert!(all[0].get_gain() > 0);
+}
+
+#[test]
+fn no_walls_are_suggested_without_remaining_walls() {
+    let board = SquareBoard5::with_rules(Rules { wall_count: 0 });
+    assert!(board
+        .most_damaging_walls(TwoPlayerIndices::White, 5)
+        .is_empty());
+}
+
+// whether `winner` reaches the goal within `plies` plies against every defence
+fn can_force_win(
+    board: &SquareBoard3,
+    to_move: TwoPlayerIndices,
+    winner: TwoPlayerIndices,
+    plies: usize,
+) -> bool {
+    if let Some(player) = board.get_winner() {
+        return player == winner;
+    }
+    if plies == 0 {
+        return false;
+    }
+    let mut outcomes = board
+        .get_successors(to_move)
+        .into_iter()
+        .map(|(_, next)| can_force_win(&next, to_move.next_player(), winner, plies - 1));
+    if to_move == winner {
+        outcomes.any(|x| x)
+    } else {
+        outcomes.all(|x| x)
+    }
+}
+
+#[test]
+fn race_without_walls_is_won_by_the_player_to_move_on_equal_paths() {
+    let board = SquareBoard5::with_rules(Rules { wall_count: 0 });
+    assert_eq!(
+        board.solve_race(TwoPlayerIndices::White),
+        Some(RaceResult {
+            winner: TwoPlayerIndices::White,
+            plies: 7,
+        })
+    );
+    assert_eq!(
+        board.solve_race(TwoPlayerIndices::Black),
+        Some(RaceResult {
+            winner: TwoPlayerIndices::Black,
+            plies: 7,
+        })
+    );
+    assert_eq!(SquareBoard5::new().solve_race(TwoPlayerIndices::White), None);
+}
+
+#[test]
+fn race_results_are_forced() {
+    let white = TwoPlayerIndices::White;
+    let black = TwoPlayerIndices::Black;
+    let board = SquareBoard3::with_rules(Rules { wall_count: 1 });
+    let mut checked = 0;
+    for (_, after_white) in board.get_successors(white) {
+        for (_, after_black) in after_white.get_successors(black) {
+            assert_eq!(after_white.solve_race(black), None);
+            let result = match after_black.solve_race(white) {
+                Some(result) => result,
+                None => continue,
+            };
+            let loser = result.winner.next_player();
+            assert!(can_force_win(&after_black, white, result.winner, result.plies));
+            assert!(!can_force_win(&after_black, white, result.winner, result.plies - 1));
+            assert!(!can_force_win(&after_black, white, loser, result.plies + 1));
+            checked += 1;
+        }
+    }
+    assert!(checked > 0);
+}
+
+#[test]
+fn search_scores_races_exactly() {
+    let board = SquareBoard3::with_rules(Rules { wall_count: 0 });
+    let result = ai::search(&board, TwoPlayerIndices::White, 1);
+    assert_eq!(result.score, ai::WIN_SCORE - 3);
+    assert_eq!(result.best_move, Some(Move::Step(DirectionsSquare::Down)));
+    let result = ai::search(&board, TwoPlayerIndices::Black, 4);
+    assert_eq!(result.score, ai::WIN_SCORE - 3);
+    assert_eq!(result.best_move, Some(Move::Step(DirectionsSquare::Up)));
+}
+
+#[test]
+fn search_finds_the_fastest_win() {
+    let board = board_after(&[
+        "step down",
+        "step up",
+        "step down",
+        "step up",
+        "step down",
+        "step up",
+    ]);
+    let result = ai::search(&board, TwoPlayerIndices::White, 1);
+    assert_eq!(result.best_move, Some(Move::Step(DirectionsSquare::Down)));
+    assert_eq!(result.score, ai::WIN_SCORE - 1);
+}
+
+#[test]
+fn game_declares_the_race_once_the_walls_are_used() {
+    for &end_decided_races in &[false, true] {
+        let mut game: Game<SquareBoard3> = Game::with_rules(Rules { wall_count: 1 });
+        game.end_decided_races = end_decided_races;
+        game.play_move("wall (0,0) horizontal".parse().unwrap())
+            .unwrap();
+        assert_eq!(game.race_result, None);
+        game.play_move("wall (1,1) horizontal".parse().unwrap())
+            .unwrap();
+        let result = game.board.solve_race(TwoPlayerIndices::White).unwrap();
+        assert_eq!(game.race_result, Some(result));
+        let entries = game.get_logbook().get_entries();
+        assert!(entries.iter().any(|(_, entry)| {
+            *entry == LogEntry::RaceDecided {
+                winner: result.winner,
+                plies: result.plies,
+            }
+        }));
+        assert_eq!(game.is_game_over(), end_decided_races);
+        if end_decided_races {
+            assert_eq!(game.winner, Some(result.winner));
+        }
+    }
+}
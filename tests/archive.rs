@@ -0,0 +1,170 @@
+extern crate varidor;
+use varidor::archive::*;
+use varidor::*;
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+const WHITE: TwoPlayerIndices = TwoPlayerIndices::White;
+const BLACK: TwoPlayerIndices = TwoPlayerIndices::Black;
+
+fn record(players: [&str; 2], moves: &[&str], winner: Option<TwoPlayerIndices>) -> GameRecord {
+    let mut record = GameRecord::new(5, Rules::default());
+    record.set_tag("White", players[0]);
+    record.set_tag("Black", players[1]);
+    record.moves = moves.iter().map(|x| x.parse().unwrap()).collect();
+    record.winner = winner;
+    record
+}
+
+// an archive with three games in a new file
+fn archive(name: &str) -> (Archive, PathBuf) {
+    let path = env::temp_dir().join(name);
+    let _ = fs::remove_file(&path);
+    let mut archive = Archive::open(&path).unwrap();
+    assert!(archive.is_empty());
+    let records = [
+        record(["alice", "bob"], &["step down", "step up", "step left"], Some(WHITE)),
+        record(["bob", "carol"], &["step down", "step up", "step right"], Some(BLACK)),
+        record(["carol", "alice"], &["wall (0,2) horizontal", "step up"], None),
+    ];
+    for (index, record) in records.iter().enumerate() {
+        assert_eq!(archive.add(record).unwrap(), index as u64 + 1);
+    }
+    (archive, path)
+}
+
+fn ids(games: Vec<&ArchivedGame>) -> Vec<u64> {
+    games.iter().map(|game| game.id).collect()
+}
+
+#[test]
+fn games_are_stored_with_their_metadata() {
+    let (_, path) = archive("varidor_archive_stored.txt");
+    let mut record = GameRecord::new(3, Rules { wall_count: 2 });
+    record.moves.push("step down".parse().unwrap());
+    record.set_tag("Date", "2024-05-01");
+    let mut archive = Archive::open(&path).unwrap();
+    assert_eq!(archive.len(), 3);
+    assert_eq!(archive.add(&record).unwrap(), 4);
+    let game = archive.get_game(4).unwrap();
+    assert_eq!(game.get_player(WHITE), UNKNOWN_PLAYER);
+    assert_eq!(game.get_date(), "2024-05-01");
+    assert_eq!(game.get_length(), 1);
+
+    let archive = Archive::open(&path).unwrap();
+    assert_eq!(archive.get_games().len(), 4);
+    let game = archive.get_game(1).unwrap();
+    assert_eq!(game.get_player(WHITE), "alice");
+    assert_eq!(game.get_player(BLACK), "bob");
+    assert_eq!(game.get_date(), get_today());
+    assert_eq!(game.get_date().len(), "2024-05-01".len());
+    assert_eq!(game.record.board_size, 5);
+    assert_eq!(game.record.rules, Rules::default());
+    assert_eq!(game.record.winner, Some(WHITE));
+    assert_eq!(game.get_length(), 3);
+    assert_eq!(archive.get_game(4).unwrap().record.rules, Rules { wall_count: 2 });
+    assert_eq!(archive.get_game(5), None);
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn illegal_games_are_not_stored() {
+    let (mut archive, path) = archive("varidor_archive_illegal.txt");
+    let text = fs::read_to_string(&path).unwrap();
+    let illegal = record(["alice", "bob"], &["step up"], None);
+    assert!(archive.add(&illegal).is_err());
+    let mut unsupported = illegal.clone();
+    unsupported.board_size = 7;
+    assert!(archive.add(&unsupported).is_err());
+    assert_eq!(archive.len(), 3);
+    assert_eq!(fs::read_to_string(&path).unwrap(), text);
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn games_are_found_by_player_result_position_and_opening() {
+    let (archive, path) = archive("varidor_archive_search.txt");
+    let search = |query: GameQuery| ids(archive.search(&query));
+    assert_eq!(search(GameQuery::default()), vec![1, 2, 3]);
+    let by_player = |name: &str| GameQuery {
+        player: Some(name.to_string()),
+        ..GameQuery::default()
+    };
+    assert_eq!(search(by_player("alice")), vec![1, 3]);
+    assert_eq!(search(by_player("dave")), Vec::<u64>::new());
+    let by_result = |result| GameQuery {
+        result: Some(result),
+        ..GameQuery::default()
+    };
+    assert_eq!(search(by_result(Some(BLACK))), vec![2]);
+    assert_eq!(search(by_result(None)), vec![3]);
+    let by_board = GameQuery {
+        board_size: Some(3),
+        ..GameQuery::default()
+    };
+    assert_eq!(search(by_board), Vec::<u64>::new());
+
+    // the mirror image of a position or an opening finds the game as well
+    let mut board = SquareBoard5::new();
+    for (index, text) in ["step down", "step up", "step left"].iter().enumerate() {
+        board
+            .play_move(GameRecord::get_player(index), text.parse().unwrap())
+            .unwrap();
+    }
+    let by_position = GameQuery {
+        position_hash: Some(board.get_position(BLACK).get_canonical_hash()),
+        ..GameQuery::default()
+    };
+    assert_eq!(search(by_position.clone()), vec![1, 2]);
+    let alice_in_position = GameQuery {
+        player: Some("alice".to_string()),
+        ..by_position
+    };
+    assert_eq!(search(alice_in_position), vec![1]);
+    let start = SquareBoard5::new().get_position(WHITE).get_canonical_hash();
+    assert!(archive.get_games().iter().all(|x| x.contains_position(start)));
+    let by_opening = |text: &str| GameQuery {
+        opening: Move::parse_all(text).unwrap(),
+        ..GameQuery::default()
+    };
+    assert_eq!(search(by_opening("step down step up step right")), vec![1, 2]);
+    assert_eq!(search(by_opening("wall (0,2) horizontal")), vec![3]);
+    assert_eq!(search(by_opening("step down step down")), Vec::<u64>::new());
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn games_are_exported_and_imported_as_record_files() {
+    let (archive, path) = archive("varidor_archive_export.txt");
+    let export_path = env::temp_dir().join("varidor_archive_exported.txt");
+    let query = GameQuery {
+        player: Some("bob".to_string()),
+        ..GameQuery::default()
+    };
+    assert_eq!(archive.export(&export_path, &query).unwrap(), 2);
+    let text = fs::read_to_string(&export_path).unwrap();
+    assert_eq!(GameRecord::parse_all(&text).unwrap().len(), 2);
+
+    let import_path = env::temp_dir().join("varidor_archive_imported.txt");
+    let _ = fs::remove_file(&import_path);
+    let mut imported = Archive::open(&import_path).unwrap();
+    assert_eq!(imported.import(&export_path).unwrap(), 2);
+    assert_eq!(imported.import(&export_path).unwrap(), 0);
+    assert_eq!(imported.import(&path).unwrap(), 1);
+    assert_eq!(imported.len(), 3);
+    let game = imported.get_game(3).unwrap();
+    assert_eq!(game.get_player(WHITE), "carol");
+    assert_eq!(game.get_player(BLACK), "alice");
+    assert_eq!(game.record.get_tag("Id"), Some("3"));
+    // the same moves from another position are another game
+    let mut from_position = imported.get_game(1).unwrap().record.clone();
+    from_position.start_position = Some("5 white (3,4) (3,0) 5 5".parse().unwrap());
+    fs::write(&export_path, from_position.to_string()).unwrap();
+    assert_eq!(imported.import(&export_path).unwrap(), 1);
+    assert_eq!(imported.len(), 4);
+    for path in &[path, export_path, import_path] {
+        fs::remove_file(path).unwrap();
+    }
+}
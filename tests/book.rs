@@ -0,0 +1,122 @@
+extern crate varidor;
+use varidor::ai::*;
+use varidor::book::*;
+use varidor::*;
+
+fn record(moves: &[&str], winner: Option<TwoPlayerIndices>) -> GameRecord {
+    let mut record = GameRecord::new(5, Rules::default());
+    record.moves = moves.iter().map(|x| x.parse().unwrap()).collect();
+    record.winner = winner;
+    record
+}
+
+fn mirrored(record: &GameRecord) -> GameRecord {
+    let mut mirrored = record.clone();
+    mirrored.moves = record.moves.iter().map(|x| x.mirrored(5)).collect();
+    mirrored
+}
+
+#[test]
+fn records_are_read_back_from_their_text() {
+    let mut game: Game<SquareBoard5> = Game::new();
+    for text in &["step down", "wall (0,2) horizontal", "step left", "step up"] {
+        game.play_move(text.parse().unwrap()).unwrap();
+    }
+    let mut record = game.to_record();
+    record.set_tag("White", "alice");
+    record.set_tag("White", "carol");
+    assert_eq!(record.get_tag("White"), Some("carol"));
+    assert_eq!(record.moves.len(), 4);
+    let text = record.to_string();
+    let tags = "[Board \"5\"]\n[Walls \"5\"]\n[Result \"none\"]\n[White \"carol\"]\n";
+    assert!(text.starts_with(tags));
+    assert_eq!(text.parse::<GameRecord>(), Ok(record.clone()));
+    let both = format!("{}\n{}", text, text);
+    assert_eq!(GameRecord::parse_all(&both), Ok(vec![record.clone(), record.clone()]));
+    assert!(both.parse::<GameRecord>().is_err());
+    assert!("[Board \"5\"]\nstep down".parse::<GameRecord>().is_err());
+    assert!("[Board 5]\n[Walls \"5\"]\n[Result \"none\"]".parse::<GameRecord>().is_err());
+    let boards = record.replay::<SquareBoard5>().unwrap();
+    assert_eq!(boards.len(), 5);
+    assert_eq!(
+        boards[4].get_position(TwoPlayerIndices::White),
+        game.board.get_position(TwoPlayerIndices::White)
+    );
+    assert!(record.replay::<SquareBoard3>().is_err());
+}
+
+#[test]
+fn mirrored_positions_share_their_hash() {
+    let first = record(&["step left", "wall (0,1) vertical"], None);
+    let first_board = first.replay::<SquareBoard5>().unwrap().pop().unwrap();
+    let second_board = mirrored(&first).replay::<SquareBoard5>().unwrap().pop().unwrap();
+    let first_position = first_board.get_position(TwoPlayerIndices::White);
+    let second_position = second_board.get_position(TwoPlayerIndices::White);
+    assert!(first_position != second_position);
+    assert_eq!(first_position.mirrored(), second_position);
+    assert_eq!(first_position.get_canonical_hash(), second_position.get_canonical_hash());
+    assert!(first_position.get_hash() != second_position.get_hash());
+    let black_position = first_board.get_position(TwoPlayerIndices::Black);
+    assert!(first_position.get_canonical_hash() != black_position.get_canonical_hash());
+}
+
+#[test]
+fn book_counts_moves_and_results_of_mirrored_games_together() {
+    let white = TwoPlayerIndices::White;
+    let black = TwoPlayerIndices::Black;
+    let first = record(&["step left", "step up", "step down"], Some(white));
+    let mut book = OpeningBook::new(2);
+    book.add_record::<SquareBoard5>(&first).unwrap();
+    book.add_record::<SquareBoard5>(&mirrored(&first)).unwrap();
+    book.add_record::<SquareBoard5>(&record(&["step down"], Some(black)))
+        .unwrap();
+    assert_eq!(book.len(), 2);
+    let start = SquareBoard5::new();
+    let moves = book.explore(&start, white);
+    assert_eq!(moves.len(), 2);
+    assert_eq!(moves[0].games, 2);
+    assert_eq!(moves[0].wins, 2);
+    assert_eq!(moves[1].next_move, Move::Step(DirectionsSquare::Down));
+    assert_eq!(moves[1].get_win_rate(), 0.);
+    for &direction in &[DirectionsSquare::Left, DirectionsSquare::Right] {
+        let mut board = start.clone();
+        board.play_move(white, Move::Step(direction)).unwrap();
+        let moves = book.explore(&board, black);
+        assert_eq!(moves.len(), 1);
+        assert_eq!(moves[0].next_move, Move::Step(DirectionsSquare::Up));
+        assert_eq!((moves[0].games, moves[0].losses), (2, 2));
+        assert!(book.explore(&board, white).is_empty());
+    }
+    let mut bytes = vec![];
+    book.write_to(&mut bytes).unwrap();
+    assert_eq!(OpeningBook::read_from(&bytes[..]).unwrap(), book);
+    assert!(OpeningBook::read_from(&b"varidor opening book 1\nmax_plies x\n"[..]).is_err());
+    // counts whose sum overflows are not more than the games
+    let overflow = "varidor opening book 1\nmax_plies 4\n1f 1 4294967295 2 step down\n";
+    assert!(OpeningBook::read_from(overflow.as_bytes()).is_err());
+}
+
+#[test]
+fn engine_plays_book_moves_and_searches_outside_the_book() {
+    let mut book = OpeningBook::new(1);
+    book.add_record::<SquareBoard5>(&record(&["wall (1,3) horizontal"], None))
+        .unwrap();
+    let settings = SearchSettings {
+        depth: 1,
+        book: Some(&book),
+        ..SearchSettings::default()
+    };
+    let mut random = Random::new(7);
+    let board = SquareBoard5::new();
+    let played = choose_move(&board, TwoPlayerIndices::White, &settings, &mut random).unwrap();
+    assert!(
+        played == "wall (1,3) horizontal".parse().unwrap()
+            || played == "wall (2,3) horizontal".parse().unwrap()
+    );
+    let searched = choose_move(&board, TwoPlayerIndices::Black, &settings, &mut random);
+    assert_eq!(searched, search(&board, TwoPlayerIndices::Black, 1).best_move);
+    let game = self_play::<SquareBoard3, _>(Rules::default(), &settings, 2, &mut Random::new(1));
+    assert!(game.winner.is_some());
+    let boards = game.replay::<SquareBoard3>().unwrap();
+    assert_eq!(boards.last().unwrap().get_winner(), game.winner);
+}
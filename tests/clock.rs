@@ -0,0 +1,134 @@
+extern crate varidor;
+use varidor::clock::*;
+use varidor::protocol::*;
+use varidor::*;
+
+use std::time::Duration;
+
+const WHITE: TwoPlayerIndices = TwoPlayerIndices::White;
+const BLACK: TwoPlayerIndices = TwoPlayerIndices::Black;
+
+#[test]
+fn time_controls_are_read_and_written() {
+    let time_controls = vec![
+        ("300", TimeControl::sudden_death(300.)),
+        ("180+2", TimeControl::fischer(180., 2.)),
+        ("600b30", TimeControl::byoyomi(600., 30.)),
+        (
+            "60+0.5b10",
+            TimeControl {
+                main_time: 60.,
+                increment: 0.5,
+                byoyomi: 10.,
+            },
+        ),
+    ];
+    for (text, time_control) in time_controls {
+        assert_eq!(text.parse(), Ok(time_control));
+        assert_eq!(time_control.to_string(), text);
+    }
+    for text in &["", "five", "-1", "60+", "60b"] {
+        assert!(text.parse::<TimeControl>().is_err(), "{}", text);
+    }
+}
+
+#[test]
+fn increment_is_added_and_byoyomi_restarts_with_every_move() {
+    let mut clock = Clock::new(TimeControl::fischer(10., 2.));
+    assert!(!clock.tick(WHITE, 4.));
+    clock.press(WHITE);
+    assert_eq!(clock.get_main_time(WHITE), 8.);
+    assert_eq!(clock.get_main_time(BLACK), 10.);
+    assert!(clock.tick(BLACK, 10.));
+    assert_eq!(clock.get_flagged(), Some(BLACK));
+
+    let mut clock = Clock::new(TimeControl::byoyomi(5., 3.));
+    assert!(!clock.tick(WHITE, 7.));
+    assert!(clock.is_in_byoyomi(WHITE));
+    assert_eq!(clock.get_time_left(WHITE, WHITE), 1.);
+    clock.press(WHITE);
+    assert_eq!(clock.get_time_left(WHITE, BLACK), 3.);
+    assert!(!clock.tick(BLACK, 1.));
+    clock.press(BLACK);
+    assert!(!clock.tick(WHITE, 2.5));
+    clock.press(WHITE);
+    assert!(!clock.tick(BLACK, 1.));
+    clock.press(BLACK);
+    assert!(clock.tick(WHITE, 3.));
+    assert_eq!(clock.get_flagged(), Some(WHITE));
+}
+
+#[test]
+fn undo_takes_back_the_increment_of_the_ply() {
+    let mut game: Game<SquareBoard5> = Game::new();
+    game.set_time_control(TimeControl::fischer(10., 2.));
+    game.add_time(3.);
+    for _ in 0..3 {
+        game.play_move("step down".parse().unwrap()).unwrap();
+        assert_eq!(game.clock.as_ref().unwrap().get_main_time(WHITE), 9.);
+        game.undo().unwrap();
+        assert_eq!(game.clock.as_ref().unwrap().get_main_time(WHITE), 7.);
+    }
+    game.play_move("step down".parse().unwrap()).unwrap();
+    game.add_time(1.);
+    game.play_move("step up".parse().unwrap()).unwrap();
+    game.undo().unwrap();
+    let clock = game.clock.as_ref().unwrap();
+    assert_eq!((clock.get_main_time(WHITE), clock.get_main_time(BLACK)), (9., 9.));
+    assert_eq!(game.get_current_player(), BLACK);
+}
+
+#[test]
+fn loss_on_time_ends_the_game_and_is_recorded() {
+    let mut game: Game<SquareBoard5> = Game::new();
+    game.set_time_control(TimeControl::fischer(10., 1.));
+    game.add_time(3.);
+    game.play_move("step down".parse().unwrap()).unwrap();
+    game.add_time(10.);
+    assert_eq!(game.winner, Some(WHITE));
+    assert_eq!(game.get_time_forfeit(), Some(BLACK));
+    assert_eq!(
+        game.play_move("step up".parse().unwrap()),
+        Err(GameError::GameOver { winner: WHITE })
+    );
+    assert_eq!(game.undo(), Err(GameError::GameOver { winner: WHITE }));
+    let clock = game.clock.as_ref().unwrap();
+    assert_eq!(clock.get_main_time(WHITE), 8.);
+    assert_eq!(clock.get_main_time(BLACK), 0.);
+    assert!(game.get_logbook().get_entries().iter().any(|entry| {
+        entry.1 == LogEntry::TimeForfeit { player: BLACK }
+    }));
+    let record = game.to_record();
+    assert_eq!(record.winner, Some(WHITE));
+    assert_eq!(record.get_tag("TimeControl"), Some("10+1"));
+    assert_eq!(record.get_tag("Termination"), Some("time forfeit"));
+    let text = record.to_string();
+    assert_eq!(text.parse::<GameRecord>(), Ok(record));
+}
+
+#[test]
+fn clocks_are_sent_to_engines() {
+    let mut clock = Clock::new(TimeControl {
+        main_time: 60.,
+        increment: 2.,
+        byoyomi: 5.,
+    });
+    clock.tick(WHITE, 30.);
+    let limits = clock.get_go_limits(WHITE);
+    assert_eq!(limits.depth, None);
+    assert_eq!(limits.white_time, Some(Duration::from_secs(30)));
+    assert_eq!(limits.black_time, Some(Duration::from_secs(60)));
+    assert_eq!(limits.white_increment, Some(Duration::from_secs(2)));
+    assert_eq!(limits.black_increment, Some(Duration::from_secs(2)));
+    assert_eq!(limits.byoyomi, Some(Duration::from_secs(5)));
+    let limits = Clock::new(TimeControl::sudden_death(60.)).get_go_limits(BLACK);
+    assert_eq!(limits.white_increment, None);
+    assert_eq!(limits.byoyomi, None);
+    // times beyond a duration are sent as the longest time of the protocol
+    let long = "1e30+1e30".parse::<TimeControl>().unwrap();
+    let limits = Clock::new(long).get_go_limits(WHITE);
+    assert_eq!(limits.white_time, Some(Duration::from_millis(u64::MAX)));
+    assert_eq!(limits.white_increment, limits.white_time);
+    let command = Command::Go(limits);
+    assert_eq!(command.to_string().parse(), Ok(command));
+}
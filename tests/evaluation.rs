@@ -0,0 +1,105 @@
+extern crate varidor;
+use varidor::ai::*;
+use varidor::evaluation::*;
+use varidor::*;
+
+use std::time::Instant;
+
+// prefers the own pawn away from the middle column
+struct SideEvaluator;
+
+impl Evaluator<SquareBoard5> for SideEvaluator {
+    fn evaluate(&self, board: &SquareBoard5, player: TwoPlayerIndices) -> i32 {
+        let column = board.get_current_field(player).column as i32;
+        let opponent_column = board.get_current_field(player.next_player()).column as i32;
+        (column - 2).abs() - (opponent_column - 2).abs()
+    }
+}
+
+#[test]
+fn features_of_the_start_position_are_balanced() {
+    let board = SquareBoard5::new();
+    for &player in &[TwoPlayerIndices::White, TwoPlayerIndices::Black] {
+        assert_eq!(get_features(&board, player), [0., 0., 0., 0., 0., 1.]);
+    }
+    let mut board = SquareBoard5::new();
+    board
+        .play_move(TwoPlayerIndices::White, "step down".parse().unwrap())
+        .unwrap();
+    let features = get_features(&board, TwoPlayerIndices::Black);
+    assert_eq!(features[0], -1.);
+    assert_eq!(features[2], 0.);
+    let evaluator = LinearEvaluator::default();
+    assert!(evaluator.evaluate(&board, TwoPlayerIndices::Black) < 0);
+    assert!(evaluator.evaluate(&board, TwoPlayerIndices::White) > 0);
+}
+
+#[test]
+fn evaluators_are_read_back_from_their_text() {
+    let evaluator = LinearEvaluator {
+        weights: [1.5, -0.25, 0.125, 0., 2., -3.],
+    };
+    assert_eq!(evaluator.to_string().parse(), Ok(evaluator));
+    let partial = "walls=2 path_length=1".parse::<LinearEvaluator>().unwrap();
+    assert_eq!(partial.weights, [1., 0., 2., 0., 0., 0.]);
+    assert!("walls".parse::<LinearEvaluator>().is_err());
+    assert!("speed=1".parse::<LinearEvaluator>().is_err());
+    assert!("walls=many".parse::<LinearEvaluator>().is_err());
+}
+
+#[test]
+fn search_scores_with_the_given_evaluator() {
+    let board = SquareBoard5::new();
+    let player = TwoPlayerIndices::White;
+    let settings = SearchSettings {
+        depth: 1,
+        tablebase: None,
+        book: None,
+        evaluator: SideEvaluator,
+        deadline: None,
+    };
+    let result = search_with(&board, player, &settings);
+    assert_eq!(result.score, 1);
+    assert!(!result.stopped);
+    let sideways = ["step left".parse().unwrap(), "step right".parse().unwrap()];
+    assert!(sideways.contains(&result.best_move.unwrap()));
+    let default = search(&board, player, 1);
+    assert_eq!(default.best_move, Some("step down".parse().unwrap()));
+    let mut next_board = board.clone();
+    next_board.play_move(player, "step down".parse().unwrap()).unwrap();
+    let score = LinearEvaluator::default().evaluate(&next_board, player.next_player());
+    assert_eq!(default.score, -score);
+
+    // past the deadline only a search of one ply is finished
+    let late = SearchSettings {
+        depth: 3,
+        deadline: Some(Instant::now()),
+        ..SearchSettings::default()
+    };
+    assert!(search_with(&board, player, &late).stopped);
+    let one_ply = search_with(&board, player, &SearchSettings { depth: 1, ..late });
+    assert_eq!(one_ply, default);
+}
+
+#[test]
+fn tuning_reduces_the_prediction_error() {
+    let settings = SearchSettings {
+        depth: 1,
+        ..SearchSettings::default()
+    };
+    let mut random = Random::new(3);
+    let records = (0..20)
+        .map(|_| self_play::<SquareBoard5, _>(Rules::default(), &settings, 4, &mut random))
+        .collect::<Vec<_>>();
+    let samples = get_tuning_samples::<SquareBoard5>(&records).unwrap();
+    let plies = records.iter().map(|x| x.moves.len()).sum::<usize>();
+    assert_eq!(samples.len(), plies);
+    assert!(get_tuning_samples::<SquareBoard3>(&records).unwrap().is_empty());
+    let initial = LinearEvaluator { weights: [0.; 6] };
+    let differences = samples.iter().map(|x| (0.5 - x.result) * (0.5 - x.result));
+    let error = differences.sum::<f64>() / samples.len() as f64;
+    assert!((get_prediction_error(&initial, &samples) - error).abs() < 1e-12);
+    let result = Tuner::default().tune(initial, &samples);
+    assert!(result.error_after < result.error_before);
+    assert!(result.evaluator.weights[0] > 0.);
+}
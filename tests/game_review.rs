@@ -0,0 +1,59 @@
+extern crate varidor;
+use varidor::ai::*;
+use varidor::analysis::*;
+use varidor::game_review::*;
+use varidor::*;
+
+#[test]
+fn drops_of_the_chance_to_win_are_judged() {
+    assert_eq!(get_win_chance(0), 0.5);
+    assert!(get_win_chance(WIN_SCORE) > 0.999);
+    assert!(get_win_chance(-100) < 0.3);
+    assert_eq!(Judgement::from_drop(0.05), None);
+    assert_eq!(Judgement::from_drop(INACCURACY_DROP), Some(Judgement::Inaccuracy));
+    assert_eq!(Judgement::from_drop(0.25), Some(Judgement::Mistake));
+    assert_eq!(Judgement::from_drop(0.9), Some(Judgement::Blunder));
+    assert_eq!(Judgement::Blunder.to_string(), "blunder");
+}
+
+#[test]
+fn moves_of_the_search_are_not_judged() {
+    let settings = SearchSettings {
+        depth: 2,
+        ..SearchSettings::default()
+    };
+    let record = self_play::<SquareBoard3, _>(Rules::default(), &settings, 0, &mut Random::new(1));
+    let review = analyze_game::<SquareBoard3>(&record, 2).unwrap();
+    assert_eq!(review.moves.len(), record.moves.len());
+    assert!(review.moves.iter().all(|x| x.judgement.is_none() && x.get_drop() == 0.));
+    let white = review.get_summary(TwoPlayerIndices::White);
+    assert_eq!(white.moves, record.moves.len().div_ceil(2));
+    assert_eq!(white.accuracy, 100.);
+    assert!(review.record.annotations.is_empty());
+    assert_eq!(review.record.get_tag("BlackAccuracy"), Some("100.0"));
+}
+
+#[test]
+fn mistakes_are_annotated_with_the_better_line() {
+    let mut record = GameRecord::new(5, Rules::default());
+    record.moves = Move::parse_all("step down step up wall (0,2) horizontal step up").unwrap();
+    let review = analyze_game::<SquareBoard5>(&record, 2).unwrap();
+    let blunder = &review.moves[2];
+    assert_eq!(blunder.player, TwoPlayerIndices::White);
+    assert_eq!(blunder.judgement, Some(Judgement::Blunder));
+    assert!(blunder.get_drop() >= BLUNDER_DROP);
+    let best_move = blunder.get_best_move().unwrap();
+    assert_ne!(best_move, blunder.played);
+
+    let annotation = &review.record.annotations[&2];
+    let comment = format!("blunder, better {}", best_move);
+    assert_eq!(annotation.comment.as_deref(), Some(comment.as_str()));
+    assert_eq!(annotation.variations[0].moves, blunder.best_line);
+    let white = review.get_summary(TwoPlayerIndices::White);
+    assert_eq!((white.moves, white.blunders), (2, 1));
+    assert!(white.accuracy < review.get_summary(TwoPlayerIndices::Black).accuracy);
+    let text = review.record.to_string();
+    assert_eq!(text.parse::<GameRecord>(), Ok(review.record.clone()));
+    assert!(AnalysisBoard::<SquareBoard5>::from_record(&review.record).is_ok());
+    assert_eq!(review.record.moves, record.moves);
+}
@@ -0,0 +1,70 @@
+extern crate varidor;
+use varidor::ai::*;
+use varidor::tablebase::*;
+use varidor::*;
+
+use std::sync::Arc;
+
+const WHITE: TwoPlayerIndices = TwoPlayerIndices::White;
+const BLACK: TwoPlayerIndices = TwoPlayerIndices::Black;
+
+#[test]
+fn hints_are_the_move_of_the_search_until_the_next_ply() {
+    let mut game: Game<SquareBoard5> = Game::new();
+    let hint = game.request_hint(2).unwrap();
+    assert_eq!(Some(hint), search(&game.board, WHITE, 2).best_move);
+    assert_eq!(game.hint, Some(hint));
+    assert!(game.get_logbook().get_entries().iter().any(|entry| {
+        entry.1 == LogEntry::HintGiven {
+            player: WHITE,
+            hint,
+        }
+    }));
+    let text = game.get_logbook().to_text(Language::English);
+    assert!(text.ends_with("White: hint down"));
+    game.play_move(hint).unwrap();
+    assert_eq!(game.hint, None);
+    game.request_hint(2).unwrap();
+    game.undo().unwrap();
+    assert_eq!(game.hint, None);
+    assert_eq!(game.get_hint_count(WHITE), 1);
+    assert_eq!(game.get_hint_count(BLACK), 1);
+}
+
+#[test]
+fn hints_are_counted_and_commented_in_the_record() {
+    let mut game: Game<SquareBoard5> = Game::new();
+    game.play_move("step down".parse().unwrap()).unwrap();
+    let hint = game.request_hint(2).unwrap();
+    game.request_hint(2).unwrap();
+    game.play_move("step up".parse().unwrap()).unwrap();
+    game.request_hint(2).unwrap();
+    let record = game.to_record();
+    assert_eq!(record.get_tag("BlackHints"), Some("2"));
+    assert_eq!(record.get_tag("WhiteHints"), Some("1"));
+    let comment = format!("hint {}", hint);
+    assert_eq!(record.annotations[&1].comment.as_deref(), Some(comment.as_str()));
+    // the hint for the move still to be played is only counted
+    assert_eq!(record.annotations.len(), 1);
+    assert_eq!(record.to_string().parse::<GameRecord>(), Ok(record));
+
+    let mut won: Game<SquareBoard3> = Game::new();
+    for text in &["step down", "step up", "step down"] {
+        won.play_move(text.parse().unwrap()).unwrap();
+    }
+    assert!(won.is_game_over());
+    assert!(won.request_hint(2).is_err());
+    assert_eq!(won.hint, None);
+}
+
+#[test]
+fn hints_use_the_tablebase_of_the_game() {
+    let tablebase = Tablebase::generate::<SquareBoard3>(Rules::default()).unwrap();
+    let mut game: Game<SquareBoard3> = Game::new();
+    game.play_move("wall (0,1) horizontal".parse().unwrap()).unwrap();
+    let plain = game.request_hint(1).unwrap();
+    game.tablebase = Some(Arc::new(tablebase));
+    let hint = game.request_hint(1).unwrap();
+    assert_eq!(hint, "step up".parse().unwrap());
+    assert_ne!(hint, plain);
+}
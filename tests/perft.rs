@@ -0,0 +1,52 @@
+extern crate varidor;
+use varidor::perft::*;
+use varidor::*;
+
+// reference counts for the initial positions with the default rules, White to move
+const SQUARE_BOARD_3: [u64; 5] = [11, 77, 388, 1642, 6039];
+const SQUARE_BOARD_5: [u64; 3] = [35, 1109, 31540];
+const SQUARE_BOARD_9: [u64; 2] = [131, 16677];
+
+#[test]
+fn perft_square_board_3() {
+    let board = SquareBoard3::new();
+    for (depth, &count) in SQUARE_BOARD_3.iter().enumerate() {
+        assert_eq!(perft(&board, TwoPlayerIndices::White, depth + 1), count);
+    }
+}
+
+#[test]
+fn perft_square_board_5() {
+    let board = SquareBoard5::new();
+    for (depth, &count) in SQUARE_BOARD_5.iter().enumerate().take(2) {
+        assert_eq!(perft(&board, TwoPlayerIndices::White, depth + 1), count);
+    }
+}
+
+#[test]
+fn perft_square_board_9() {
+    let board = SquareBoard9::new();
+    assert_eq!(perft(&board, TwoPlayerIndices::White, 1), SQUARE_BOARD_9[0]);
+}
+
+// slow in debug builds, run with `cargo test --release -- --ignored`
+#[test]
+#[ignore]
+fn perft_deep() {
+    let board = SquareBoard5::new();
+    assert_eq!(perft(&board, TwoPlayerIndices::White, 3), SQUARE_BOARD_5[2]);
+    let board = SquareBoard9::new();
+    assert_eq!(perft(&board, TwoPlayerIndices::White, 2), SQUARE_BOARD_9[1]);
+}
+
+#[test]
+fn divide_adds_up_to_perft() {
+    let board = SquareBoard3::new();
+    let divided = perft_divide(&board, TwoPlayerIndices::White, 3);
+    assert_eq!(divided.len() as u64, SQUARE_BOARD_3[0]);
+    assert_eq!(
+        divided.iter().map(|&(_, count)| count).sum::<u64>(),
+        SQUARE_BOARD_3[2]
+    );
+    assert!(divided.contains(&(Move::Step(DirectionsSquare::Down), 88)));
+}
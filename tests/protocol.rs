@@ -0,0 +1,230 @@
+extern crate varidor;
+use varidor::protocol::*;
+use varidor::*;
+
+use std::time::{Duration, Instant};
+
+fn moves(text: &str) -> Vec<Move> {
+    Move::parse_all(text).unwrap()
+}
+
+#[test]
+fn commands_and_responses_are_read_back_from_their_text() {
+    let commands = vec![
+        Command::Varidor,
+        Command::IsReady,
+        Command::NewGame {
+            board_size: 9,
+            rules: Rules { wall_count: 10 },
+        },
+        Command::Position {
+            position: None,
+            moves: vec![],
+        },
+        Command::Position {
+            position: None,
+            moves: moves("step down wall (1,2) vertical step left"),
+        },
+        Command::Position {
+            position: Some(SquareBoard5::new().get_position(TwoPlayerIndices::White)),
+            moves: moves("step down"),
+        },
+        Command::Go(GoLimits::default()),
+        Command::Go(GoLimits {
+            depth: Some(4),
+            move_time: Some(Duration::from_millis(1500)),
+            white_time: Some(Duration::from_secs(60)),
+            black_time: Some(Duration::from_secs(30)),
+            ..GoLimits::default()
+        }),
+        Command::Go(GoLimits {
+            white_time: Some(Duration::from_secs(60)),
+            black_time: Some(Duration::from_secs(30)),
+            white_increment: Some(Duration::from_secs(2)),
+            black_increment: Some(Duration::from_millis(500)),
+            byoyomi: Some(Duration::from_secs(10)),
+            ..GoLimits::default()
+        }),
+        Command::Quit,
+    ];
+    for command in commands {
+        assert_eq!(command.to_string().parse(), Ok(command.clone()));
+    }
+    let responses = vec![
+        Response::Id {
+            key: "name".to_string(),
+            value: "some engine".to_string(),
+        },
+        Response::VaridorOk,
+        Response::ReadyOk,
+        Response::Info(SearchInfo {
+            depth: 2,
+            score: -35,
+            nodes: 1234,
+            principal_variation: moves("wall (0,3) horizontal step up"),
+        }),
+        Response::BestMove(Some("wall (0,3) horizontal".parse().unwrap())),
+        Response::BestMove(None),
+        Response::Error("cannot read 'x' as command".to_string()),
+    ];
+    for response in responses {
+        assert_eq!(response.to_string().parse(), Ok(response.clone()));
+    }
+    assert!("go depth".parse::<Command>().is_err());
+    assert!("go speed 3".parse::<Command>().is_err());
+    assert!("position startpos moves step sideways".parse::<Command>().is_err());
+    assert!("hello".parse::<Command>().is_err());
+}
+
+#[test]
+fn positions_are_read_back_and_set_up_on_boards() {
+    let mut board = SquareBoard5::new();
+    let mut player = TwoPlayerIndices::White;
+    for next_move in moves("step down wall (1,2) vertical step right wall (0,0) horizontal") {
+        board.play_move(player, next_move).unwrap();
+        player = player.next_player();
+    }
+    let position = board.get_position(player);
+    let text = position.to_string();
+    assert_eq!(
+        text,
+        "5 white (3,3) (2,0) 5 3 (0,0) horizontal (1,2) vertical"
+    );
+    assert_eq!(text.parse(), Ok(position.clone()));
+    // walls in another order are the same position
+    let reordered = "5 white (3,3) (2,0) 5 3 (1,2) vertical (0,0) horizontal";
+    assert_eq!(reordered.parse(), Ok(position.clone()));
+    let mut unsorted = position.clone();
+    unsorted.walls.reverse();
+    assert_eq!(unsorted.get_canonical_hash(), position.get_canonical_hash());
+    let copy = SquareBoard5::from_position(&position, Rules::default()).unwrap();
+    assert_eq!(copy.get_position(player), position);
+    assert_eq!(copy.get_path_length(player), board.get_path_length(player));
+    assert_eq!(copy.get_legal_moves(player), board.get_legal_moves(player));
+    assert!(SquareBoard3::from_position(&position, Rules::default()).is_none());
+    let mut overlapping = position.clone();
+    overlapping.walls.push("(1,1) vertical".parse().unwrap());
+    assert!(SquareBoard5::from_position(&overlapping, Rules::default()).is_none());
+    let mut outside = position.clone();
+    outside.fields[0].column = 5;
+    assert!(SquareBoard5::from_position(&outside, Rules::default()).is_none());
+    assert!("5 white (1,3) (2,0) 4".parse::<Position>().is_err());
+    assert!("5 red (1,3) (2,0) 4 3".parse::<Position>().is_err());
+    // fields and walls off the board are rejected, the mirror image needs them on it
+    assert!("5 white (9,0) (2,0) 4 3".parse::<Position>().is_err());
+    assert!("5 white (1,3) (2,5) 4 3".parse::<Position>().is_err());
+    assert!("5 white (1,3) (2,0) 4 3 (4,0) vertical".parse::<Position>().is_err());
+    assert!("0 white (0,0) (0,0) 0 0".parse::<Position>().is_err());
+}
+
+#[test]
+fn sessions_answer_the_commands() {
+    let input = "varidor\nisready\nnewgame 3 5\nposition startpos moves step down\n\
+                 go depth 2\nfly\nnewgame 6 5\ngo\nquit\nisready\n";
+    let mut output = vec![];
+    EngineSession::new("test")
+        .run(input.as_bytes(), &mut output)
+        .unwrap();
+    let responses = String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .map(|line| line.parse::<Response>().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        responses[0],
+        Response::Id {
+            key: "name".to_string(),
+            value: "test".to_string(),
+        }
+    );
+    assert_eq!(responses[2..4], [Response::VaridorOk, Response::ReadyOk]);
+    let infos = responses
+        .iter()
+        .filter_map(|response| match *response {
+            Response::Info(ref info) => Some(info.clone()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(infos.iter().map(|x| x.depth).collect::<Vec<_>>(), vec![1, 2]);
+    let best_move = infos[1].principal_variation[0];
+    assert!(responses.contains(&Response::BestMove(Some(best_move))));
+    let errors = responses
+        .iter()
+        .filter(|response| matches!(**response, Response::Error(_)))
+        .count();
+    assert_eq!(errors, 2);
+    // nothing is answered after quit
+    assert_eq!(responses.last(), Some(&Response::Error("unsupported board size 6".to_string())));
+}
+
+#[test]
+fn searches_stop_at_the_move_time() {
+    let input = "newgame 9 10\nposition startpos\ngo movetime 200\n";
+    let mut output = vec![];
+    let start = Instant::now();
+    EngineSession::new("test")
+        .run(input.as_bytes(), &mut output)
+        .unwrap();
+    assert!(start.elapsed() < Duration::from_millis(1000));
+    let responses = String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .map(|line| line.parse::<Response>().unwrap())
+        .collect::<Vec<_>>();
+    let depths = responses
+        .iter()
+        .filter_map(|response| match *response {
+            Response::Info(ref info) => Some(info.depth),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(depths[0], 1);
+    let best_move = responses.last().unwrap();
+    assert!(matches!(*best_move, Response::BestMove(Some(_))));
+
+    // a move time beyond the clock of the system has no deadline
+    let input = "newgame 3 5\nposition startpos\ngo movetime 18446744073709551615\n";
+    let mut output = vec![];
+    EngineSession::new("test")
+        .run(input.as_bytes(), &mut output)
+        .unwrap();
+    assert!(String::from_utf8(output).unwrap().contains("bestmove step down"));
+}
+
+#[test]
+fn games_are_played_against_engine_processes() {
+    let mut engine = EngineProcess::spawn(env!("CARGO_BIN_EXE_varidor-engine"), &[]).unwrap();
+    assert_eq!(engine.get_name(), Some("varidor"));
+    engine.wait_until_ready().unwrap();
+    let limits = GoLimits {
+        depth: Some(1),
+        ..GoLimits::default()
+    };
+    let mut game: Game<SquareBoard3> = Game::new();
+    game.play_move("step down".parse().unwrap()).unwrap();
+    while !game.is_game_over() && game.to_record().moves.len() < 100 {
+        let next_move = game.play_engine_move(&mut engine, limits).unwrap();
+        assert_eq!(game.to_record().moves.last(), Some(&next_move));
+    }
+    assert!(game.is_game_over());
+    match game.play_engine_move(&mut engine, limits) {
+        Err(EngineError::Game(GameError::GameOver { .. })) => {}
+        result => panic!("unexpected {:?}", result),
+    }
+    // the engine plays on the position the game started from
+    let position = "3 black (2,2) (0,1) 0 0".parse::<Position>().unwrap();
+    let mut game = Game::<SquareBoard3>::with_position(&position, Rules::default()).unwrap();
+    let next_move = game.play_engine_move(&mut engine, limits).unwrap();
+    assert_eq!(next_move, "step up".parse().unwrap());
+    assert_eq!(game.winner, Some(TwoPlayerIndices::Black));
+    engine
+        .send(&Command::NewGame {
+            board_size: 7,
+            rules: Rules::default(),
+        })
+        .unwrap();
+    match engine.go(limits) {
+        Err(EngineError::Engine(message)) => assert_eq!(message, "unsupported board size 7"),
+        result => panic!("unexpected {:?}", result),
+    }
+}
@@ -0,0 +1,188 @@
+extern crate varidor;
+use varidor::ai::*;
+use varidor::puzzle::*;
+use varidor::*;
+
+const WIN_IN_2: &str = "[Position \"5 white (2,2) (2,0) 3 1 (0,0) horizontal (0,1) horizontal \
+                        (0,2) horizontal (0,3) horizontal (2,1) vertical (3,0) horizontal\"]\n\
+                        [Walls \"3\"]\n[Title \"White to move, win in 2\"]\n\
+                        step down\nstep up\nstep down\n";
+
+// a race without walls which every step of White wins
+fn race(solution: &str) -> Puzzle {
+    format!("[Position \"5 white (1,1) (3,1) 0 0\"]\n{}", solution)
+        .parse()
+        .unwrap()
+}
+
+#[test]
+fn puzzles_are_read_back_from_their_text() {
+    let puzzle = WIN_IN_2.parse::<Puzzle>().unwrap();
+    assert_eq!(puzzle.get_solver(), TwoPlayerIndices::White);
+    assert_eq!(puzzle.get_move_count(), 2);
+    assert_eq!(puzzle.get_tag("Title"), Some("White to move, win in 2"));
+    assert_eq!(puzzle.rules, Rules { wall_count: 3 });
+    assert_eq!(puzzle.position.walls.len(), 6);
+    assert_eq!(puzzle.to_string(), WIN_IN_2.replace(" \\\n", " "));
+    let race = race("step down | step left\n");
+    assert!(race.is_accepted(0, "step left".parse().unwrap()));
+    assert!(!race.is_accepted(0, "step up".parse().unwrap()));
+    assert_eq!(race.rules, Rules::default());
+    assert_eq!(race.to_string().parse::<Puzzle>(), Ok(race.clone()));
+    assert_eq!(Puzzle::parse_all(&format!("{}\n{}", WIN_IN_2, race)).unwrap().len(), 2);
+
+    let position = "[Position \"5 white (1,1) (3,1) 0 0\"]\n";
+    for solution in &["step down\nstep up\n", "step down\nstep up | step left\nstep down\n"] {
+        assert!(format!("{}{}", position, solution).parse::<Puzzle>().is_err());
+    }
+    assert!("step down\n".parse::<Puzzle>().is_err());
+}
+
+#[test]
+fn solutions_are_verified_to_be_unique() {
+    let puzzle = WIN_IN_2.parse::<Puzzle>().unwrap();
+    assert_eq!(puzzle.verify::<SquareBoard5>(), Ok(()));
+    assert_eq!(puzzle.verify::<SquareBoard3>(), Err(PuzzleError::Position));
+    let moves = |text: &str| Move::parse_all(text).unwrap();
+    assert_eq!(
+        race("step down | step left\n").verify::<SquareBoard5>(),
+        Err(PuzzleError::NotUnique {
+            ply: 1,
+            moves: moves("step up step right"),
+        })
+    );
+    let all = race("step down | step up | step left | step right\n");
+    assert_eq!(all.verify::<SquareBoard5>(), Ok(()));
+
+    let mut wrong = puzzle.clone();
+    wrong.solution[0] = "step left".parse().unwrap();
+    assert!(matches!(
+        wrong.verify::<SquareBoard5>(),
+        Err(PuzzleError::IllegalMove { ply: 3, .. })
+    ));
+    let mut too_short = puzzle.clone();
+    too_short.solution.truncate(1);
+    assert_eq!(too_short.verify::<SquareBoard5>(), Err(PuzzleError::NotWon));
+    let slow = race("step left\nstep up\nstep down\n");
+    assert_eq!(
+        slow.verify::<SquareBoard5>(),
+        Err(PuzzleError::NotUnique {
+            ply: 1,
+            moves: moves("step up step down step right"),
+        })
+    );
+    // Black wins by stepping up instead of the reply
+    let not_forced = "[Position \"5 white (2,1) (2,3) 1 0\"]\n\
+                      wall (0,0) vertical\nstep left\nstep down\n";
+    assert_eq!(
+        not_forced.parse::<Puzzle>().unwrap().verify::<SquareBoard5>(),
+        Err(PuzzleError::NotForced {
+            ply: 1,
+            next_move: "wall (0,0) vertical".parse().unwrap(),
+        })
+    );
+}
+
+#[test]
+fn puzzle_games_reply_with_the_solution() {
+    let puzzle = WIN_IN_2.parse::<Puzzle>().unwrap();
+    let mut solved = PuzzleGame::<SquareBoard5>::new(puzzle.clone()).unwrap();
+    assert_eq!(solved.game.get_current_player(), TwoPlayerIndices::White);
+    assert_eq!(solved.game.board.get_position(TwoPlayerIndices::White), puzzle.position);
+    // illegal moves are rejected without failing the puzzle
+    assert!(solved.play_move("step right".parse().unwrap()).is_err());
+    let state = solved.play_move("step down".parse().unwrap()).unwrap();
+    assert_eq!(state, PuzzleState::Solving);
+    assert_eq!(solved.get_ply(), 2);
+    assert_eq!(solved.game.get_current_player(), TwoPlayerIndices::White);
+    let state = solved.play_move("step down".parse().unwrap()).unwrap();
+    assert_eq!(state, PuzzleState::Solved);
+    assert_eq!(solved.game.winner, Some(TwoPlayerIndices::White));
+    let record = solved.game.to_record();
+    assert_eq!(record.start_position, Some(puzzle.position.clone()));
+    assert_eq!(record.rules, puzzle.rules);
+    assert_eq!(record.moves.len(), 3);
+
+    let mut failed = PuzzleGame::<SquareBoard5>::new(puzzle).unwrap();
+    let state = failed.play_move("wall (3,3) vertical".parse().unwrap()).unwrap();
+    assert_eq!(state, PuzzleState::Failed);
+    assert_eq!(failed.play_move("step down".parse().unwrap()), Ok(PuzzleState::Failed));
+
+    // the reply of an unsound puzzle after the alternative ends it
+    let unsound = race("step left | step down\nstep up\nstep down\n");
+    let mut unsound = PuzzleGame::<SquareBoard5>::new(unsound).unwrap();
+    let winner = TwoPlayerIndices::White;
+    let result = unsound.play_move("step down".parse().unwrap());
+    assert_eq!(result, Err(GameError::GameOver { winner }));
+    assert_eq!(unsound.get_state(), PuzzleState::Failed);
+    assert_eq!(unsound.get_ply(), unsound.game.to_record().moves.len());
+
+    let all = race("step down | step up | step left | step right\n");
+    let mut alternative = PuzzleGame::<SquareBoard5>::new(all).unwrap();
+    let state = alternative.play_move("step left".parse().unwrap());
+    assert_eq!(state, Ok(PuzzleState::Solved));
+}
+
+#[test]
+fn puzzles_are_found_in_positions_with_a_single_winning_move() {
+    let expected = WIN_IN_2.parse::<Puzzle>().unwrap();
+    let board = expected.get_board::<SquareBoard5>().unwrap();
+    let puzzle = find_puzzle(&board, TwoPlayerIndices::White, 2).unwrap();
+    assert_eq!(puzzle.position, expected.position);
+    assert_eq!(puzzle.rules, expected.rules);
+    assert_eq!(puzzle.solution[0], expected.solution[0]);
+    assert_eq!(puzzle.get_move_count(), 2);
+    assert_eq!(puzzle.get_tag("Title"), expected.get_tag("Title"));
+    assert_eq!(puzzle.verify::<SquareBoard5>(), Ok(()));
+    assert_eq!(find_puzzle(&board, TwoPlayerIndices::White, 1), None);
+    // every step wins the race
+    let race = race("step down\n").get_board::<SquareBoard5>().unwrap();
+    assert_eq!(find_puzzle(&race, TwoPlayerIndices::White, 2), None);
+
+    let mut filter = PuzzleFilter::default();
+    assert!(filter.matches(&puzzle));
+    filter.min_walls = 1;
+    assert!(!filter.matches(&puzzle));
+}
+
+#[test]
+fn generated_puzzles_are_sound_and_distinct_up_to_mirroring() {
+    let settings = SearchSettings {
+        depth: 2,
+        ..SearchSettings::default()
+    };
+    let rules = Rules { wall_count: 3 };
+    let mut random = Random::new(5);
+    let mut generator = PuzzleGenerator::new(PuzzleFilter::default());
+    let mut records = vec![];
+    for _ in 0..4 {
+        let record = self_play::<SquareBoard3, _>(rules, &settings, 2, &mut random);
+        generator.add_record::<SquareBoard3>(&record).unwrap();
+        records.push(record);
+    }
+    assert!(!generator.puzzles.is_empty());
+    let mut hashes = vec![];
+    for puzzle in &generator.puzzles {
+        assert_eq!(puzzle.verify::<SquareBoard3>(), Ok(()));
+        assert!(puzzle.get_move_count() <= 2);
+        assert_eq!(puzzle.rules, rules);
+        hashes.push(puzzle.position.get_canonical_hash());
+    }
+    hashes.sort();
+    hashes.dedup();
+    assert_eq!(hashes.len(), generator.puzzles.len());
+
+    // the same games and their mirror images add nothing
+    for record in &records {
+        let mut mirrored = record.clone();
+        mirrored.moves = record.moves.iter().map(|x| x.mirrored(3)).collect();
+        assert_eq!(generator.add_record::<SquareBoard3>(record), Ok(0));
+        assert_eq!(generator.add_record::<SquareBoard3>(&mirrored), Ok(0));
+    }
+    let mut known = PuzzleGenerator::new(PuzzleFilter::default());
+    for puzzle in &generator.puzzles {
+        known.add_known(puzzle);
+    }
+    known.add_record::<SquareBoard3>(&records[0]).unwrap();
+    assert!(known.puzzles.is_empty());
+}
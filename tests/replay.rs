@@ -0,0 +1,97 @@
+extern crate varidor;
+use varidor::analysis::*;
+use varidor::game_review::*;
+use varidor::replay::*;
+use varidor::*;
+
+fn game() -> Game<SquareBoard5> {
+    let mut game: Game<SquareBoard5> = Game::new();
+    for text in &["step down", "wall (0,2) horizontal", "step left", "step up"] {
+        game.play_move(text.parse().unwrap()).unwrap();
+    }
+    game
+}
+
+#[test]
+fn replays_show_the_position_after_any_ply() {
+    let game = game();
+    let mut replay = Replay::<SquareBoard5>::new(game.to_record()).unwrap();
+    assert_eq!(replay.get_ply_count(), 4);
+    assert_eq!(replay.get_ply(), 0);
+    assert_eq!(replay.get_last_move(), None);
+    assert_eq!(
+        replay.get_board().get_position(TwoPlayerIndices::White),
+        SquareBoard5::new().get_position(TwoPlayerIndices::White)
+    );
+    assert!(!replay.step_backward());
+    assert!(replay.step_forward());
+    assert_eq!(replay.get_last_move(), Some("step down".parse().unwrap()));
+    assert_eq!(replay.get_current_player(), TwoPlayerIndices::Black);
+    assert_eq!(
+        replay.get_board().get_current_field(TwoPlayerIndices::White),
+        FieldIndexSquare { column: 2, row: 3 }
+    );
+    replay.goto_ply(2);
+    let black = replay.get_board().get_player_data(TwoPlayerIndices::Black);
+    assert_eq!(black.get_wall_count(), 4);
+    assert_eq!(replay.get_current_player(), TwoPlayerIndices::White);
+    replay.goto_ply(100);
+    assert_eq!(replay.get_ply(), 4);
+    assert!(!replay.step_forward());
+    assert_eq!(replay.get_last_move(), Some("step up".parse().unwrap()));
+    assert_eq!(
+        replay.get_board().get_position(TwoPlayerIndices::White),
+        game.board.get_position(TwoPlayerIndices::White)
+    );
+    assert!(replay.step_backward());
+    assert_eq!(replay.get_ply(), 3);
+}
+
+#[test]
+fn replays_do_not_change_the_game() {
+    let mut game = game();
+    let record = game.to_record();
+    let mut replay = Replay::<SquareBoard5>::new(record.clone()).unwrap();
+    replay.goto_ply(1);
+    game.play_move("step right".parse().unwrap()).unwrap();
+    assert_eq!(replay.get_record(), &record);
+    assert_eq!(replay.get_ply_count(), 4);
+    assert_eq!(game.to_record().moves.len(), 5);
+
+    let mut illegal = record.clone();
+    illegal.moves.insert(0, "step up".parse().unwrap());
+    assert!(Replay::<SquareBoard5>::new(illegal).is_err());
+    assert!(Replay::<SquareBoard3>::new(record).is_err());
+}
+
+#[test]
+fn games_from_a_position_are_replayed_from_it() {
+    let position = "5 black (1,3) (3,1) 3 2".parse::<Position>().unwrap();
+    let mut game = Game::<SquareBoard5>::with_position(&position, Rules::default()).unwrap();
+    for text in &["step up", "wall (0,0) horizontal"] {
+        game.play_move(text.parse().unwrap()).unwrap();
+    }
+    let record = game.to_record();
+    assert!(record.to_string().contains("[Position \"5 black (1,3) (3,1) 3 2\"]\n"));
+    assert_eq!(record.to_string().parse::<GameRecord>(), Ok(record.clone()));
+    assert_eq!(record.get_move_player(0), TwoPlayerIndices::Black);
+    assert_eq!(record.get_move_player(1), TwoPlayerIndices::White);
+
+    let mut replay = Replay::<SquareBoard5>::new(record.clone()).unwrap();
+    assert_eq!(replay.get_board().get_position(TwoPlayerIndices::Black), position);
+    assert_eq!(replay.get_current_player(), TwoPlayerIndices::Black);
+    replay.goto_ply(2);
+    assert_eq!(
+        replay.get_board().get_position(TwoPlayerIndices::Black),
+        game.board.get_position(TwoPlayerIndices::Black)
+    );
+    let review = analyze_game::<SquareBoard5>(&record, 2).unwrap();
+    assert_eq!(review.moves[0].player, TwoPlayerIndices::Black);
+    let analysis = AnalysisBoard::<SquareBoard5>::from_record(&record).unwrap();
+    assert_eq!(analysis.get_current_player(), TwoPlayerIndices::Black);
+    assert_eq!(analysis.to_record(), record);
+
+    let mut other_size = record;
+    other_size.start_position = Some("3 black (1,0) (1,2) 1 1".parse().unwrap());
+    assert_eq!(other_size.replay::<SquareBoard5>().err(), Some(RecordError::Position));
+}
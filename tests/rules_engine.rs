@@ -0,0 +1,785 @@
+extern crate varidor;
+use varidor::*;
+
+use std::error::Error;
+
+// small xorshift generator, so that failing games can be replayed from their seed
+struct Random(u64);
+impl Random {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+}
+
+type Path = Vec<(FieldIndexSquare<usize>, DirectionsSquare)>;
+
+#[derive(PartialEq, Debug)]
+struct Snapshot {
+    fields: Vec<FieldIndexSquare<usize>>,
+    wall_counts: Vec<usize>,
+    shortest_paths: Vec<Vec<Path>>,
+    walls: Vec<bool>,
+    crossings: Vec<bool>,
+}
+
+const PLAYERS: [TwoPlayerIndices; 2] = [TwoPlayerIndices::White, TwoPlayerIndices::Black];
+
+fn snapshot<S, W, C>(board: &SquareBoard<usize, S, W, C>) -> Snapshot
+where
+    S: IntegerTrait<usize>,
+    W: WallPositionTrait<usize>,
+    C: WallCrosingTrait<usize>,
+{
+    let size = S::SIZE;
+    let mut walls = Vec::new();
+    for column in 0..size - 1 {
+        for row in 0..size {
+            walls.push(
+                board.wall_lookup_unsafe(FieldIndexSquare { column, row }, true)
+                    == WallPlaced::IsWall,
+            );
+        }
+    }
+    for column in 0..size {
+        for row in 0..size - 1 {
+            walls.push(
+                board.wall_lookup_unsafe(FieldIndexSquare { column, row }, false)
+                    == WallPlaced::IsWall,
+            );
+        }
+    }
+    let mut crossings = Vec::new();
+    for column in 0..size - 1 {
+        for row in 0..size - 1 {
+            crossings.push(
+                board.croosing_lookup_unsafe(FieldIndexSquare { column, row })
+                    == WallCrossing::IsWallCrossing,
+            );
+        }
+    }
+    Snapshot {
+        fields: PLAYERS
+            .iter()
+            .map(|&p| board.get_current_field(p))
+            .collect(),
+        wall_counts: PLAYERS
+            .iter()
+            .map(|&p| board.get_player_data(p).get_wall_count())
+            .collect(),
+        shortest_paths: PLAYERS
+            .iter()
+            .map(|&p| sorted_paths(board.get_player_data(p).get_shortest_paths()))
+            .collect(),
+        walls,
+        crossings,
+    }
+}
+
+// the paths of the graph, independent of the order in which they were discovered
+fn sorted_paths(
+    shortest_paths: &ShortestPathDag<FieldIndexSquare<usize>, DirectionsSquare>,
+) -> Vec<Path> {
+    let mut paths = shortest_paths.iter_paths().collect::<Vec<_>>();
+    paths.sort_by_key(|path| {
+        path.iter()
+            .map(|&(field, direction)| (field.column, field.row, direction as usize))
+            .collect::<Vec<_>>()
+    });
+    paths
+}
+
+// distance to the goal of every field by relaxation, independent of the graph construction
+fn distances_to_goal<S, W, C>(
+    board: &SquareBoard<usize, S, W, C>,
+    player: TwoPlayerIndices,
+) -> Vec<Option<usize>>
+where
+    S: IntegerTrait<usize>,
+    W: WallPositionTrait<usize>,
+    C: WallCrosingTrait<usize>,
+{
+    let size = S::SIZE;
+    // indexed by column * size + row
+    let fields = (0..size * size)
+        .map(|index| FieldIndexSquare {
+            column: index / size,
+            row: index % size,
+        })
+        .collect::<Vec<_>>();
+    let mut distances = fields
+        .iter()
+        .map(|&field| {
+            if SquareBoard::<usize, S, W, C>::is_final_field(field, player) {
+                Some(0)
+            } else {
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for (index, &field) in fields.iter().enumerate() {
+            for (next_field, _) in board.get_surrounding_possible_fields(field) {
+                if let Some(distance) = distances[next_field.column * size + next_field.row] {
+                    let shorter = match distances[index] {
+                        Some(current) => current > distance + 1,
+                        None => true,
+                    };
+                    if shorter {
+                        distances[index] = Some(distance + 1);
+                        changed = true;
+                    }
+                }
+            }
+        }
+    }
+    distances
+}
+
+fn check_invariants<S, W, C>(board: &SquareBoard<usize, S, W, C>, walls_placed: usize)
+where
+    S: IntegerTrait<usize>,
+    W: WallPositionTrait<usize>,
+    C: WallCrosingTrait<usize>,
+{
+    let size = S::SIZE;
+    let rules = board.get_rules();
+    for &player in PLAYERS.iter() {
+        assert!(board.get_player_data(player).get_wall_count() <= rules.wall_count);
+        let shortest_paths = board.compute_shortest_paths(player);
+        assert!(
+            shortest_paths.is_reachable(),
+            "{} has no path to the goal",
+            player
+        );
+        let paths = sorted_paths(&shortest_paths);
+        assert_eq!(
+            paths,
+            sorted_paths(board.get_player_data(player).get_shortest_paths())
+        );
+        let start = board.get_current_field(player);
+        let distances = distances_to_goal(board, player);
+        let distance_to_goal =
+            |field: FieldIndexSquare<usize>| distances[field.column * size + field.row];
+        let length = distance_to_goal(start).unwrap();
+        assert_eq!(shortest_paths.get_length(), Some(length));
+        assert_eq!(shortest_paths.get_path_count(), paths.len() as u64);
+        for path in &paths {
+            assert_eq!(path.len(), length);
+            let mut field = start;
+            for &(next_field, direction) in path {
+                assert_eq!(
+                    SquareBoard::<usize, S, W, C>::get_field_in_direction(field, direction),
+                    Some((next_field, direction))
+                );
+                assert_eq!(
+                    board.check_for_wall(field, direction),
+                    Some(WallPlaced::IsEmpty)
+                );
+                assert!(shortest_paths.contains_step(field, direction));
+                field = next_field;
+            }
+            assert!(SquareBoard::<usize, S, W, C>::is_final_field(field, player));
+        }
+        // a field is on a shortest path exactly if it is on the way at the right distance
+        for column in 0..size {
+            for row in 0..size {
+                let field = FieldIndexSquare { column, row };
+                let on_some_path = field == start
+                    || paths.iter().any(|path| path.iter().any(|&(x, _)| x == field));
+                assert_eq!(shortest_paths.contains_field(field), on_some_path);
+            }
+        }
+        assert_eq!(
+            shortest_paths.get_steps().len(),
+            (0..length)
+                .map(|distance| {
+                    shortest_paths
+                        .get_fields_at_distance(distance)
+                        .iter()
+                        .map(|&field| {
+                            board
+                                .get_surrounding_possible_fields(field)
+                                .iter()
+                                .filter(|&&(next_field, _)| {
+                                    distance_to_goal(next_field).unwrap() + 1
+                                        == distance_to_goal(field).unwrap()
+                                })
+                                .count()
+                        })
+                        .sum::<usize>()
+                })
+                .sum::<usize>()
+        );
+    }
+    // a slot is free exactly if neither its segments nor its crossing are taken
+    for slot in WallSlot::all(size) {
+        let taken = slot.get_segments().iter().any(|&(field, is_left_or_right)| {
+            board.wall_lookup_unsafe(field, is_left_or_right) == WallPlaced::IsWall
+        }) || board.croosing_lookup_unsafe(slot.anchor) == WallCrossing::IsWallCrossing;
+        assert_eq!(board.is_wall_slot_free(slot), !taken, "{}", slot);
+    }
+    let snapshot = snapshot(board);
+    let used_walls: usize = snapshot
+        .wall_counts
+        .iter()
+        .map(|&count| rules.wall_count - count)
+        .sum();
+    assert_eq!(used_walls, walls_placed);
+    assert_eq!(
+        snapshot.walls.iter().filter(|&&x| x).count(),
+        2 * walls_placed
+    );
+    assert_eq!(
+        snapshot.crossings.iter().filter(|&&x| x).count(),
+        walls_placed
+    );
+    // every occupied crossing is the center of exactly one wall
+    for column in 0..size - 1 {
+        for row in 0..size - 1 {
+            let lookup = |column, row, is_left_or_right| {
+                board.wall_lookup_unsafe(FieldIndexSquare { column, row }, is_left_or_right)
+                    == WallPlaced::IsWall
+            };
+            let vertical = lookup(column, row, true) && lookup(column, row + 1, true);
+            let horizontal = lookup(column, row, false) && lookup(column + 1, row, false);
+            let crossing = board.croosing_lookup_unsafe(FieldIndexSquare { column, row })
+                == WallCrossing::IsWallCrossing;
+            if crossing {
+                assert!(vertical || horizontal);
+            }
+        }
+    }
+}
+
+fn random_move(random: &mut Random, size: usize) -> Move {
+    let directions = [
+        DirectionsSquare::Up,
+        DirectionsSquare::Down,
+        DirectionsSquare::Left,
+        DirectionsSquare::Right,
+    ];
+    let direction = directions[random.below(4)];
+    if random.below(2) == 0 {
+        Move::Step(direction)
+    } else {
+        // anchors in the last row or column are off the board
+        Move::Wall(WallSlot {
+            anchor: FieldIndexSquare {
+                column: random.below(size),
+                row: random.below(size),
+            },
+            orientation: if random.below(2) == 0 {
+                WallOrientation::Horizontal
+            } else {
+                WallOrientation::Vertical
+            },
+        })
+    }
+}
+
+fn play_random_games<S, W, C>(seed: u64, games: usize, new: fn() -> SquareBoard<usize, S, W, C>)
+where
+    S: IntegerTrait<usize>,
+    W: WallPositionTrait<usize> + Clone,
+    C: WallCrosingTrait<usize> + Clone,
+{
+    let mut random = Random(seed);
+    for _ in 0..games {
+        let mut board = new();
+        let mut player = TwoPlayerIndices::White;
+        let mut walls_placed = 0;
+        check_invariants(&board, walls_placed);
+        for _ in 0..200 {
+            if board.get_winner().is_some() {
+                break;
+            }
+            let next_move = random_move(&mut random, S::SIZE);
+            let before = snapshot(&board);
+            let wall_count = board.get_player_data(player).get_wall_count();
+            // make_move agrees with play_move and unmake_move restores the position
+            let mut in_place = board.clone();
+            let made = in_place.make_move(player, next_move);
+            if let Ok(token) = made.clone() {
+                in_place.unmake_move(token);
+            }
+            assert_eq!(before, snapshot(&in_place));
+            match board.play_move(player, next_move) {
+                Ok(()) => {
+                    assert!(made.is_ok());
+                    if let Move::Wall(_) = next_move {
+                        walls_placed += 1;
+                        assert_eq!(
+                            board.get_player_data(player).get_wall_count() + 1,
+                            wall_count
+                        );
+                    }
+                    player = player.next_player();
+                }
+                Err(error) => {
+                    assert_eq!(made.err(), Some(error));
+                    // rejected moves, including walls taken back after blocking a player,
+                    // leave the board untouched
+                    assert_eq!(before, snapshot(&board), "{}", error);
+                    if wall_count == 0 {
+                        if let Move::Wall(_) = next_move {
+                            assert_eq!(
+                                error,
+                                GameError::WallPlacment(WallPlacmentError::NoMoreWalls { player })
+                            );
+                        }
+                    }
+                }
+            }
+            check_invariants(&board, walls_placed);
+        }
+    }
+}
+
+#[test]
+fn random_games_keep_invariants_on_square_board_3() {
+    play_random_games(0x1234_5678, 200, SquareBoard3::new);
+}
+#[test]
+fn random_games_keep_invariants_on_square_board_4() {
+    play_random_games(0x8765_4321, 100, SquareBoard4::new);
+}
+#[test]
+fn random_games_keep_invariants_on_square_board_5() {
+    play_random_games(0x0bad_cafe, 50, SquareBoard5::new);
+}
+
+fn field(column: usize, row: usize) -> FieldIndexSquare<usize> {
+    FieldIndexSquare { column, row }
+}
+fn wall_error(error: WallPlacmentError) -> GameError {
+    GameError::WallPlacment(error)
+}
+
+#[test]
+fn wall_leaving_the_board_is_rejected() {
+    let mut board = SquareBoard3::new();
+    assert_eq!(
+        board.place_wall(
+            TwoPlayerIndices::White,
+            field(0, 0),
+            DirectionsSquare::Left,
+            WallDirections::Left
+        ),
+        Err(WallPlacmentError::BoardBoundary {
+            field: field(0, 0),
+            direction: DirectionsSquare::Left,
+        })
+    );
+    assert_eq!(
+        board.place_wall(
+            TwoPlayerIndices::White,
+            field(2, 1),
+            DirectionsSquare::Up,
+            WallDirections::Right
+        ),
+        Err(WallPlacmentError::BoardBoundary {
+            field: field(2, 1),
+            direction: DirectionsSquare::Up,
+        })
+    );
+}
+
+#[test]
+fn wall_on_top_of_wall_is_rejected() {
+    let mut board = SquareBoard3::new();
+    let player = TwoPlayerIndices::White;
+    assert_eq!(
+        board.place_wall(
+            player,
+            field(0, 0),
+            DirectionsSquare::Up,
+            WallDirections::Right
+        ),
+        Ok(())
+    );
+    assert_eq!(
+        board.place_wall(
+            player,
+            field(0, 0),
+            DirectionsSquare::Up,
+            WallDirections::Right
+        ),
+        Err(WallPlacmentError::WallAlreadyPlaced { field: field(0, 0) })
+    );
+    assert_eq!(
+        board.place_wall(
+            player,
+            field(1, 0),
+            DirectionsSquare::Up,
+            WallDirections::Right
+        ),
+        Err(WallPlacmentError::WallAlreadyPlaced { field: field(1, 0) })
+    );
+}
+
+#[test]
+fn crossing_walls_are_rejected() {
+    let mut board = SquareBoard3::new();
+    let player = TwoPlayerIndices::White;
+    assert_eq!(
+        board.place_wall(
+            player,
+            field(0, 0),
+            DirectionsSquare::Up,
+            WallDirections::Right
+        ),
+        Ok(())
+    );
+    assert_eq!(
+        board.place_wall(
+            player,
+            field(0, 0),
+            DirectionsSquare::Right,
+            WallDirections::Left
+        ),
+        Err(WallPlacmentError::WallsAlreadyCrossing { field: field(0, 0) })
+    );
+}
+
+#[test]
+fn every_wall_placement_has_one_slot_on_the_board() {
+    let directions = [
+        DirectionsSquare::Up,
+        DirectionsSquare::Down,
+        DirectionsSquare::Left,
+        DirectionsSquare::Right,
+    ];
+    let slots = SquareBoard4::get_wall_slots();
+    assert_eq!(slots.len(), 2 * 3 * 3);
+    assert_eq!(slots, WallSlot::all(4).collect::<Vec<_>>());
+    let mut placements_per_slot = vec![0; slots.len()];
+    for column in 0..4 {
+        for row in 0..4 {
+            for &direction in &directions {
+                for &wall_direction in &[WallDirections::Left, WallDirections::Right] {
+                    let first_field = field(column, row);
+                    match SquareBoard4::get_wall_slot(first_field, direction, wall_direction) {
+                        Ok(slot) => {
+                            let index = slots.iter().position(|&x| x == slot).unwrap();
+                            placements_per_slot[index] += 1;
+                            let mut by_placement = SquareBoard4::new();
+                            let mut by_slot = SquareBoard4::new();
+                            let player = TwoPlayerIndices::White;
+                            assert_eq!(
+                                by_placement.place_wall(
+                                    player,
+                                    first_field,
+                                    direction,
+                                    wall_direction
+                                ),
+                                by_slot.place_wall_at(player, slot)
+                            );
+                            assert_eq!(snapshot(&by_placement), snapshot(&by_slot));
+                            assert!(by_slot.is_wall_at_unsafe(slot));
+                            let (field, direction, wall_direction) = slot.to_placement();
+                            assert_eq!(
+                                SquareBoard4::get_wall_slot(field, direction, wall_direction),
+                                Ok(slot)
+                            );
+                        }
+                        Err(error) => assert_eq!(
+                            error,
+                            WallPlacmentError::BoardBoundary {
+                                field: first_field,
+                                direction,
+                            }
+                        ),
+                    }
+                }
+            }
+        }
+    }
+    assert!(placements_per_slot.iter().all(|&count| count == 4));
+}
+
+#[test]
+fn slot_relations_agree_with_the_board() {
+    let rules = Rules { wall_count: 2 };
+    let player = TwoPlayerIndices::White;
+    for first in WallSlot::all(4) {
+        for second in WallSlot::all(4) {
+            assert_eq!(first.conflicts_with(&second), second.conflicts_with(&first));
+            let mut board = SquareBoard4::with_rules(rules);
+            assert_eq!(board.place_wall_at(player, first), Ok(()));
+            match board.place_wall_at(player, second) {
+                Ok(()) | Err(WallPlacmentError::PlayerBlocked { .. }) => {
+                    assert!(!first.conflicts_with(&second), "{} {}", first, second)
+                }
+                Err(WallPlacmentError::WallAlreadyPlaced { .. }) => {
+                    assert!(first.overlaps(&second), "{} {}", first, second)
+                }
+                Err(WallPlacmentError::WallsAlreadyCrossing { .. }) => {
+                    assert!(first.crosses(&second), "{} {}", first, second)
+                }
+                Err(error) => panic!("{}", error),
+            }
+        }
+    }
+    let off_board = WallSlot::new(field(3, 1), WallOrientation::Vertical);
+    assert!(!off_board.is_on_board(4));
+    assert_eq!(
+        SquareBoard4::new().place_wall_at(player, off_board),
+        Err(WallPlacmentError::BoardBoundary {
+            field: field(3, 1),
+            direction: DirectionsSquare::Right,
+        })
+    );
+}
+
+#[test]
+fn walls_are_found_only_at_their_slot() {
+    // the end of (1,3) horizontal meets the crossing of (2,3) vertical where (2,3) horizontal
+    // would be
+    let mut board = SquareBoard5::new();
+    let player = TwoPlayerIndices::White;
+    let first = WallSlot::new(field(1, 3), WallOrientation::Horizontal);
+    let second = WallSlot::new(field(2, 3), WallOrientation::Vertical);
+    assert_eq!(board.place_wall_at(player, first), Ok(()));
+    assert_eq!(board.place_wall_at(player, second), Ok(()));
+    let placed = WallSlot::all(5)
+        .filter(|&slot| board.is_wall_at_unsafe(slot))
+        .collect::<Vec<_>>();
+    assert_eq!(placed, vec![first, second]);
+}
+
+#[test]
+fn wall_table_agrees_with_slot_relations() {
+    for &size in &[3, 4, 5, 9] {
+        let table = WallSlotTable::new(size);
+        assert_eq!(table.len(), WallSlot::all(size).len());
+        for (index, entry) in table.get_entries().iter().enumerate() {
+            assert_eq!(table.get_index(entry.slot), Some(index));
+            for (other_index, other) in table.get_entries().iter().enumerate() {
+                assert_eq!(
+                    entry.conflicting.contains(&other_index),
+                    index != other_index && entry.slot.conflicts_with(&other.slot)
+                );
+            }
+            assert_eq!(entry.segments, entry.slot.get_segments());
+            for &(field, direction) in &entry.cut_steps {
+                let wall_side = match direction {
+                    DirectionsSquare::Up | DirectionsSquare::Right => field,
+                    DirectionsSquare::Down => FieldIndexSquare {
+                        column: field.column,
+                        row: field.row - 1,
+                    },
+                    DirectionsSquare::Left => FieldIndexSquare {
+                        column: field.column - 1,
+                        row: field.row,
+                    },
+                };
+                assert!(entry.segments.contains(&(
+                    wall_side,
+                    direction == DirectionsSquare::Left || direction == DirectionsSquare::Right
+                )));
+            }
+        }
+        let off_board = WallSlot::new(field(size - 1, 0), WallOrientation::Vertical);
+        assert_eq!(table.get_index(off_board), None);
+    }
+}
+
+#[test]
+fn boards_of_one_size_share_their_wall_table() {
+    let first = SquareBoard5::new();
+    let second = SquareBoard5::with_rules(Rules::default());
+    assert!(std::ptr::eq(first.get_wall_table(), second.get_wall_table()));
+    assert!(std::ptr::eq(first.get_wall_table(), WallSlotTable::get_shared(5)));
+    assert!(!std::ptr::eq(first.get_wall_table(), SquareBoard3::new().get_wall_table()));
+    assert_eq!(WallSlotTable::get_shared(9).get_board_size(), 9);
+}
+
+#[test]
+fn moves_are_read_back_from_their_display() {
+    let board = SquareBoard5::new();
+    for next_move in board.get_legal_moves(TwoPlayerIndices::White) {
+        assert_eq!(next_move.to_string().parse::<Move>(), Ok(next_move));
+    }
+    assert_eq!(
+        " wall (1, 2)  vertical ".parse::<Move>().ok(),
+        Some(Move::Wall(WallSlot {
+            anchor: FieldIndexSquare { column: 1, row: 2 },
+            orientation: WallOrientation::Vertical,
+        }))
+    );
+    for text in &["", "step", "step north", "wall (1,2)", "wall (1) vertical", "jump up"] {
+        assert!(text.parse::<Move>().is_err(), "{}", text);
+    }
+}
+
+#[test]
+fn wall_without_remaining_walls_is_rejected() {
+    let mut board = SquareBoard3::with_rules(Rules { wall_count: 0 });
+    assert_eq!(
+        board.place_wall(
+            TwoPlayerIndices::Black,
+            field(0, 0),
+            DirectionsSquare::Up,
+            WallDirections::Right
+        ),
+        Err(WallPlacmentError::NoMoreWalls {
+            player: TwoPlayerIndices::Black,
+        })
+    );
+}
+
+#[test]
+fn wall_blocking_a_player_is_rejected_and_taken_back() {
+    let mut board = SquareBoard3::new();
+    let player = TwoPlayerIndices::Black;
+    assert_eq!(
+        board.place_wall(
+            player,
+            field(0, 1),
+            DirectionsSquare::Up,
+            WallDirections::Right
+        ),
+        Ok(())
+    );
+    let before = snapshot(&board);
+    assert_eq!(
+        board.place_wall(
+            player,
+            field(1, 1),
+            DirectionsSquare::Right,
+            WallDirections::Left
+        ),
+        Err(WallPlacmentError::PlayerBlocked {
+            player: TwoPlayerIndices::White,
+        })
+    );
+    assert_eq!(before, snapshot(&board));
+    check_invariants(&board, 1);
+}
+
+#[test]
+fn unconnected_wall_pieces_are_rejected() {
+    let mut game: Game<SquareBoard3> = Game::new();
+    let not_connected = Err(wall_error(WallPlacmentError::NotConnected));
+    assert_eq!(
+        game.place_wall_by_pieces((0, 0, true), (0, 0, false)),
+        not_connected
+    );
+    assert_eq!(
+        game.place_wall_by_pieces((0, 0, true), (1, 0, true)),
+        not_connected
+    );
+    assert_eq!(
+        game.place_wall_by_pieces((0, 0, false), (0, 1, false)),
+        not_connected
+    );
+    assert_eq!(
+        game.place_wall_by_pieces((0, 0, true), (0, 1, true)),
+        Ok(())
+    );
+}
+
+#[test]
+fn moves_through_walls_and_over_the_boundary_are_rejected() {
+    let mut board = SquareBoard3::new();
+    let player = TwoPlayerIndices::White;
+    assert_eq!(board.get_current_field(player), field(1, 2));
+    assert_eq!(
+        board.move_player(player, DirectionsSquare::Up),
+        Err(MoveError::BoardBoundary {
+            field: field(1, 2),
+            direction: DirectionsSquare::Up,
+        })
+    );
+    assert_eq!(
+        board.place_wall(
+            player,
+            field(1, 1),
+            DirectionsSquare::Up,
+            WallDirections::Left
+        ),
+        Ok(())
+    );
+    assert_eq!(
+        board.move_player(player, DirectionsSquare::Down),
+        Err(MoveError::Wall {
+            field: field(1, 2),
+            direction: DirectionsSquare::Down,
+        })
+    );
+}
+
+#[test]
+fn game_ends_when_goal_is_reached_and_undo_restores_the_position() {
+    let mut game: Game<SquareBoard3> = Game::new();
+    let start = snapshot(&game.board);
+    assert_eq!(game.play_move(Move::Step(DirectionsSquare::Down)), Ok(()));
+    let after_first_move = snapshot(&game.board);
+    assert_eq!(game.play_move(Move::Step(DirectionsSquare::Left)), Ok(()));
+    assert_eq!(game.play_move(Move::Step(DirectionsSquare::Down)), Ok(()));
+    assert_eq!(game.winner, Some(TwoPlayerIndices::White));
+    assert_eq!(
+        game.play_move(Move::Step(DirectionsSquare::Up)),
+        Err(GameError::GameOver {
+            winner: TwoPlayerIndices::White,
+        })
+    );
+    assert_eq!(game.undo(), Ok(()));
+    assert_eq!(game.undo(), Ok(()));
+    assert_eq!(snapshot(&game.board), after_first_move);
+    assert_eq!(game.get_current_player(), TwoPlayerIndices::Black);
+    assert_eq!(game.undo(), Ok(()));
+    assert_eq!(snapshot(&game.board), start);
+    assert_eq!(game.undo(), Err(GameError::NothingToUndo));
+    assert!(game.get_logbook().get_played_moves().is_empty());
+}
+
+#[test]
+fn undo_restores_the_position_the_game_started_from() {
+    let position = "3 black (1,1) (0,0) 1 1".parse::<Position>().unwrap();
+    let mut game = Game::<SquareBoard3>::with_position(&position, Rules::default()).unwrap();
+    assert_eq!(game.play_move(Move::Step(DirectionsSquare::Right)), Ok(()));
+    assert_eq!(game.play_move(Move::Step(DirectionsSquare::Left)), Ok(()));
+    assert_eq!(game.undo(), Ok(()));
+    assert_eq!(game.undo(), Ok(()));
+    assert_eq!(game.get_current_player(), TwoPlayerIndices::Black);
+    assert_eq!(game.board.get_position(TwoPlayerIndices::Black), position);
+    assert_eq!(game.undo(), Err(GameError::NothingToUndo));
+}
+
+#[test]
+fn logbooks_are_written_as_valid_json() {
+    assert_eq!(string_to_json("a \"b\"\\\n\u{1}"), "\"a \\\"b\\\"\\\\\\n\\u0001\"");
+    let mut logbook = Logbook::new();
+    let entry = LogEntry::Undo {
+        player: TwoPlayerIndices::White,
+    };
+    for &seconds in &[f64::NAN, f64::INFINITY] {
+        logbook.push(Timestamp { ply: 1, seconds }, entry.clone());
+    }
+    let json = logbook.to_json();
+    assert_eq!(json.matches("\"seconds\":null,\"type\":\"undo\"").count(), 2);
+    assert!(!json.contains("NaN") && !json.contains("inf"));
+    let mut game: Game<SquareBoard3> = Game::new();
+    let error = game.play_move(Move::Step(DirectionsSquare::Up)).unwrap_err();
+    assert!(game.get_logbook().to_json().contains(&string_to_json(&error.to_string())));
+}
+
+#[test]
+fn game_errors_are_shown_once_in_error_chains() {
+    let mut game: Game<SquareBoard3> = Game::new();
+    let error = game.play_move(Move::Step(DirectionsSquare::Up)).unwrap_err();
+    match error {
+        GameError::Move(ref move_error) => assert_eq!(error.to_string(), move_error.to_string()),
+        ref error => panic!("unexpected {:?}", error),
+    }
+    assert!(error.source().is_none());
+}
@@ -0,0 +1,197 @@
+extern crate varidor;
+use varidor::server::websocket::*;
+use varidor::server::*;
+use varidor::*;
+
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::thread;
+use std::time::Duration;
+
+fn start_server() -> SocketAddr {
+    let server = GameServer::<SquareBoard3>::bind("127.0.0.1:0", Rules::default()).unwrap();
+    let address = server.get_address().unwrap();
+    thread::spawn(move || server.run());
+    address
+}
+
+struct Client {
+    socket: WebSocket,
+}
+
+impl Client {
+    fn connect(address: SocketAddr) -> Self {
+        Client {
+            socket: WebSocket::connect(address, "/").unwrap(),
+        }
+    }
+    fn send(&mut self, text: &str) {
+        self.socket.send(text).unwrap();
+    }
+    fn receive(&mut self) -> ServerMessage {
+        self.socket.receive().unwrap().unwrap().parse().unwrap()
+    }
+    fn join(&mut self, game: &str, role: &str) -> Position {
+        self.send(&format!("join {} {}", game, role));
+        match self.receive() {
+            ServerMessage::Joined { .. } => {}
+            message => panic!("unexpected {}", message),
+        }
+        match self.receive() {
+            ServerMessage::State(position) => position,
+            message => panic!("unexpected {}", message),
+        }
+    }
+}
+
+#[test]
+fn accept_keys_follow_the_rfc() {
+    assert_eq!(
+        get_accept_key("dGhlIHNhbXBsZSBub25jZQ=="),
+        "s3pPLMBiTxaQ9kYGzzhZRbK+xOo="
+    );
+}
+
+#[test]
+fn messages_are_read_back_from_their_text() {
+    let position = SquareBoard5::new().get_position(TwoPlayerIndices::Black);
+    let client_messages = vec![
+        ClientMessage::Join {
+            game: "lobby".to_string(),
+            role: Role::Player(TwoPlayerIndices::Black),
+        },
+        ClientMessage::Join {
+            game: "lobby".to_string(),
+            role: Role::Spectator,
+        },
+        ClientMessage::Move("wall (1,2) vertical".parse().unwrap()),
+    ];
+    for message in client_messages {
+        assert_eq!(message.to_string().parse(), Ok(message.clone()));
+    }
+    let server_messages = vec![
+        ServerMessage::Joined {
+            game: "lobby".to_string(),
+            role: Role::Player(TwoPlayerIndices::White),
+        },
+        ServerMessage::State(position),
+        ServerMessage::Played {
+            player: TwoPlayerIndices::White,
+            next_move: "step down".parse().unwrap(),
+        },
+        ServerMessage::Rejected("wall already placed".to_string()),
+        ServerMessage::GameOver(TwoPlayerIndices::Black),
+        ServerMessage::Error("join a game first".to_string()),
+    ];
+    for message in server_messages {
+        assert_eq!(message.to_string().parse(), Ok(message.clone()));
+    }
+    assert!("join lobby red".parse::<ClientMessage>().is_err());
+    assert!("move step".parse::<ClientMessage>().is_err());
+}
+
+#[test]
+fn players_and_spectators_follow_the_game_on_the_server() {
+    let address = start_server();
+    let mut white = Client::connect(address);
+    let mut black = Client::connect(address);
+    let mut spectator = Client::connect(address);
+    white.send("move step down");
+    assert_eq!(
+        white.receive(),
+        ServerMessage::Error("join a game first".to_string())
+    );
+    let start = white.join("first", "white");
+    assert_eq!(start, SquareBoard3::new().get_position(TwoPlayerIndices::White));
+    black.send("join first white");
+    assert_eq!(black.receive(), ServerMessage::Error("white is taken".to_string()));
+    black.join("first", "black");
+    spectator.join("first", "spectator");
+
+    black.send("move step up");
+    assert_eq!(
+        black.receive(),
+        ServerMessage::Error("it is not the turn of black".to_string())
+    );
+    spectator.send("move step down");
+    assert_eq!(
+        spectator.receive(),
+        ServerMessage::Error("spectators cannot move".to_string())
+    );
+    // the board of the server rejects the move and only the mover hears of it
+    white.send("move step up");
+    match white.receive() {
+        ServerMessage::Rejected(reason) => {
+            let error: GameError = GameError::Move(MoveError::BoardBoundary {
+                field: FieldIndexSquare { column: 1, row: 2 },
+                direction: DirectionsSquare::Up,
+            });
+            assert_eq!(reason, error.to_string());
+        }
+        message => panic!("unexpected {}", message),
+    }
+    let mut board = SquareBoard3::new();
+    let mut player = TwoPlayerIndices::White;
+    let moves = ["step right", "step up", "step down", "step left", "step down"];
+    for (index, text) in moves.iter().enumerate() {
+        if index % 2 == 0 {
+            white.send(&format!("move {}", text));
+        } else {
+            black.send(&format!("move {}", text));
+        }
+        let next_move = text.parse().unwrap();
+        board.play_move(player, next_move).unwrap();
+        let next_player = player.next_player();
+        for client in &mut [&mut black, &mut spectator] {
+            assert_eq!(client.receive(), ServerMessage::Played { player, next_move });
+            let state = ServerMessage::State(board.get_position(next_player));
+            assert_eq!(client.receive(), state);
+        }
+        player = next_player;
+    }
+    for client in &mut [&mut black, &mut spectator] {
+        assert_eq!(client.receive(), ServerMessage::GameOver(TwoPlayerIndices::White));
+    }
+    // a late spectator sees the final state, a new game starts from the beginning
+    let mut late = Client::connect(address);
+    assert_eq!(late.join("first", "spectator"), board.get_position(player));
+    assert_eq!(late.receive(), ServerMessage::GameOver(TwoPlayerIndices::White));
+    let mut other = Client::connect(address);
+    assert_eq!(other.join("second", "black"), start);
+}
+
+#[test]
+fn seats_are_free_again_after_disconnecting() {
+    let address = start_server();
+    let mut first = Client::connect(address);
+    first.join("game", "white");
+    first.socket.close().unwrap();
+    assert_eq!(first.socket.receive().unwrap(), None);
+    drop(first);
+    // the server frees the seat when the connection ends, which may take a moment
+    let mut second = Client::connect(address);
+    for _ in 0..100 {
+        second.send("join game white");
+        match second.receive() {
+            ServerMessage::Joined { .. } => return,
+            ServerMessage::Error(_) => thread::sleep(std::time::Duration::from_millis(10)),
+            message => panic!("unexpected {}", message),
+        }
+    }
+    panic!("seat was not freed");
+}
+
+#[test]
+fn endless_handshake_headers_are_refused() {
+    let address = start_server();
+    let mut stream = TcpStream::connect(address).unwrap();
+    stream.set_read_timeout(Some(Duration::from_secs(10))).unwrap();
+    let header = format!("GET / HTTP/1.1\r\n{}", "X-Padding: 0123456789\r\n".repeat(2000));
+    // the server may close the connection before all of it is written
+    let _ = stream.write_all(header.as_bytes());
+    let mut response = vec![];
+    match stream.read_to_end(&mut response) {
+        Ok(_) => assert!(response.is_empty()),
+        Err(error) => assert_eq!(error.kind(), io::ErrorKind::ConnectionReset),
+    }
+}
@@ -0,0 +1,86 @@
+extern crate varidor;
+use varidor::analysis::*;
+use varidor::*;
+
+const TEXT: &str = "[Board \"5\"]\n[Walls \"5\"]\n[Result \"none\"]\n\
+                    step down\n{the usual start}\n(\nwall (1,2) vertical\n{sharp}\nstep up\n(\n\
+                    step left\n)\n)\nstep up\nstep left\n";
+
+fn moves(texts: &[&str]) -> Vec<Move> {
+    texts.iter().map(|x| x.parse().unwrap()).collect()
+}
+
+#[test]
+fn comments_and_variations_are_read_back_from_records() {
+    let record = TEXT.parse::<GameRecord>().unwrap();
+    assert_eq!(record.moves, moves(&["step down", "step up", "step left"]));
+    let annotation = &record.annotations[&0];
+    assert_eq!(annotation.comment.as_deref(), Some("the usual start"));
+    assert_eq!(annotation.variations.len(), 1);
+    let variation = &annotation.variations[0];
+    assert_eq!(variation.moves, moves(&["wall (1,2) vertical", "step up"]));
+    assert_eq!(variation.annotations[&0].comment.as_deref(), Some("sharp"));
+    assert_eq!(variation.annotations[&1].variations[0].moves, moves(&["step left"]));
+    assert_eq!(record.to_string(), TEXT);
+    assert_eq!(record.replay::<SquareBoard5>().unwrap().len(), 4);
+
+    for text in &["(\nstep down\n)\nstep down", "step down\n(\nstep up", "{comment}\nstep down"] {
+        let record = format!("[Board \"5\"]\n[Walls \"5\"]\n[Result \"none\"]\n{}\n", text);
+        assert!(record.parse::<GameRecord>().is_err());
+    }
+}
+
+#[test]
+fn moves_played_in_the_analysis_add_variations() {
+    let mut analysis = AnalysisBoard::<SquareBoard5>::new(Rules::default());
+    for text in &["step down", "step up", "step left"] {
+        analysis.play_move(text.parse().unwrap()).unwrap();
+    }
+    assert_eq!(analysis.get_ply(), 3);
+    analysis.goto_start();
+    assert!(analysis.forward());
+    analysis.play_move("wall (1,2) vertical".parse().unwrap()).unwrap();
+    analysis.set_comment(Some("sharp".to_string()));
+    assert!(!analysis.is_main_line());
+    assert!(analysis.play_move("wall (1,2) vertical".parse().unwrap()).is_err());
+    assert!(analysis.back());
+    assert_eq!(analysis.get_continuations(), moves(&["step up", "wall (1,2) vertical"]));
+    // known moves are followed instead of being added again
+    analysis.play_move("step up".parse().unwrap()).unwrap();
+    assert!(analysis.is_main_line());
+    analysis.goto_end();
+    assert_eq!(analysis.get_line(), moves(&["step down", "step up", "step left"]));
+    assert_eq!(analysis.get_current_player(), TwoPlayerIndices::Black);
+    assert!(!analysis.forward());
+
+    let record = analysis.to_record();
+    assert_eq!(record.moves.len(), 3);
+    let variation = &record.annotations[&1].variations[0];
+    assert_eq!(variation.moves, moves(&["wall (1,2) vertical"]));
+    assert_eq!(variation.annotations[&0].comment.as_deref(), Some("sharp"));
+}
+
+#[test]
+fn variations_are_promoted_and_deleted() {
+    let record = TEXT.parse::<GameRecord>().unwrap();
+    let mut analysis = AnalysisBoard::<SquareBoard5>::from_record(&record).unwrap();
+    assert_eq!(analysis.get_ply(), 0);
+    assert_eq!(analysis.to_record(), record);
+    for text in &["wall (1,2) vertical", "step left"] {
+        analysis.play_move(text.parse().unwrap()).unwrap();
+    }
+    analysis.promote_variation();
+    assert!(analysis.is_main_line());
+    let promoted = analysis.to_record();
+    assert_eq!(promoted.moves, moves(&["wall (1,2) vertical", "step left"]));
+    assert_eq!(promoted.annotations[&0].variations[0].moves[0], moves(&["step down"])[0]);
+    let back = AnalysisBoard::<SquareBoard5>::from_record(&promoted).unwrap();
+    assert_eq!(back.to_record(), promoted);
+
+    assert!(analysis.delete_variation());
+    assert_eq!(analysis.get_line(), moves(&["wall (1,2) vertical"]));
+    assert_eq!(analysis.get_continuations(), moves(&["step up"]));
+    analysis.goto_start();
+    assert!(!analysis.delete_variation());
+    assert!(AnalysisBoard::<SquareBoard3>::from_record(&record).is_err());
+}
@@ -0,0 +1,150 @@
+extern crate varidor;
+use varidor::ai::*;
+use varidor::tablebase::*;
+use varidor::*;
+
+// the outcome follows from the outcomes after each move
+fn expected_result(
+    tablebase: &Tablebase,
+    board: &SquareBoard3,
+    player: TwoPlayerIndices,
+) -> TablebaseResult {
+    if board.get_winner().is_some() {
+        return TablebaseResult::Loss { plies: 0 };
+    }
+    let children = board
+        .get_successors(player)
+        .into_iter()
+        .map(|(_, next)| tablebase.probe(&next, player.next_player()).unwrap())
+        .collect::<Vec<_>>();
+    let fastest_win = children
+        .iter()
+        .filter_map(|&result| match result {
+            TablebaseResult::Loss { plies } => Some(plies + 1),
+            _ => None,
+        })
+        .min();
+    let slowest_loss = children
+        .iter()
+        .map(|&result| match result {
+            TablebaseResult::Win { plies } => Some(plies + 1),
+            _ => None,
+        })
+        .try_fold(0, |maximum, plies| plies.map(|plies| std::cmp::max(maximum, plies)));
+    match (fastest_win, slowest_loss) {
+        (Some(plies), _) => TablebaseResult::Win { plies },
+        (None, Some(plies)) => TablebaseResult::Loss { plies },
+        (None, None) => TablebaseResult::Draw,
+    }
+}
+
+// every position reachable within `depth` plies
+fn visit<F>(board: &SquareBoard3, player: TwoPlayerIndices, depth: usize, check: &mut F)
+where
+    F: FnMut(&SquareBoard3, TwoPlayerIndices),
+{
+    check(board, player);
+    if depth == 0 || board.get_winner().is_some() {
+        return;
+    }
+    for (_, next) in board.get_successors(player) {
+        visit(&next, player.next_player(), depth - 1, check);
+    }
+}
+
+#[test]
+fn tablebase_results_are_consistent_with_the_moves() {
+    let rules = Rules { wall_count: 2 };
+    let tablebase = Tablebase::generate::<SquareBoard3>(rules).unwrap();
+    assert_eq!(tablebase.len(), 3500);
+    let board = SquareBoard3::with_rules(rules);
+    visit(&board, TwoPlayerIndices::White, 4, &mut |board, player| {
+        let result = tablebase.probe(board, player).unwrap();
+        assert_eq!(result, expected_result(&tablebase, board, player));
+        if let Some(race) = board.solve_race(player) {
+            let race_result = if race.winner == player {
+                TablebaseResult::Win { plies: race.plies }
+            } else {
+                TablebaseResult::Loss { plies: race.plies }
+            };
+            assert_eq!(result, race_result);
+        }
+    });
+}
+
+#[test]
+fn first_player_wins_on_the_small_board() {
+    let rules = Rules::default();
+    let tablebase = Tablebase::generate::<SquareBoard3>(rules).unwrap();
+    let board = SquareBoard3::with_rules(rules);
+    assert_eq!(
+        tablebase.probe(&board, TwoPlayerIndices::White),
+        Some(TablebaseResult::Win { plies: 5 })
+    );
+    let (next_move, result) = tablebase
+        .get_best_move(&board, TwoPlayerIndices::White)
+        .unwrap();
+    assert_eq!(result, TablebaseResult::Win { plies: 5 });
+    let mut next = board.clone();
+    next.play_move(TwoPlayerIndices::White, next_move).unwrap();
+    assert_eq!(
+        tablebase.probe(&next, TwoPlayerIndices::Black),
+        Some(TablebaseResult::Loss { plies: 4 })
+    );
+    assert_eq!(
+        tablebase.probe(&SquareBoard3::new(), TwoPlayerIndices::White),
+        Some(TablebaseResult::Win { plies: 5 })
+    );
+    let other_rules = SquareBoard3::with_rules(Rules { wall_count: 1 });
+    assert_eq!(tablebase.probe(&other_rules, TwoPlayerIndices::White), None);
+}
+
+#[test]
+fn tablebase_is_read_back_from_its_file_format() {
+    let tablebase = Tablebase::generate::<SquareBoard3>(Rules { wall_count: 1 }).unwrap();
+    let mut bytes = vec![];
+    tablebase.write_to(&mut bytes).unwrap();
+    assert!(bytes.len() < 3 * tablebase.len());
+    assert_eq!(Tablebase::read_from(&mut &bytes[..]).unwrap(), tablebase);
+    assert!(Tablebase::read_from(&mut &bytes[..bytes.len() - 1]).is_err());
+    assert!(Tablebase::read_from(&mut &b"not a tablebase"[..]).is_err());
+    // a key difference with more than 64 bits
+    let mut overflow = bytes[..6].to_vec();
+    overflow.extend_from_slice(&1u64.to_le_bytes());
+    overflow.extend_from_slice(&[0xff; 9]);
+    overflow.extend_from_slice(&[0x03, 0]);
+    assert!(Tablebase::read_from(&mut &overflow[..]).is_err());
+    overflow[23] = 0x01;
+    assert_eq!(Tablebase::read_from(&mut &overflow[..]).unwrap().len(), 1);
+    // a length far beyond the entries and a repeated key
+    let mut header = bytes[..6].to_vec();
+    header.extend_from_slice(&(1u64 << 60).to_le_bytes());
+    assert!(Tablebase::read_from(&mut &header[..]).is_err());
+    let mut repeated = bytes[..6].to_vec();
+    repeated.extend_from_slice(&2u64.to_le_bytes());
+    repeated.extend_from_slice(&[5, 1, 0, 1]);
+    assert!(Tablebase::read_from(&mut &repeated[..]).is_err());
+    repeated[16] = 1;
+    assert_eq!(Tablebase::read_from(&mut &repeated[..]).unwrap().len(), 2);
+    // wall counts above 255 do not fit the header
+    let many_walls = Tablebase::generate::<SquareBoard3>(Rules { wall_count: 256 }).unwrap();
+    assert!(many_walls.write_to(&mut vec![]).is_err());
+}
+
+#[test]
+fn search_uses_the_tablebase_scores() {
+    let rules = Rules::default();
+    let tablebase = Tablebase::generate::<SquareBoard3>(rules).unwrap();
+    let board = SquareBoard3::with_rules(rules);
+    let result = search_with(
+        &board,
+        TwoPlayerIndices::White,
+        &SearchSettings {
+            depth: 1,
+            tablebase: Some(&tablebase),
+            ..SearchSettings::default()
+        },
+    );
+    assert_eq!(result.score, WIN_SCORE - 5);
+    assert_eq!(result.nodes, 1 + board.get_legal_moves(TwoPlayerIndices::White).len() as u64);
+}
@@ -0,0 +1,103 @@
+extern crate varidor;
+use varidor::record::*;
+use varidor::tournament::*;
+use varidor::*;
+
+fn result(winners: &[Option<TwoPlayerIndices>]) -> TournamentResult {
+    let records = winners
+        .iter()
+        .map(|&winner| {
+            let mut record = GameRecord::new(3, Rules::default());
+            record.winner = winner;
+            record
+        })
+        .collect();
+    TournamentResult::new(["a".to_string(), "b".to_string()], records)
+}
+
+#[test]
+fn results_count_from_the_view_of_the_first_engine() {
+    let white = Some(TwoPlayerIndices::White);
+    let black = Some(TwoPlayerIndices::Black);
+    // the first engine has White in the even games
+    let result = result(&[white, white, black, black, None, white]);
+    assert_eq!((result.wins, result.draws, result.losses), (2, 1, 3));
+    assert_eq!(result.get_games(), 6);
+    assert!((result.get_score() - 2.5 / 6.).abs() < 1e-12);
+    assert!(result.get_elo_difference() < 0.);
+    let (lower, upper) = result.get_elo_interval();
+    assert!(lower < result.get_elo_difference() && result.get_elo_difference() < upper);
+}
+
+#[test]
+fn elo_follows_the_logistic_curve() {
+    let white = Some(TwoPlayerIndices::White);
+    let black = Some(TwoPlayerIndices::Black);
+    let even = result(&[white, white, None, None]);
+    assert_eq!(even.get_elo_difference(), 0.);
+    // three wins out of four are 400 * log10(3) points
+    let ahead = result(&[white, black, white, black]);
+    assert_eq!(ahead.get_elo_difference(), f64::INFINITY);
+    let three = result(&[white, black, white, white]);
+    assert!((three.get_elo_difference() - 400. * 3f64.log10()).abs() < 1e-9);
+    let (lower, upper) = three.get_elo_interval();
+    assert!(lower < 0. && upper == f64::INFINITY);
+    assert!(three.to_string().starts_with("a - b: +3 =0 -1, Elo +191 ("));
+}
+
+#[test]
+fn tournaments_alternate_colours_and_do_not_depend_on_threads() {
+    let engines = [Engine::new("deep", 2), Engine::new("shallow", 1)];
+    let tournament = Tournament {
+        games: 6,
+        ..Tournament::default()
+    };
+    let result = tournament.run::<SquareBoard3>(&engines);
+    assert_eq!(result.get_games(), 6);
+    for (index, record) in result.records.iter().enumerate() {
+        let (white, black) = if index % 2 == 0 {
+            ("deep", "shallow")
+        } else {
+            ("shallow", "deep")
+        };
+        assert_eq!(record.get_tag("White"), Some(white));
+        assert_eq!(record.get_tag("Black"), Some(black));
+        assert_eq!(record.get_tag("Round"), Some((index + 1).to_string().as_str()));
+        let boards = record.replay::<SquareBoard3>().unwrap();
+        assert_eq!(boards.last().unwrap().get_winner(), record.winner);
+    }
+    let parallel = Tournament {
+        threads: 4,
+        ..tournament
+    };
+    assert_eq!(parallel.run::<SquareBoard3>(&engines), result);
+    let directory = std::env::temp_dir().join("varidor_tournament_records");
+    let _ = std::fs::remove_dir_all(&directory);
+    result.save_records(&directory).unwrap();
+    assert_eq!(read_records_from_directory(&directory).unwrap(), result.records);
+    std::fs::remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn tournaments_are_played_on_every_board_size() {
+    let engines = [Engine::new("a", 1), Engine::new("b", 1)];
+    let tournament = Tournament {
+        games: 2,
+        ..Tournament::default()
+    };
+    let result = tournament.run::<SquareBoard7>(&engines);
+    assert_eq!(result.get_games(), 2);
+    let path = std::env::temp_dir().join("varidor_tournament_archive_7.txt");
+    let _ = std::fs::remove_file(&path);
+    let mut archive = varidor::archive::Archive::open(&path).unwrap();
+    for record in &result.records {
+        assert_eq!(record.board_size, 7);
+        let boards = record.replay::<SquareBoard7>().unwrap();
+        assert_eq!(boards.last().unwrap().get_winner(), record.winner);
+        archive.add(record).unwrap();
+    }
+    assert_eq!(archive.len(), 2);
+    std::fs::remove_file(&path).unwrap();
+    let result = tournament.run::<SquareBoard11>(&engines);
+    assert!(result.records.iter().all(|x| x.board_size == 11));
+}